@@ -1,47 +1,80 @@
 use crate::api_core::common::{
-    FileIdentifier, FileRecord, FileSelection, FileServiceSelection, OptionalStringNumber,
+    FileIdentifier, FileRecord, FileSelection, FileServiceSelection, FileStream, FileUploadStream,
+    OptionalStringNumber, ServiceIdentifier,
 };
 use crate::api_core::endpoints::access_management::{
-    ApiVersion, ApiVersionResponse, GetServices, GetServicesResponse, SessionKey,
-    SessionKeyResponse, VerifyAccessKey, VerifyAccessKeyResponse,
+    ApiVersion, ApiVersionResponse, GetService, GetServiceResponse, GetServices,
+    GetServicesResponse, Permission, RequestNewPermissions, RequestNewPermissionsResponse,
+    SessionKey, SessionKeyResponse, VerifyAccessKey, VerifyAccessKeyResponse,
 };
 use crate::api_core::endpoints::adding_files::{
     AddFile, AddFileRequest, AddFileResponse, ArchiveFiles, ArchiveFilesRequest, DeleteFiles,
     DeleteFilesRequest, UnarchiveFiles, UnarchiveFilesRequest, UndeleteFiles, UndeleteFilesRequest,
 };
 use crate::api_core::endpoints::adding_notes::{
-    DeleteNotes, DeleteNotesRequest, SetNotes, SetNotesRequest,
+    DeleteNotes, DeleteNotesRequest, SetNotes, SetNotesRequest, SetNotesResponse,
 };
 use crate::api_core::endpoints::adding_tags::{
-    AddTags, AddTagsRequest, CleanTags, CleanTagsResponse,
+    AddTags, AddTagsRequest, CleanTags, CleanTagsResponse, GetSiblingsAndParents,
+    GetSiblingsAndParentsResponse, SearchTags, SearchTagsResponse, TagDisplayType,
 };
 use crate::api_core::endpoints::adding_urls::{
     AddUrl, AddUrlRequest, AddUrlResponse, AssociateUrl, AssociateUrlRequest, GetUrlFiles,
     GetUrlFilesResponse, GetUrlInfo, GetUrlInfoResponse,
 };
 use crate::api_core::endpoints::client_builder::ClientBuilder;
+use crate::api_core::endpoints::editing_ratings::{Rating, SetRating, SetRatingRequest};
 use crate::api_core::endpoints::managing_cookies_and_http_headers::{
     GetCookies, GetCookiesResponse, SetCookies, SetCookiesRequest, SetUserAgent,
     SetUserAgentRequest,
 };
+use crate::api_core::endpoints::managing_database::{
+    EmptyRequest, ForceCommit, GetClientOptions, GetClientOptionsResponse, LockOff, LockOn,
+    MrBones, MrBonesResponse,
+};
+use crate::api_core::endpoints::managing_file_relationships::{
+    FileRelationship, GetFileRelationships, GetFileRelationshipsResponse, GetPotentialPairs,
+    GetPotentialPairsResponse, GetPotentialsCount, GetPotentialsCountResponse, GetRandomPotentials,
+    GetRandomPotentialsResponse, PotentialsSearchOptions, RemovePotentials,
+    RemovePotentialsRequest, SetFileRelationships, SetFileRelationshipsRequest, SetKings,
+    SetKingsRequest,
+};
 use crate::api_core::endpoints::managing_pages::{
     AddFiles, AddFilesRequest, FocusPage, FocusPageRequest, GetPageInfo, GetPageInfoResponse,
     GetPages, GetPagesResponse,
 };
+use crate::api_core::endpoints::managing_services::{
+    CommitPending, ForgetPending, GetPendingCounts, GetPendingCountsResponse, PendingContentRequest,
+};
 use crate::api_core::endpoints::searching_and_fetching_files::{
-    FileMetadata, FileMetadataResponse, FileMetadataType, FileSearchOptions, GetFile,
-    SearchFileHashes, SearchFileHashesResponse, SearchFiles, SearchFilesResponse, SearchQueryEntry,
+    FileMetadata, FileMetadataInfoResponse, FileMetadataResponse, FileMetadataType,
+    FileMetadataWithOptions, FileSearchOptions, GetFile, GetFilePath, GetFilePathResponse,
+    GetThumbnail, GetThumbnailPath, GetThumbnailPathResponse, MetadataOptions, RenderFile,
+    RenderOptions, SearchFileHashes, SearchFileHashesResponse, SearchFiles, SearchFilesResponse,
+    SearchFilesWithHashes, SearchFilesWithHashesResponse, SearchQueryEntry,
 };
 use crate::api_core::endpoints::Endpoint;
-use crate::error::{Error, Result};
+use crate::api_core::retry::RetryPolicy;
+use crate::error::{Error, HydrusError, Result};
+use crate::utils::{
+    chunk_file_ids, format_range_header, parse_content_range_length, parse_content_range_start,
+    range_start,
+};
 use bytes::Buf;
-use reqwest::Response;
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_RANGE, RANGE};
+use reqwest::{Body, Request, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::io;
+use std::ops::RangeBounds;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Mutex as AsyncMutex;
 
 const ACCESS_KEY_HEADER: &str = "Hydrus-Client-API-Access-Key";
+const SESSION_KEY_HEADER: &str = "Hydrus-Client-API-Session-Key";
 const CONTENT_TYPE_HEADER: &str = "Content-Type";
 const ACCEPT_HEADER: &str = "Accept";
 
@@ -50,6 +83,13 @@ const CONTENT_TYPE_CBOR: &str = "application/cbor";
 #[cfg(feature = "json")]
 const CONTENT_TYPE_JSON: &str = "application/json";
 
+/// The status hydrus responds with when a session key has expired
+const STATUS_SESSION_EXPIRED: u16 = 419;
+
+/// The maximum length of the file id list in a metadata query.
+/// Hydrus rejects request lines that are longer than 16KiB.
+const MAX_FILE_IDS_QUERY_LENGTH: usize = 8192;
+
 #[derive(Clone)]
 /// A low level Client for the hydrus API. It provides basic abstraction
 /// over the REST api.
@@ -58,6 +98,13 @@ pub struct Client {
     pub(crate) inner: reqwest::Client,
     pub(crate) base_url: String,
     pub(crate) access_key: String,
+    /// The session key shared between clones of the client.
+    /// It's only set if the client authenticates with session keys.
+    pub(crate) session: Option<Arc<AsyncMutex<Option<String>>>>,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    /// The permissions of the access key shared between clones of the client.
+    /// It's only set if the client checks permissions before sending requests.
+    pub(crate) permissions: Option<Arc<AsyncMutex<Option<Vec<Permission>>>>>,
 }
 
 impl Client {
@@ -72,6 +119,9 @@ impl Client {
             inner: reqwest::Client::new(),
             access_key: access_key.as_ref().to_string(),
             base_url: url.as_ref().to_string(),
+            session: None,
+            retry_policy: None,
+            permissions: None,
         }
     }
     /// Returns the current API version. It's being incremented every time the API changes.
@@ -80,10 +130,42 @@ impl Client {
         self.get_and_parse::<ApiVersion, ()>(&()).await
     }
 
-    /// Creates a new session key
+    /// Creates a new session key. This request is always authenticated with the access key.
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn session_key(&self) -> Result<SessionKeyResponse> {
-        self.get_and_parse::<SessionKey, ()>(&()).await
+        let response = self
+            .build_get::<SessionKey, ()>(&())
+            .header(ACCESS_KEY_HEADER, &self.access_key)
+            .send()
+            .await?;
+        let response = Self::extract_error(response).await?;
+
+        Self::extract_content(response).await
+    }
+
+    /// Requests a new access key with the given permissions. Hydrus only accepts the request
+    /// while the dialog to add an access key from an API request is open.
+    /// This request is never authenticated.
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn request_new_permissions<S: ToString + Debug>(
+        &self,
+        name: S,
+        permissions: &[Permission],
+    ) -> Result<RequestNewPermissionsResponse> {
+        let permission_ids: Vec<u32> = permissions.iter().map(Permission::id).collect();
+        let response = self
+            .build_get::<RequestNewPermissions, [(&str, String)]>(&[
+                ("name", name.to_string()),
+                (
+                    "basic_permissions",
+                    Self::serialize_query_object(permission_ids)?,
+                ),
+            ])
+            .send()
+            .await?;
+        let response = Self::extract_error(response).await?;
+
+        Self::extract_content(response).await
     }
 
     /// Verifies if the access key is valid and returns some information about its permissions
@@ -98,6 +180,17 @@ impl Client {
         self.get_and_parse::<GetServices, ()>(&()).await
     }
 
+    /// Returns information about a single service
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_service(&self, service: ServiceIdentifier) -> Result<GetServiceResponse> {
+        let service_arg = match service {
+            ServiceIdentifier::Name(name) => ("service_name", name),
+            ServiceIdentifier::Key(key) => ("service_key", key),
+        };
+        self.get_and_parse::<GetService, [(&str, String)]>(&[service_arg])
+            .await
+    }
+
     /// Adds a file to hydrus
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn add_file<S: ToString + Debug>(&self, path: S) -> Result<AddFileResponse> {
@@ -109,7 +202,33 @@ impl Client {
     /// Adds a file from binary data to hydrus
     #[tracing::instrument(skip(self, data), level = "debug")]
     pub async fn add_binary_file(&self, data: Vec<u8>) -> Result<AddFileResponse> {
-        self.post_binary::<AddFile>(data).await
+        let length = data.len() as u64;
+        self.post_binary::<AddFile>(data.into(), Some(length)).await
+    }
+
+    /// Adds a file to hydrus by streaming its content instead of buffering it into memory.
+    /// IO errors of the stream abort the upload and are returned as [Error::Io]
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn add_binary_file_stream(&self, file: FileUploadStream) -> Result<AddFileResponse> {
+        let io_error = Arc::new(Mutex::new(None));
+        let stream_error = Arc::clone(&io_error);
+        let stream = file.inner.map_err(move |e| {
+            let forwarded = io::Error::new(e.kind(), e.to_string());
+            *stream_error.lock().unwrap() = Some(e);
+            forwarded
+        });
+        let result = self
+            .post_binary::<AddFile>(Body::wrap_stream(stream), file.length)
+            .await;
+
+        let io_error = io_error.lock().unwrap().take();
+
+        if let Some(e) = io_error {
+            tracing::error!("Failed to read file stream '{}'", e);
+            Err(Error::Io(e))
+        } else {
+            result
+        }
     }
 
     /// Moves files with matching hashes to the trash
@@ -188,6 +307,40 @@ impl Client {
         .await
     }
 
+    /// Searches for tags that start with the given text together with the number
+    /// of files they're assigned to
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn search_tags<S: ToString + Debug>(
+        &self,
+        search: S,
+        tag_service: ServiceIdentifier,
+        display_type: TagDisplayType,
+    ) -> Result<SearchTagsResponse> {
+        let service_arg = match tag_service {
+            ServiceIdentifier::Name(name) => ("tag_service_name", name),
+            ServiceIdentifier::Key(key) => ("tag_service_key", key),
+        };
+        self.get_and_parse::<SearchTags, [(&str, String)]>(&[
+            ("search", search.to_string()),
+            service_arg,
+            ("tag_display_type", display_type.as_str().to_string()),
+        ])
+        .await
+    }
+
+    /// Returns the siblings and parents of the given tags for every tag service
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_siblings_and_parents(
+        &self,
+        tags: Vec<String>,
+    ) -> Result<GetSiblingsAndParentsResponse> {
+        self.get_and_parse::<GetSiblingsAndParents, [(&str, String)]>(&[(
+            "tags",
+            Self::serialize_query_object(tags)?,
+        )])
+        .await
+    }
+
     /// Adds tags to files with the given hashes
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn add_tags(&self, request: AddTagsRequest) -> Result<()> {
@@ -203,7 +356,7 @@ impl Client {
         query: Vec<SearchQueryEntry>,
         options: FileSearchOptions,
     ) -> Result<SearchFilesResponse> {
-        let mut args = options.into_query_args();
+        let mut args = options.into_query_args()?;
         args.push(("tags", Self::serialize_query_object(query)?));
         self.get_and_parse::<SearchFiles, [(&str, String)]>(&args)
             .await
@@ -216,13 +369,28 @@ impl Client {
         query: Vec<SearchQueryEntry>,
         options: FileSearchOptions,
     ) -> Result<SearchFileHashesResponse> {
-        let mut args = options.into_query_args();
+        let mut args = options.into_query_args()?;
         args.push(("tags", Self::serialize_query_object(query)?));
         args.push(("return_hashes", Self::serialize_query_object(true)?));
         self.get_and_parse::<SearchFileHashes, [(&str, String)]>(&args)
             .await
     }
 
+    /// Searches for files and returns both their ids and hashes
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn search_files_with_hashes(
+        &self,
+        query: Vec<SearchQueryEntry>,
+        options: FileSearchOptions,
+    ) -> Result<SearchFilesWithHashesResponse> {
+        let mut args = options.into_query_args()?;
+        args.push(("tags", Self::serialize_query_object(query)?));
+        args.push(("return_file_ids", Self::serialize_query_object(true)?));
+        args.push(("return_hashes", Self::serialize_query_object(true)?));
+        self.get_and_parse::<SearchFilesWithHashes, [(&str, String)]>(&args)
+            .await
+    }
+
     /// Returns the metadata for a given list of file_ids or hashes
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn get_file_metadata<M: FileMetadataType>(
@@ -230,26 +398,49 @@ impl Client {
         file_ids: Vec<u64>,
         hashes: Vec<String>,
     ) -> Result<FileMetadataResponse<M>> {
-        let id_query = if file_ids.len() > 0 {
-            ("file_ids", Self::serialize_query_object(file_ids)?)
-        } else {
-            ("hashes", Self::serialize_query_object(hashes)?)
-        };
-        let query = [
-            id_query,
-            (
-                "only_return_identifiers",
-                Self::serialize_query_object(M::only_identifiers())?,
-            ),
-            (
-                "only_return_basic_information",
-                Self::serialize_query_object(M::only_basic_information())?,
-            ),
-        ];
+        let query = Self::metadata_query_args(file_ids, hashes, M::options())?;
         self.get_and_parse::<FileMetadata<M>, [(&str, String)]>(&query)
             .await
     }
 
+    /// Returns the metadata for a given list of file_ids or hashes with
+    /// the fields selected by the options
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_file_metadata_with_options(
+        &self,
+        file_ids: Vec<u64>,
+        hashes: Vec<String>,
+        options: MetadataOptions,
+    ) -> Result<FileMetadataInfoResponse> {
+        let query = Self::metadata_query_args(file_ids, hashes, options)?;
+        self.get_and_parse::<FileMetadataWithOptions, [(&str, String)]>(&query)
+            .await
+    }
+
+    /// Returns the metadata of many files as a stream. The metadata is requested in chunks
+    /// of at most `chunk_size` files with up to `concurrency` requests running at once.
+    /// Chunks are split further if their file ids would exceed the url length hydrus accepts.
+    /// The order of the files is kept.
+    pub fn get_file_metadata_chunked<M: FileMetadataType + 'static>(
+        &self,
+        file_ids: Vec<u64>,
+        chunk_size: usize,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<M::Response>> {
+        let client = self.clone();
+        let chunks = chunk_file_ids(file_ids, chunk_size.max(1), MAX_FILE_IDS_QUERY_LENGTH);
+        tracing::debug!("Fetching metadata in {} chunks", chunks.len());
+
+        stream::iter(chunks)
+            .map(move |chunk| {
+                let client = client.clone();
+                async move { client.get_file_metadata::<M>(chunk, vec![]).await }
+            })
+            .buffered(concurrency.max(1))
+            .map_ok(|response| stream::iter(response.metadata.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
     /// Returns the metadata for a single file identifier
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn get_file_metadata_by_identifier<M: FileMetadataType>(
@@ -270,26 +461,107 @@ impl Client {
     /// Returns the bytes of a file from hydrus
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn get_file(&self, id: FileIdentifier) -> Result<FileRecord> {
-        let response = match id {
-            FileIdentifier::ID(id) => {
-                self.get::<GetFile, [(&str, u64)]>(&[("file_id", id)])
-                    .await?
-            }
-            FileIdentifier::Hash(hash) => {
-                self.get::<GetFile, [(&str, String)]>(&[("hash", hash)])
-                    .await?
-            }
-        };
-        let mime_type = response
+        let response = self
+            .get_by_file_identifier::<GetFile>(id, HeaderMap::new())
+            .await?;
+
+        Self::extract_file_record(response).await
+    }
+
+    /// Returns the content of a file from hydrus as a stream of bytes
+    /// without buffering it into memory
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_file_stream(&self, id: FileIdentifier) -> Result<FileStream> {
+        let response = self
+            .get_by_file_identifier::<GetFile>(id, HeaderMap::new())
+            .await?;
+
+        Ok(Self::extract_file_stream(response))
+    }
+
+    /// Returns a byte range of a file from hydrus as a stream. This can be used
+    /// to resume partial downloads. Fails if hydrus doesn't respond with the
+    /// requested range.
+    #[tracing::instrument(skip(self, range), level = "debug")]
+    pub async fn get_file_stream_range<R: RangeBounds<u64>>(
+        &self,
+        id: FileIdentifier,
+        range: R,
+    ) -> Result<FileStream> {
+        let start = range_start(&range);
+        let mut headers = HeaderMap::new();
+        let range = HeaderValue::from_str(&format_range_header(range)?)
+            .map_err(|e| Error::BuildError(e.to_string()))?;
+        headers.insert(RANGE, range);
+        let response = self.get_by_file_identifier::<GetFile>(id, headers).await?;
+
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(Error::InvalidResponse(format!(
+                "Expected partial content but got status {}",
+                response.status()
+            )));
+        }
+        let content_start = response
             .headers()
-            .get("mime-type")
-            .cloned()
-            .map(|h| h.to_str().unwrap().to_string())
-            .unwrap_or("image/jpeg".into());
+            .get(CONTENT_RANGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(parse_content_range_start);
+        if content_start != start {
+            return Err(Error::InvalidResponse(format!(
+                "The content range starts at {:?} instead of the requested byte {:?}",
+                content_start, start
+            )));
+        }
 
-        let bytes = response.bytes().await?.to_vec();
+        Ok(Self::extract_file_stream(response))
+    }
+
+    /// Returns the thumbnail of a file from hydrus
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_thumbnail(&self, id: FileIdentifier) -> Result<FileRecord> {
+        let response = self
+            .get_by_file_identifier::<GetThumbnail>(id, HeaderMap::new())
+            .await?;
+
+        Self::extract_file_record(response).await
+    }
 
-        Ok(FileRecord { bytes, mime_type })
+    /// Returns a file rendered to a different format like png or webp
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn render_file(
+        &self,
+        id: FileIdentifier,
+        options: RenderOptions,
+    ) -> Result<FileRecord> {
+        let mut args = options.into_query_args();
+        match id {
+            FileIdentifier::ID(id) => args.push(("file_id", id.to_string())),
+            FileIdentifier::Hash(hash) => args.push(("hash", hash)),
+        }
+        let response = self.get::<RenderFile, [(&str, String)]>(&args).await?;
+
+        Self::extract_file_record(response).await
+    }
+
+    /// Returns the path of a file in the file storage of hydrus. The path can
+    /// only be read if hydrus runs on the same machine
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_file_path(&self, id: FileIdentifier) -> Result<GetFilePathResponse> {
+        let response = self
+            .get_by_file_identifier::<GetFilePath>(id, HeaderMap::new())
+            .await?;
+
+        Self::extract_content(response).await
+    }
+
+    /// Returns the path of the thumbnail of a file in the file storage of hydrus
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_thumbnail_path(&self, id: FileIdentifier) -> Result<GetThumbnailPathResponse> {
+        let response = self
+            .get_by_file_identifier::<GetThumbnailPath>(id, HeaderMap::new())
+            .await?;
+
+        Self::extract_content(response).await
     }
 
     /// Returns all files associated with the given url
@@ -348,12 +620,22 @@ impl Client {
         id: FileIdentifier,
         notes: HashMap<String, String>,
     ) -> Result<()> {
-        self.post::<SetNotes>(SetNotesRequest::new(id, notes))
+        self.set_notes_with_request(SetNotesRequest::new(id, notes))
             .await?;
 
         Ok(())
     }
 
+    /// Sets the notes of a file with the merge options of the request and
+    /// returns the notes that were actually set
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn set_notes_with_request(
+        &self,
+        request: SetNotesRequest,
+    ) -> Result<SetNotesResponse> {
+        self.post_and_parse::<SetNotes>(request).await
+    }
+
     /// Deletes the notes of a file
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn delete_notes(&self, id: FileIdentifier, note_names: Vec<String>) -> Result<()> {
@@ -363,6 +645,20 @@ impl Client {
         Ok(())
     }
 
+    /// Sets the rating of a file on the given rating service
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn set_rating<S: ToString + Debug>(
+        &self,
+        id: FileIdentifier,
+        rating_service_key: S,
+        rating: Rating,
+    ) -> Result<()> {
+        self.post::<SetRating>(SetRatingRequest::new(id, rating_service_key, rating))
+            .await?;
+
+        Ok(())
+    }
+
     /// Returns all pages of the client
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn get_pages(&self) -> Result<GetPagesResponse> {
@@ -439,9 +735,172 @@ impl Client {
         Ok(())
     }
 
+    /// Returns the duplicate relationships of the given files
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_file_relationships(
+        &self,
+        file_ids: Vec<u64>,
+        hashes: Vec<String>,
+    ) -> Result<GetFileRelationshipsResponse> {
+        let id_query = if !file_ids.is_empty() {
+            ("file_ids", Self::serialize_query_object(file_ids)?)
+        } else {
+            ("hashes", Self::serialize_query_object(hashes)?)
+        };
+        self.get_and_parse::<GetFileRelationships, [(&str, String)]>(&[id_query])
+            .await
+    }
+
+    /// Returns the number of potential duplicate pairs matching the options
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_potentials_count(
+        &self,
+        options: PotentialsSearchOptions,
+    ) -> Result<GetPotentialsCountResponse> {
+        let args = Self::potentials_query_args(options)?;
+        self.get_and_parse::<GetPotentialsCount, [(&str, String)]>(&args)
+            .await
+    }
+
+    /// Returns pairs of potential duplicates matching the options
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_potential_pairs(
+        &self,
+        options: PotentialsSearchOptions,
+    ) -> Result<GetPotentialPairsResponse> {
+        let args = Self::potentials_query_args(options)?;
+        self.get_and_parse::<GetPotentialPairs, [(&str, String)]>(&args)
+            .await
+    }
+
+    /// Returns the hashes of a random group of potential duplicates matching the options
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_random_potentials(
+        &self,
+        options: PotentialsSearchOptions,
+    ) -> Result<GetRandomPotentialsResponse> {
+        let args = Self::potentials_query_args(options)?;
+        self.get_and_parse::<GetRandomPotentials, [(&str, String)]>(&args)
+            .await
+    }
+
+    /// Sets the relationships between pairs of files
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn set_file_relationships(&self, relationships: Vec<FileRelationship>) -> Result<()> {
+        self.post::<SetFileRelationships>(SetFileRelationshipsRequest { relationships })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sets the files as the best files of their duplicate groups
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn set_kings(&self, files: FileSelection) -> Result<()> {
+        self.post::<SetKings>(SetKingsRequest {
+            file_selection: files,
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes all potential duplicate pairs the files are part of
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn remove_potentials(&self, files: FileSelection) -> Result<()> {
+        self.post::<RemovePotentials>(RemovePotentialsRequest {
+            file_selection: files,
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the number of pending and petitioned items for each repository
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_pending_counts(&self) -> Result<GetPendingCountsResponse> {
+        self.get_and_parse::<GetPendingCounts, ()>(&()).await
+    }
+
+    /// Uploads the pending content of a repository
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn commit_pending<S: ToString + Debug>(&self, service_key: S) -> Result<()> {
+        self.post::<CommitPending>(PendingContentRequest {
+            service_key: service_key.to_string(),
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Discards the pending content of a repository
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn forget_pending<S: ToString + Debug>(&self, service_key: S) -> Result<()> {
+        self.post::<ForgetPending>(PendingContentRequest {
+            service_key: service_key.to_string(),
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Locks the database of hydrus. While the database is locked hydrus
+    /// rejects most requests with a service unavailable error.
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn lock_database(&self) -> Result<()> {
+        self.post::<LockOn>(EmptyRequest::default()).await?;
+
+        Ok(())
+    }
+
+    /// Unlocks the database of hydrus
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn unlock_database(&self) -> Result<()> {
+        self.post::<LockOff>(EmptyRequest::default()).await?;
+
+        Ok(())
+    }
+
+    /// Forces hydrus to write all pending changes to the database
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn force_commit(&self) -> Result<()> {
+        self.post::<ForceCommit>(EmptyRequest::default()).await?;
+
+        Ok(())
+    }
+
+    /// Returns statistics about the files in the database
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn mr_bones(&self) -> Result<MrBonesResponse> {
+        self.get_and_parse::<MrBones, ()>(&()).await
+    }
+
+    /// Returns the options of the client
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub async fn get_client_options(&self) -> Result<GetClientOptionsResponse> {
+        self.get_and_parse::<GetClientOptions, ()>(&()).await
+    }
+
     /// Starts a get request to the path
     #[tracing::instrument(skip(self), level = "trace")]
     async fn get<E: Endpoint, Q: Serialize + Debug + ?Sized>(&self, query: &Q) -> Result<Response> {
+        self.get_with_headers::<E, Q>(query, HeaderMap::new()).await
+    }
+
+    /// Starts a get request to the path with additional headers
+    #[tracing::instrument(skip(self), level = "trace")]
+    async fn get_with_headers<E: Endpoint, Q: Serialize + Debug + ?Sized>(
+        &self,
+        query: &Q,
+        headers: HeaderMap,
+    ) -> Result<Response> {
+        self.check_permission::<E>().await?;
+        let request = self.build_get::<E, Q>(query).headers(headers);
+
+        self.send(request).await
+    }
+
+    /// Builds an unauthenticated get request to the path
+    fn build_get<E: Endpoint, Q: Serialize + Debug + ?Sized>(&self, query: &Q) -> RequestBuilder {
         tracing::trace!("GET request to {}", E::path());
         #[cfg(feature = "json")]
         let content_type = CONTENT_TYPE_JSON;
@@ -452,18 +911,31 @@ impl Client {
         #[cfg(feature = "cbor")]
         let params = [("cbor", true)];
 
-        let response = self
-            .inner
+        self.inner
             .get(format!("{}/{}", self.base_url, E::path()))
-            .header(ACCESS_KEY_HEADER, &self.access_key)
             .header(CONTENT_TYPE_HEADER, content_type)
             .header(ACCEPT_HEADER, content_type)
             .query(query)
             .query(&params)
-            .send()
-            .await?;
+    }
 
-        Self::extract_error(response).await
+    /// Starts a get request to the path with the file identifier as query
+    #[tracing::instrument(skip(self), level = "trace")]
+    async fn get_by_file_identifier<E: Endpoint>(
+        &self,
+        id: FileIdentifier,
+        headers: HeaderMap,
+    ) -> Result<Response> {
+        match id {
+            FileIdentifier::ID(id) => {
+                self.get_with_headers::<E, [(&str, u64)]>(&[("file_id", id)], headers)
+                    .await
+            }
+            FileIdentifier::Hash(hash) => {
+                self.get_with_headers::<E, [(&str, String)]>(&[("hash", hash)], headers)
+                    .await
+            }
+        }
     }
 
     /// Starts a get request to the path associated with the Endpoint Type
@@ -477,6 +949,64 @@ impl Client {
         Self::extract_content(response).await
     }
 
+    /// Creates the query arguments for a file metadata request
+    fn metadata_query_args(
+        file_ids: Vec<u64>,
+        hashes: Vec<String>,
+        options: MetadataOptions,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let id_query = if !file_ids.is_empty() {
+            ("file_ids", Self::serialize_query_object(file_ids)?)
+        } else {
+            ("hashes", Self::serialize_query_object(hashes)?)
+        };
+        let mut args = vec![
+            id_query,
+            (
+                "only_return_identifiers",
+                Self::serialize_query_object(options.only_identifiers)?,
+            ),
+            (
+                "only_return_basic_information",
+                Self::serialize_query_object(options.only_basic_information)?,
+            ),
+        ];
+        let flags = [
+            ("detailed_url_information", options.detailed_url_information),
+            ("include_blurhash", options.include_blurhash),
+            ("include_notes", options.include_notes),
+            ("include_milliseconds", options.include_milliseconds),
+            ("hide_service_keys_tags", options.hide_service_keys_tags),
+            ("create_new_file_ids", options.create_new_file_ids),
+        ];
+        for (name, flag) in flags {
+            if let Some(flag) = flag {
+                args.push((name, Self::serialize_query_object(flag)?));
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// Creates the query arguments for the potential duplicate endpoints
+    fn potentials_query_args(
+        options: PotentialsSearchOptions,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let tags_1 = Self::serialize_query_object(&options.tags_1)?;
+        let tags_2 = Self::serialize_query_object(&options.tags_2)?;
+        let has_tags_1 = !options.tags_1.is_empty();
+        let has_tags_2 = !options.tags_2.is_empty();
+        let mut args = options.into_query_args();
+        if has_tags_1 {
+            args.push(("tags_1", tags_1));
+        }
+        if has_tags_2 {
+            args.push(("tags_2", tags_2));
+        }
+
+        Ok(args)
+    }
+
     /// Serializes a given object into a json or cbor query object
     #[tracing::instrument(skip(obj), level = "trace")]
     fn serialize_query_object<S: Serialize>(obj: S) -> Result<String> {
@@ -500,6 +1030,7 @@ impl Client {
     #[tracing::instrument(skip(self), level = "trace")]
     async fn post<E: Endpoint>(&self, body: E::Request) -> Result<Response> {
         tracing::trace!("POST request to {}", E::path());
+        self.check_permission::<E>().await?;
         let body = Self::serialize_body(body)?;
 
         #[cfg(feature = "cbor")]
@@ -507,22 +1038,19 @@ impl Client {
         #[cfg(feature = "json")]
         let content_type = CONTENT_TYPE_JSON;
 
-        let response = self
+        let request = self
             .inner
             .post(format!("{}/{}", self.base_url, E::path()))
             .body(body)
-            .header(ACCESS_KEY_HEADER, &self.access_key)
             .header(CONTENT_TYPE_HEADER, content_type)
-            .header(ACCEPT_HEADER, content_type)
-            .send()
-            .await?;
-        let response = Self::extract_error(response).await?;
-        Ok(response)
+            .header(ACCEPT_HEADER, content_type);
+
+        self.send(request).await
     }
 
     /// Serializes a body into either CBOR or JSON
     #[tracing::instrument(skip(body), level = "trace")]
-    fn serialize_body<S: Serialize>(body: S) -> Result<Vec<u8>> {
+    pub(crate) fn serialize_body<S: Serialize>(body: S) -> Result<Vec<u8>> {
         let mut buf = Vec::new();
 
         #[cfg(feature = "json")]
@@ -552,45 +1080,259 @@ impl Client {
     /// Stats a post request to the path associated with the return type
     /// This currently only supports JSON because of a limitation of the
     /// hydrus client api.
-    #[tracing::instrument(skip(self, data), level = "trace")]
-    async fn post_binary<E: Endpoint>(&self, data: Vec<u8>) -> Result<E::Response> {
+    #[tracing::instrument(skip(self, body), level = "trace")]
+    async fn post_binary<E: Endpoint>(
+        &self,
+        body: Body,
+        content_length: Option<u64>,
+    ) -> Result<E::Response> {
         tracing::trace!("Binary POST request to {}", E::path());
+        self.check_permission::<E>().await?;
 
         #[cfg(feature = "cbor")]
         let content_type = CONTENT_TYPE_CBOR;
         #[cfg(feature = "json")]
         let content_type = CONTENT_TYPE_JSON;
 
-        let response = self
+        let mut request = self
             .inner
             .post(format!("{}/{}", self.base_url, E::path()))
-            .body(data)
-            .header(ACCESS_KEY_HEADER, &self.access_key)
+            .body(body)
             .header(CONTENT_TYPE_HEADER, "application/octet-stream")
-            .header(ACCEPT_HEADER, content_type)
-            .send()
-            .await?;
-        let response = Self::extract_error(response).await?;
+            .header(ACCEPT_HEADER, content_type);
+
+        if let Some(length) = content_length {
+            request = request.header(CONTENT_LENGTH, length);
+        }
+        let response = self.send(request).await?;
 
         Self::extract_content(response).await
     }
 
+    /// Returns an error if the access key lacks the permission required by the endpoint.
+    /// The permissions are requested once and only checked if the client was built
+    /// with permission checks enabled.
+    async fn check_permission<E: Endpoint>(&self) -> Result<()> {
+        let (permissions, required) =
+            match (&self.permissions, Permission::required_for(&E::path())) {
+                (Some(permissions), Some(required)) => (permissions, required),
+                _ => return Ok(()),
+            };
+        let mut permissions = permissions.lock().await;
+
+        if permissions.is_none() {
+            tracing::debug!("Requesting the permissions of the access key");
+            let response = self
+                .send(self.build_get::<VerifyAccessKey, ()>(&()))
+                .await?;
+            let response: VerifyAccessKeyResponse = Self::extract_content(response).await?;
+            *permissions = Some(response.permissions());
+        }
+        match &*permissions {
+            Some(permissions) if !permissions.contains(&required) => {
+                Err(Error::MissingPermission(required))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends the request and retries it according to the retry policy of the client.
+    /// Requests with a streamed body are never retried.
+    #[tracing::instrument(skip(self), level = "trace")]
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let mut request = request.build()?;
+        let max_attempts = self
+            .retry_policy
+            .as_ref()
+            .map(|policy| policy.attempts_for(request.method()))
+            .unwrap_or(1);
+        let mut attempt = 1;
+
+        loop {
+            let retry_request = if attempt < max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            match (self.send_authenticated(request).await, retry_request) {
+                (Err(e), Some(retry_request)) if self.is_retryable(&e) => {
+                    let backoff = self
+                        .retry_policy
+                        .as_ref()
+                        .map(|policy| policy.backoff(attempt))
+                        .unwrap_or_default();
+                    tracing::warn!(
+                        "Request failed with '{}'. Retrying in {:?} (attempt {}/{})",
+                        e,
+                        backoff,
+                        attempt + 1,
+                        max_attempts
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                    request = retry_request;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    /// Returns if the retry policy of the client allows retrying after the error
+    fn is_retryable(&self, error: &Error) -> bool {
+        self.retry_policy
+            .as_ref()
+            .map(|policy| policy.is_retryable(error))
+            .unwrap_or(false)
+    }
+
+    /// Authenticates and sends the request.
+    /// If hydrus reports the session key as expired it is renewed
+    /// and the request is sent again if its body can be cloned.
+    #[tracing::instrument(skip(self), level = "trace")]
+    async fn send_authenticated(&self, mut request: Request) -> Result<Response> {
+        let retry_request = request.try_clone();
+        let key = self.authenticate(&mut request).await?;
+        let response = self.inner.execute(request).await?;
+
+        if self.session.is_some() && response.status().as_u16() == STATUS_SESSION_EXPIRED {
+            tracing::debug!("Session key expired");
+            self.invalidate_session_key(&key).await;
+
+            if let Some(mut request) = retry_request {
+                self.authenticate(&mut request).await?;
+                let response = self.inner.execute(request).await?;
+
+                return Self::extract_error(response).await;
+            }
+            tracing::debug!("The request can't be sent again as its body can't be cloned");
+        }
+
+        Self::extract_error(response).await
+    }
+
+    /// Adds the authentication header to the request and returns the used key
+    async fn authenticate(&self, request: &mut Request) -> Result<String> {
+        let (header, key) = self.authentication().await?;
+        let value = HeaderValue::from_str(&key).map_err(|e| Error::BuildError(e.to_string()))?;
+        request.headers_mut().insert(header, value);
+
+        Ok(key)
+    }
+
+    /// Returns the header name and key used to authenticate requests.
+    /// A new session key is requested if the client uses session keys
+    /// and doesn't have a valid one.
+    async fn authentication(&self) -> Result<(&'static str, String)> {
+        if let Some(session) = &self.session {
+            let mut session_key = session.lock().await;
+
+            if let Some(key) = &*session_key {
+                Ok((SESSION_KEY_HEADER, key.clone()))
+            } else {
+                tracing::debug!("Requesting new session key");
+                let key = self.session_key().await?.session_key;
+                *session_key = Some(key.clone());
+
+                Ok((SESSION_KEY_HEADER, key))
+            }
+        } else {
+            Ok((ACCESS_KEY_HEADER, self.access_key.clone()))
+        }
+    }
+
+    /// Removes the stored session key if it is still the given expired key
+    async fn invalidate_session_key(&self, expired_key: &str) {
+        if let Some(session) = &self.session {
+            let mut session_key = session.lock().await;
+
+            if session_key.as_deref() == Some(expired_key) {
+                *session_key = None;
+            }
+        }
+    }
+
     /// Returns an error with the response text content if the status doesn't indicate success
     #[tracing::instrument(level = "trace")]
     async fn extract_error(response: Response) -> Result<Response> {
         if !response.status().is_success() {
-            let msg = response.text().await?;
-            tracing::error!("API returned error '{}'", msg);
-            Err(Error::Hydrus(msg))
+            let status = response.status().as_u16();
+            let bytes = response.bytes().await?;
+            let error = match Self::deserialize_content::<ErrorResponse>(&bytes) {
+                Ok(body) => HydrusError {
+                    status,
+                    error: body.error,
+                    exception_type: body.exception_type,
+                },
+                Err(_) => HydrusError {
+                    status,
+                    error: String::from_utf8_lossy(&bytes).into_owned(),
+                    exception_type: None,
+                },
+            };
+            tracing::error!("API returned error '{}'", error);
+            Err(Error::from_hydrus_error(error))
         } else {
             Ok(response)
         }
     }
 
+    /// Reads the mime type and bytes of a file response
+    #[tracing::instrument(level = "trace")]
+    async fn extract_file_record(response: Response) -> Result<FileRecord> {
+        let mime_type = Self::extract_mime_type(&response);
+        let bytes = response.bytes().await?.to_vec();
+
+        Ok(FileRecord {
+            bytes,
+            mime_type,
+            path: None,
+        })
+    }
+
+    /// Wraps the body of a file response into a stream
+    #[tracing::instrument(level = "trace")]
+    fn extract_file_stream(response: Response) -> FileStream {
+        let mime_type = Self::extract_mime_type(&response);
+        let content_length = response.content_length();
+        let total_length = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(parse_content_range_length)
+            .or(content_length);
+        let inner = response.bytes_stream().map_err(Error::from).boxed();
+
+        FileStream {
+            mime_type,
+            content_length,
+            total_length,
+            inner,
+        }
+    }
+
+    /// Returns the mime type of a file response
+    fn extract_mime_type(response: &Response) -> String {
+        response
+            .headers()
+            .get("mime-type")
+            .or_else(|| response.headers().get(CONTENT_TYPE_HEADER))
+            .and_then(|h| h.to_str().ok())
+            .map(String::from)
+            .unwrap_or_else(|| String::from("image/jpeg"))
+    }
+
     /// Parses the response as JSOn
     #[tracing::instrument(level = "trace")]
     async fn extract_content<T: DeserializeOwned + Debug>(response: Response) -> Result<T> {
         let bytes = response.bytes().await?;
+
+        Self::deserialize_content(&bytes)
+    }
+
+    /// Deserializes bytes from either CBOR or JSON
+    #[tracing::instrument(skip(bytes), level = "trace")]
+    pub(crate) fn deserialize_content<T: DeserializeOwned + Debug>(bytes: &[u8]) -> Result<T> {
         let reader = bytes.reader();
         #[cfg(feature = "json")]
         let content = {
@@ -608,3 +1350,10 @@ impl Client {
         Ok(content)
     }
 }
+
+/// The body of an error response
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    exception_type: Option<String>,
+}
@@ -1,6 +1,18 @@
+use crate::error::Result;
+use crate::utils::deserialize_timestamp;
 use crate::wrapper::service::ServiceName;
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasicServiceInfo {
@@ -190,6 +202,77 @@ impl From<ServiceName> for FileServiceSelection {
 pub struct FileRecord {
     pub bytes: Vec<u8>,
     pub mime_type: String,
+    /// The local path the content has been read from.
+    /// It's `None` if the content has been fetched from the api
+    pub path: Option<PathBuf>,
+}
+
+/// A file whose content is streamed from hydrus instead of being
+/// buffered into memory
+pub struct FileStream {
+    pub mime_type: String,
+    /// The length of the content returned by this response
+    pub content_length: Option<u64>,
+    /// The length of the complete file. This differs from the content
+    /// length when only a range of the file was requested
+    pub total_length: Option<u64>,
+    pub(crate) inner: BoxStream<'static, Result<Bytes>>,
+}
+
+/// File content that is uploaded to hydrus as a stream
+/// instead of being buffered into memory
+pub struct FileUploadStream {
+    pub(crate) inner: BoxStream<'static, io::Result<Bytes>>,
+    pub(crate) length: Option<u64>,
+}
+
+impl FileUploadStream {
+    /// Creates a new upload from a stream of bytes. If the length of the content
+    /// is known it is sent to hydrus as the content length of the request
+    pub fn new<S>(stream: S, length: Option<u64>) -> Self
+    where
+        S: Stream<Item = io::Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            inner: stream.boxed(),
+            length,
+        }
+    }
+
+    /// Creates a new upload that reads the content from an async reader
+    pub fn from_reader<R: AsyncRead + Send + 'static>(reader: R, length: Option<u64>) -> Self {
+        Self::new(ReaderStream::new(reader), length)
+    }
+}
+
+impl Debug for FileUploadStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileUploadStream")
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl Debug for FileStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileStream")
+            .field("mime_type", &self.mime_type)
+            .field("content_length", &self.content_length)
+            .field("total_length", &self.total_length)
+            .finish()
+    }
+}
+
+impl Stream for FileStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 #[derive(Clone, Default, Debug, Deserialize)]
@@ -200,12 +283,15 @@ pub struct FileMetadataServices {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct FileMetadataServiceCurrent {
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub time_imported: u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct FileMetadataServiceDeleted {
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub time_deleted: u64,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub time_imported: u64,
 }
 
@@ -1,6 +1,9 @@
 use crate::api_core::common::BasicServiceInfo;
 use crate::api_core::endpoints::Endpoint;
+use crate::error::{Error, Result};
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
 
 pub static SERVICE_TYPE_LOCAL_TAGS: &str = "local_tags";
 pub static SERVICE_TYPE_TAG_REPOSITORIES: &str = "tag_repositories";
@@ -10,6 +13,14 @@ pub static SERVICE_TYPE_ALL_LOCAL_FILES: &str = "all_local_files";
 pub static SERVICE_TYPE_ALL_KNOWN_FILES: &str = "all_known_files";
 pub static SERVICE_TYPE_ALL_KNOWN_TAGS: &str = "all_known_tags";
 pub static SERVICE_TYPE_TRASH: &str = "trash";
+pub static SERVICE_TYPE_LIKE_RATINGS: &str = "like_ratings";
+pub static SERVICE_TYPE_NUMERICAL_RATINGS: &str = "numerical_ratings";
+pub static SERVICE_TYPE_INC_DEC_RATINGS: &str = "inc_dec_ratings";
+pub static SERVICE_TYPE_IPFS: &str = "ipfs";
+pub static SERVICE_TYPE_ALL_DELETED_FILES: &str = "all_deleted_files";
+pub static SERVICE_TYPE_REPOSITORY_UPDATES: &str = "local_updates";
+pub static SERVICE_TYPE_ALL_MY_FILES: &str = "all_local_media";
+pub static SERVICE_TYPE_SERVER_ADMINISTRATION: &str = "server_administration";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ApiVersionResponse {
@@ -44,12 +55,156 @@ impl Endpoint for SessionKey {
     }
 }
 
+/// A basic permission that can be granted to an access key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Permission {
+    ImportUrls,
+    ImportFiles,
+    AddTags,
+    SearchFiles,
+    ManagePages,
+    ManageCookies,
+    ManageDatabase,
+    AddNotes,
+    ManageFileRelationships,
+    EditRatings,
+    ManagePopups,
+    EditTimes,
+    CommitPending,
+    SeeLocalPaths,
+}
+
+impl Permission {
+    /// Returns the id hydrus uses for the permission
+    pub fn id(&self) -> u32 {
+        match self {
+            Permission::ImportUrls => 0,
+            Permission::ImportFiles => 1,
+            Permission::AddTags => 2,
+            Permission::SearchFiles => 3,
+            Permission::ManagePages => 4,
+            Permission::ManageCookies => 5,
+            Permission::ManageDatabase => 6,
+            Permission::AddNotes => 7,
+            Permission::ManageFileRelationships => 8,
+            Permission::EditRatings => 9,
+            Permission::ManagePopups => 10,
+            Permission::EditTimes => 11,
+            Permission::CommitPending => 12,
+            Permission::SeeLocalPaths => 13,
+        }
+    }
+
+    /// Returns all permissions known to the client
+    pub fn all() -> Vec<Self> {
+        (0..=13).filter_map(|id| Self::try_from(id).ok()).collect()
+    }
+
+    /// Returns the permission hydrus requires for requests to the given endpoint path
+    pub(crate) fn required_for(path: &str) -> Option<Self> {
+        match path {
+            "add_tags/search_tags" => return Some(Permission::SearchFiles),
+            "get_files/file_path" | "get_files/thumbnail_path" => {
+                return Some(Permission::SeeLocalPaths)
+            }
+            _ => {}
+        }
+        let permission = match path.split('/').next()? {
+            "add_urls" => Permission::ImportUrls,
+            "add_files" => Permission::ImportFiles,
+            "add_tags" => Permission::AddTags,
+            "get_files" => Permission::SearchFiles,
+            "manage_pages" => Permission::ManagePages,
+            "manage_cookies" | "manage_headers" => Permission::ManageCookies,
+            "manage_database" => Permission::ManageDatabase,
+            "add_notes" => Permission::AddNotes,
+            "manage_file_relationships" => Permission::ManageFileRelationships,
+            "edit_ratings" => Permission::EditRatings,
+            "manage_popups" => Permission::ManagePopups,
+            "edit_times" => Permission::EditTimes,
+            "manage_services" => Permission::CommitPending,
+            _ => return None,
+        };
+
+        Some(permission)
+    }
+}
+
+impl TryFrom<u32> for Permission {
+    type Error = Error;
+
+    fn try_from(id: u32) -> Result<Self> {
+        let permission = match id {
+            0 => Permission::ImportUrls,
+            1 => Permission::ImportFiles,
+            2 => Permission::AddTags,
+            3 => Permission::SearchFiles,
+            4 => Permission::ManagePages,
+            5 => Permission::ManageCookies,
+            6 => Permission::ManageDatabase,
+            7 => Permission::AddNotes,
+            8 => Permission::ManageFileRelationships,
+            9 => Permission::EditRatings,
+            10 => Permission::ManagePopups,
+            11 => Permission::EditTimes,
+            12 => Permission::CommitPending,
+            13 => Permission::SeeLocalPaths,
+            _ => return Err(Error::InvalidPermission(id)),
+        };
+
+        Ok(permission)
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Permission::ImportUrls => "import and edit urls",
+            Permission::ImportFiles => "import and delete files",
+            Permission::AddTags => "edit file tags",
+            Permission::SearchFiles => "search for and fetch files",
+            Permission::ManagePages => "manage pages",
+            Permission::ManageCookies => "manage cookies and headers",
+            Permission::ManageDatabase => "manage database",
+            Permission::AddNotes => "edit file notes",
+            Permission::ManageFileRelationships => "manage file relationships",
+            Permission::EditRatings => "edit file ratings",
+            Permission::ManagePopups => "manage popups",
+            Permission::EditTimes => "edit file times",
+            Permission::CommitPending => "commit pending",
+            Permission::SeeLocalPaths => "see local paths",
+        };
+        name.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct VerifyAccessKeyResponse {
     pub basic_permissions: Vec<u32>,
+    #[serde(default)]
+    pub permits_everything: bool,
     pub human_description: String,
 }
 
+impl VerifyAccessKeyResponse {
+    /// Returns the permissions granted to the access key.
+    /// Unknown permission ids are ignored.
+    pub fn permissions(&self) -> Vec<Permission> {
+        if self.permits_everything {
+            return Permission::all();
+        }
+        self.basic_permissions
+            .iter()
+            .filter_map(|id| Permission::try_from(*id).ok())
+            .collect()
+    }
+
+    /// Returns if the access key is allowed to perform actions that require the permission
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permits_everything || self.basic_permissions.contains(&permission.id())
+    }
+}
+
 pub struct VerifyAccessKey;
 
 impl Endpoint for VerifyAccessKey {
@@ -61,6 +216,22 @@ impl Endpoint for VerifyAccessKey {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestNewPermissionsResponse {
+    pub access_key: String,
+}
+
+pub struct RequestNewPermissions;
+
+impl Endpoint for RequestNewPermissions {
+    type Request = ();
+    type Response = RequestNewPermissionsResponse;
+
+    fn path() -> String {
+        String::from("request_new_permissions")
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct GetServicesResponse(pub HashMap<String, Vec<BasicServiceInfo>>);
 
@@ -74,3 +245,31 @@ impl Endpoint for GetServices {
         String::from("get_services")
     }
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceInfo {
+    pub name: String,
+    pub service_key: String,
+    #[serde(rename = "type")]
+    pub service_type: u32,
+    pub type_pretty: String,
+    pub star_shape: Option<String>,
+    pub min_stars: Option<u32>,
+    pub max_stars: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetServiceResponse {
+    pub service: ServiceInfo,
+}
+
+pub struct GetService;
+
+impl Endpoint for GetService {
+    type Request = ();
+    type Response = GetServiceResponse;
+
+    fn path() -> String {
+        String::from("get_service")
+    }
+}
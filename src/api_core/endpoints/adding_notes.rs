@@ -2,11 +2,17 @@ use crate::api_core::common::FileIdentifier;
 use crate::api_core::endpoints::Endpoint;
 use std::collections::HashMap;
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetNotesResponse {
+    /// The notes that were actually set after merging
+    pub notes: HashMap<String, String>,
+}
+
 pub struct SetNotes;
 
 impl Endpoint for SetNotes {
     type Request = SetNotesRequest;
-    type Response = ();
+    type Response = SetNotesResponse;
 
     fn path() -> String {
         String::from("add_notes/set_notes")
@@ -20,6 +26,12 @@ pub struct SetNotesRequest {
     hash: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     file_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_cleverly: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extend_existing_note_if_possible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict_resolution: Option<u8>,
 }
 
 impl SetNotesRequest {
@@ -35,6 +47,40 @@ impl SetNotesRequest {
 
         request
     }
+
+    /// Merges the notes with existing notes of the same name instead
+    /// of overwriting them
+    pub fn merge_cleverly(mut self, merge_cleverly: bool) -> Self {
+        self.merge_cleverly = Some(merge_cleverly);
+        self
+    }
+
+    /// If an existing note should be replaced when the new note starts with
+    /// its content. Only applies when merging cleverly
+    pub fn extend_existing_note_if_possible(mut self, extend: bool) -> Self {
+        self.extend_existing_note_if_possible = Some(extend);
+        self
+    }
+
+    /// Sets how conflicts with existing notes are resolved. Only applies
+    /// when merging cleverly
+    pub fn conflict_resolution(mut self, resolution: NoteConflictResolution) -> Self {
+        self.conflict_resolution = Some(resolution as u8);
+        self
+    }
+}
+
+/// How a note is set when a different note with the same name already exists
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoteConflictResolution {
+    /// Replaces the existing note
+    Replace = 0,
+    /// Keeps the existing note
+    Ignore = 1,
+    /// Appends the new note to the existing note
+    Append = 2,
+    /// Adds the new note with a different name
+    Rename = 3,
 }
 
 pub struct DeleteNotes;
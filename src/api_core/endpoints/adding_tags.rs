@@ -18,6 +18,75 @@ impl Endpoint for CleanTags {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchTagsResponse {
+    pub tags: Vec<TagWithCount>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagWithCount {
+    pub value: String,
+    pub count: u64,
+}
+
+pub struct SearchTags;
+
+impl Endpoint for SearchTags {
+    type Request = ();
+    type Response = SearchTagsResponse;
+
+    fn path() -> String {
+        String::from("add_tags/search_tags")
+    }
+}
+
+/// The way tags are displayed. Storage tags are the tags as they are saved
+/// while display tags have siblings and parents applied
+#[derive(Clone, Debug, Default, PartialOrd, PartialEq, Eq, Hash)]
+pub enum TagDisplayType {
+    #[default]
+    Storage,
+    Display,
+}
+
+impl TagDisplayType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            TagDisplayType::Storage => "storage",
+            TagDisplayType::Display => "display",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetSiblingsAndParentsResponse {
+    /// The siblings and parents mapped by tag and service key
+    pub tags: HashMap<String, HashMap<String, TagSiblingsAndParents>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagSiblingsAndParents {
+    /// All tags that are siblings of the tag including the tag itself
+    pub siblings: Vec<String>,
+    /// The sibling the tag is displayed as
+    pub ideal_tag: String,
+    /// All tags that have the ideal tag as parent
+    pub descendants: Vec<String>,
+    /// All parents of the ideal tag
+    pub ancestors: Vec<String>,
+}
+
+pub struct GetSiblingsAndParents;
+
+impl Endpoint for GetSiblingsAndParents {
+    type Request = ();
+    type Response = GetSiblingsAndParentsResponse;
+
+    fn path() -> String {
+        String::from("add_tags/get_siblings_and_parents")
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AddTagsRequest {
     pub hashes: Vec<String>,
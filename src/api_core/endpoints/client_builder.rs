@@ -1,11 +1,16 @@
+use crate::api_core::retry::RetryPolicy;
 use crate::error::{Error, Result};
 use crate::Client;
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct ClientBuilder {
     reqwest_builder: reqwest::ClientBuilder,
     base_url: String,
     access_key: Option<String>,
+    use_session_key: bool,
+    retry_policy: Option<RetryPolicy>,
+    check_permissions: bool,
 }
 
 impl Default for ClientBuilder {
@@ -14,6 +19,9 @@ impl Default for ClientBuilder {
             reqwest_builder: Default::default(),
             base_url: "127.0.0.1:45869".to_string(),
             access_key: None,
+            use_session_key: false,
+            retry_policy: None,
+            check_permissions: false,
         }
     }
 }
@@ -35,6 +43,32 @@ impl ClientBuilder {
         self
     }
 
+    /// Authenticates requests with a session key instead of the access key.
+    /// The session key is requested with the access key on the first request
+    /// and renewed automatically when it expires.
+    pub fn use_session_key(mut self, use_session_key: bool) -> Self {
+        self.use_session_key = use_session_key;
+
+        self
+    }
+
+    /// Sets the policy for retrying failed requests.
+    /// By default requests aren't retried.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+
+        self
+    }
+
+    /// Checks the permissions of the access key before sending requests and
+    /// fails with [Error::MissingPermission] instead of sending requests hydrus would reject.
+    /// The permissions are requested with the first request that requires a permission.
+    pub fn check_permissions(mut self, check_permissions: bool) -> Self {
+        self.check_permissions = check_permissions;
+
+        self
+    }
+
     /// Sets the default timeout for requests to the API
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.reqwest_builder = self.reqwest_builder.timeout(timeout);
@@ -51,6 +85,9 @@ impl ClientBuilder {
             inner: self.reqwest_builder.build()?,
             base_url: self.base_url,
             access_key,
+            session: self.use_session_key.then(|| Arc::new(Default::default())),
+            retry_policy: self.retry_policy,
+            permissions: self.check_permissions.then(|| Arc::new(Default::default())),
         })
     }
 }
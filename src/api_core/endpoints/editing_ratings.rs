@@ -0,0 +1,70 @@
+use crate::api_core::common::FileIdentifier;
+use crate::api_core::endpoints::Endpoint;
+
+/// The raw value of a rating as it is sent and received by hydrus.
+/// It can only be interpreted with the type of the rating service
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RatingValue {
+    Bool(bool),
+    Number(u32),
+}
+
+/// The rating of a file on a rating service
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    /// A like or dislike. `None` if the file isn't rated
+    LikeDislike(Option<bool>),
+    /// A number of stars. `None` if the file isn't rated
+    Numerical(Option<u32>),
+    /// A counter that can be incremented and decremented
+    IncDec(u32),
+}
+
+impl Rating {
+    /// Returns the value that is sent to hydrus
+    pub fn value(&self) -> Option<RatingValue> {
+        match self {
+            Rating::LikeDislike(like) => like.map(RatingValue::Bool),
+            Rating::Numerical(stars) => stars.map(RatingValue::Number),
+            Rating::IncDec(count) => Some(RatingValue::Number(*count)),
+        }
+    }
+}
+
+pub struct SetRating;
+
+impl Endpoint for SetRating {
+    type Request = SetRatingRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("edit_ratings/set_rating")
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SetRatingRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_id: Option<u64>,
+    rating_service_key: String,
+    rating: Option<RatingValue>,
+}
+
+impl SetRatingRequest {
+    pub fn new<S: ToString>(id: FileIdentifier, rating_service_key: S, rating: Rating) -> Self {
+        let (hash, file_id) = match id {
+            FileIdentifier::ID(id) => (None, Some(id)),
+            FileIdentifier::Hash(hash) => (Some(hash), None),
+        };
+
+        Self {
+            hash,
+            file_id,
+            rating_service_key: rating_service_key.to_string(),
+            rating: rating.value(),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::api_core::endpoints::Endpoint;
+use std::collections::HashMap;
+
+/// The body of requests to the database endpoints that don't take any arguments
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EmptyRequest {}
+
+pub struct LockOn;
+
+impl Endpoint for LockOn {
+    type Request = EmptyRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_database/lock_on")
+    }
+}
+
+pub struct LockOff;
+
+impl Endpoint for LockOff {
+    type Request = EmptyRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_database/lock_off")
+    }
+}
+
+pub struct ForceCommit;
+
+impl Endpoint for ForceCommit {
+    type Request = EmptyRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_database/force_commit")
+    }
+}
+
+/// Statistics about the files in the database
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BonedStats {
+    pub num_inbox: u64,
+    pub num_archive: u64,
+    pub num_deleted: u64,
+    pub size_inbox: u64,
+    pub size_archive: u64,
+    pub size_deleted: u64,
+    pub earliest_import_time: Option<u64>,
+    /// The media views, media viewtime, preview views and preview viewtime
+    pub total_viewtime: (u64, u64, u64, u64),
+    pub total_alternate_files: u64,
+    pub total_duplicate_files: u64,
+    pub total_potential_pairs: u64,
+}
+
+impl BonedStats {
+    /// Returns the number of files that haven't been deleted
+    pub fn num_files(&self) -> u64 {
+        self.num_inbox + self.num_archive
+    }
+
+    /// Returns the size of the files that haven't been deleted
+    pub fn size_files(&self) -> u64 {
+        self.size_inbox + self.size_archive
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MrBonesResponse {
+    pub boned_stats: BonedStats,
+}
+
+pub struct MrBones;
+
+impl Endpoint for MrBones {
+    type Request = ();
+    type Response = MrBonesResponse;
+
+    fn path() -> String {
+        String::from("manage_database/mr_bones")
+    }
+}
+
+/// The options of the client. Only the options with simple
+/// values are parsed.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClientOptions {
+    pub booleans: HashMap<String, bool>,
+    pub strings: HashMap<String, String>,
+    pub noneable_strings: HashMap<String, Option<String>>,
+    pub integers: HashMap<String, i64>,
+    pub noneable_integers: HashMap<String, Option<i64>>,
+    pub media_zooms: Vec<f64>,
+    pub slideshow_durations: Vec<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetClientOptionsResponse {
+    pub options: ClientOptions,
+}
+
+pub struct GetClientOptions;
+
+impl Endpoint for GetClientOptions {
+    type Request = ();
+    type Response = GetClientOptionsResponse;
+
+    fn path() -> String {
+        String::from("manage_database/get_client_options")
+    }
+}
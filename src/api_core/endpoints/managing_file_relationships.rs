@@ -0,0 +1,305 @@
+use crate::api_core::common::FileSelection;
+use crate::api_core::endpoints::searching_and_fetching_files::SearchQueryEntry;
+use crate::api_core::endpoints::Endpoint;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetFileRelationshipsResponse {
+    /// The relationships mapped by file hash
+    pub file_relationships: HashMap<String, FileRelationships>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileRelationships {
+    pub is_king: bool,
+    pub king: Option<String>,
+    #[serde(default)]
+    pub king_is_on_file_domain: bool,
+    #[serde(default)]
+    pub king_is_local: bool,
+    #[serde(rename = "0", default)]
+    pub potential_duplicates: Vec<String>,
+    #[serde(rename = "1", default)]
+    pub false_positives: Vec<String>,
+    #[serde(rename = "3", default)]
+    pub alternates: Vec<String>,
+    #[serde(rename = "8", default)]
+    pub duplicates: Vec<String>,
+}
+
+pub struct GetFileRelationships;
+
+impl Endpoint for GetFileRelationships {
+    type Request = ();
+    type Response = GetFileRelationshipsResponse;
+
+    fn path() -> String {
+        String::from("manage_file_relationships/get_file_relationships")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetPotentialsCountResponse {
+    pub potential_duplicates_count: u64,
+}
+
+pub struct GetPotentialsCount;
+
+impl Endpoint for GetPotentialsCount {
+    type Request = ();
+    type Response = GetPotentialsCountResponse;
+
+    fn path() -> String {
+        String::from("manage_file_relationships/get_potentials_count")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetPotentialPairsResponse {
+    pub potential_duplicate_pairs: Vec<(String, String)>,
+}
+
+pub struct GetPotentialPairs;
+
+impl Endpoint for GetPotentialPairs {
+    type Request = ();
+    type Response = GetPotentialPairsResponse;
+
+    fn path() -> String {
+        String::from("manage_file_relationships/get_potential_pairs")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetRandomPotentialsResponse {
+    pub random_potential_duplicate_hashes: Vec<String>,
+}
+
+pub struct GetRandomPotentials;
+
+impl Endpoint for GetRandomPotentials {
+    type Request = ();
+    type Response = GetRandomPotentialsResponse;
+
+    fn path() -> String {
+        String::from("manage_file_relationships/get_random_potentials")
+    }
+}
+
+/// How the files of a potential duplicate pair have to match the searches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PotentialsSearchType {
+    /// At least one file matches the first search
+    OneMatchesFirst = 0,
+    /// Both files match the first search
+    BothMatchFirst = 1,
+    /// One file matches the first and the other one the second search
+    OneMatchesEach = 2,
+}
+
+/// If potential duplicates have to be pixel duplicates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelDuplicates {
+    Required = 0,
+    Allowed = 1,
+    Excluded = 2,
+}
+
+/// Options to select the potential duplicates that are returned
+#[derive(Clone, Debug, Default)]
+pub struct PotentialsSearchOptions {
+    file_service_name: Option<String>,
+    file_service_key: Option<String>,
+    tag_service_key_1: Option<String>,
+    pub(crate) tags_1: Vec<SearchQueryEntry>,
+    tag_service_key_2: Option<String>,
+    pub(crate) tags_2: Vec<SearchQueryEntry>,
+    search_type: Option<PotentialsSearchType>,
+    pixel_duplicates: Option<PixelDuplicates>,
+    max_hamming_distance: Option<u32>,
+    max_num_pairs: Option<u32>,
+}
+
+impl PotentialsSearchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn file_service_name<S: ToString>(mut self, name: S) -> Self {
+        self.file_service_name = Some(name.to_string());
+        self
+    }
+
+    pub fn file_service_key<S: ToString>(mut self, key: S) -> Self {
+        self.file_service_key = Some(key.to_string());
+        self
+    }
+
+    /// Sets the first search and the tag service it is executed on
+    pub fn first_search<S: ToString>(
+        mut self,
+        tag_service_key: S,
+        tags: Vec<SearchQueryEntry>,
+    ) -> Self {
+        self.tag_service_key_1 = Some(tag_service_key.to_string());
+        self.tags_1 = tags;
+        self
+    }
+
+    /// Sets the second search and the tag service it is executed on
+    pub fn second_search<S: ToString>(
+        mut self,
+        tag_service_key: S,
+        tags: Vec<SearchQueryEntry>,
+    ) -> Self {
+        self.tag_service_key_2 = Some(tag_service_key.to_string());
+        self.tags_2 = tags;
+        self
+    }
+
+    pub fn search_type(mut self, search_type: PotentialsSearchType) -> Self {
+        self.search_type = Some(search_type);
+        self
+    }
+
+    pub fn pixel_duplicates(mut self, pixel_duplicates: PixelDuplicates) -> Self {
+        self.pixel_duplicates = Some(pixel_duplicates);
+        self
+    }
+
+    /// Sets the maximum perceptual hash distance of the files in a pair
+    pub fn max_hamming_distance(mut self, distance: u32) -> Self {
+        self.max_hamming_distance = Some(distance);
+        self
+    }
+
+    /// Sets the maximum number of pairs returned by [crate::Client::get_potential_pairs]
+    pub fn max_num_pairs(mut self, max: u32) -> Self {
+        self.max_num_pairs = Some(max);
+        self
+    }
+
+    /// Returns the arguments that aren't query objects
+    pub(crate) fn into_query_args(self) -> Vec<(&'static str, String)> {
+        let mut args = Vec::new();
+        if let Some(file_service_name) = self.file_service_name {
+            args.push(("file_service_name", file_service_name));
+        }
+        if let Some(file_service_key) = self.file_service_key {
+            args.push(("file_service_key", file_service_key));
+        }
+        if let Some(key) = self.tag_service_key_1 {
+            args.push(("tag_service_key_1", key));
+        }
+        if let Some(key) = self.tag_service_key_2 {
+            args.push(("tag_service_key_2", key));
+        }
+        if let Some(search_type) = self.search_type {
+            args.push(("potentials_search_type", (search_type as u8).to_string()));
+        }
+        if let Some(pixel_duplicates) = self.pixel_duplicates {
+            args.push(("pixel_duplicates", (pixel_duplicates as u8).to_string()));
+        }
+        if let Some(distance) = self.max_hamming_distance {
+            args.push(("max_hamming_distance", distance.to_string()));
+        }
+        if let Some(max) = self.max_num_pairs {
+            args.push(("max_num_pairs", max.to_string()));
+        }
+
+        args
+    }
+}
+
+/// The relationship that is set between two files
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DuplicateRelationship {
+    PotentialDuplicates = 0,
+    FalsePositives = 1,
+    SameQuality = 2,
+    Alternates = 3,
+    ABetter = 4,
+    BBetter = 7,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FileRelationship {
+    pub hash_a: String,
+    pub hash_b: String,
+    pub relationship: u8,
+    pub do_default_content_merge: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_a: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_b: Option<bool>,
+}
+
+impl FileRelationship {
+    /// Creates a new relationship between two files that merges their
+    /// content with the default duplicate merge options
+    pub fn new<S1: ToString, S2: ToString>(
+        hash_a: S1,
+        hash_b: S2,
+        relationship: DuplicateRelationship,
+    ) -> Self {
+        Self {
+            hash_a: hash_a.to_string(),
+            hash_b: hash_b.to_string(),
+            relationship: relationship as u8,
+            do_default_content_merge: true,
+            delete_a: None,
+            delete_b: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SetFileRelationshipsRequest {
+    pub relationships: Vec<FileRelationship>,
+}
+
+pub struct SetFileRelationships;
+
+impl Endpoint for SetFileRelationships {
+    type Request = SetFileRelationshipsRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_file_relationships/set_file_relationships")
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SetKingsRequest {
+    #[serde(flatten)]
+    pub file_selection: FileSelection,
+}
+
+pub struct SetKings;
+
+impl Endpoint for SetKings {
+    type Request = SetKingsRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_file_relationships/set_kings")
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RemovePotentialsRequest {
+    #[serde(flatten)]
+    pub file_selection: FileSelection,
+}
+
+pub struct RemovePotentials;
+
+impl Endpoint for RemovePotentials {
+    type Request = RemovePotentialsRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_file_relationships/remove_potentials")
+    }
+}
@@ -0,0 +1,79 @@
+use crate::api_core::endpoints::Endpoint;
+use std::collections::HashMap;
+
+/// The content of a repository that is pending to be uploaded or petitioned to be removed
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PendingCounts {
+    pub pending_tag_mappings: u64,
+    pub petitioned_tag_mappings: u64,
+    pub pending_tag_siblings: u64,
+    pub petitioned_tag_siblings: u64,
+    pub pending_tag_parents: u64,
+    pub petitioned_tag_parents: u64,
+    pub pending_files: u64,
+    pub petitioned_files: u64,
+}
+
+impl PendingCounts {
+    /// Returns the total number of pending and petitioned items
+    pub fn total(&self) -> u64 {
+        self.pending_tag_mappings
+            + self.petitioned_tag_mappings
+            + self.pending_tag_siblings
+            + self.petitioned_tag_siblings
+            + self.pending_tag_parents
+            + self.petitioned_tag_parents
+            + self.pending_files
+            + self.petitioned_files
+    }
+
+    /// Returns if nothing is pending or petitioned
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetPendingCountsResponse {
+    /// The pending counts mapped by service key
+    pub pending_counts: HashMap<String, PendingCounts>,
+}
+
+pub struct GetPendingCounts;
+
+impl Endpoint for GetPendingCounts {
+    type Request = ();
+    type Response = GetPendingCountsResponse;
+
+    fn path() -> String {
+        String::from("manage_services/get_pending_counts")
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingContentRequest {
+    pub service_key: String,
+}
+
+pub struct CommitPending;
+
+impl Endpoint for CommitPending {
+    type Request = PendingContentRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_services/commit_pending")
+    }
+}
+
+pub struct ForgetPending;
+
+impl Endpoint for ForgetPending {
+    type Request = PendingContentRequest;
+    type Response = ();
+
+    fn path() -> String {
+        String::from("manage_services/forget_pending")
+    }
+}
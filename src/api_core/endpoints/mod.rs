@@ -8,8 +8,12 @@ pub mod adding_notes;
 pub mod adding_tags;
 pub mod adding_urls;
 pub mod client_builder;
+pub mod editing_ratings;
 pub mod managing_cookies_and_http_headers;
+pub mod managing_database;
+pub mod managing_file_relationships;
 pub mod managing_pages;
+pub mod managing_services;
 pub mod searching_and_fetching_files;
 
 pub(crate) trait Endpoint {
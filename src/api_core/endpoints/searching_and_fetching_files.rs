@@ -1,29 +1,151 @@
 use crate::api_core::common::FileMetadataServices;
+use crate::api_core::endpoints::editing_ratings::RatingValue;
 use crate::api_core::endpoints::Endpoint;
+use crate::error::{Error, Result};
+use crate::utils::deserialize_optional_seconds;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
 pub mod file_sort_type {
-    pub const SORT_FILE_SIZE: u8 = 0;
-    pub const SORT_FILE_DURATION: u8 = 1;
-    pub const SORT_FILE_IMPORT_TIME: u8 = 2;
-    pub const SORT_FILE_TYPE: u8 = 3;
-    pub const SORT_FILE_RANDOM: u8 = 4;
-    pub const SORT_FILE_WIDTH: u8 = 5;
-    pub const SORT_FILE_HEIGHT: u8 = 6;
-    pub const SORT_FILE_RATIO: u8 = 7;
-    pub const SORT_FILE_PIXEL_COUNT: u8 = 8;
-    pub const SORT_FILE_TAG_COUNT: u8 = 9;
-    pub const SORT_FILE_MEDIA_VIEWS: u8 = 10;
-    pub const SORT_FILE_MEDIA_VIEWTIME: u8 = 11;
-    pub const SORT_FILE_BITRATE: u8 = 12;
-    pub const SORT_FILE_HAS_AUDIO: u8 = 13;
-    pub const SORT_FILE_MODIFIED_TIME: u8 = 14;
-    pub const SORT_FILE_FRAMERATE: u8 = 15;
-    pub const SORT_FILE_FRAME_COUNT: u8 = 16;
+    use super::SortType;
+
+    pub const SORT_FILE_SIZE: SortType = SortType::FileSize;
+    pub const SORT_FILE_DURATION: SortType = SortType::Duration;
+    pub const SORT_FILE_IMPORT_TIME: SortType = SortType::ImportTime;
+    pub const SORT_FILE_TYPE: SortType = SortType::FileType;
+    pub const SORT_FILE_RANDOM: SortType = SortType::Random;
+    pub const SORT_FILE_WIDTH: SortType = SortType::Width;
+    pub const SORT_FILE_HEIGHT: SortType = SortType::Height;
+    pub const SORT_FILE_RATIO: SortType = SortType::Ratio;
+    pub const SORT_FILE_PIXEL_COUNT: SortType = SortType::NumberOfPixels;
+    pub const SORT_FILE_TAG_COUNT: SortType = SortType::NumberOfTags;
+    pub const SORT_FILE_MEDIA_VIEWS: SortType = SortType::NumberOfMediaViewers;
+    pub const SORT_FILE_MEDIA_VIEWTIME: SortType = SortType::MediaViewTime;
+    pub const SORT_FILE_BITRATE: SortType = SortType::Bitrate;
+    pub const SORT_FILE_HAS_AUDIO: SortType = SortType::HasAudio;
+    pub const SORT_FILE_MODIFIED_TIME: SortType = SortType::ModifiedTime;
+    pub const SORT_FILE_FRAMERATE: SortType = SortType::Framerate;
+    pub const SORT_FILE_FRAME_COUNT: SortType = SortType::NumberOfFrames;
+    pub const SORT_FILE_LAST_VIEWED_TIME: SortType = SortType::LastViewedTime;
+    pub const SORT_FILE_ARCHIVE_TIMESTAMP: SortType = SortType::ArchiveTimestamp;
+    pub const SORT_FILE_HASH: SortType = SortType::Hash;
+    pub const SORT_FILE_PIXEL_HASH: SortType = SortType::PixelHash;
+    pub const SORT_FILE_BLURHASH: SortType = SortType::Blurhash;
+}
+
+/// The order the files of a search are returned in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SortType {
+    FileSize,
+    Duration,
+    ImportTime,
+    FileType,
+    Random,
+    Width,
+    Height,
+    Ratio,
+    NumberOfPixels,
+    NumberOfTags,
+    NumberOfMediaViewers,
+    MediaViewTime,
+    Bitrate,
+    HasAudio,
+    ModifiedTime,
+    Framerate,
+    NumberOfFrames,
+    LastViewedTime,
+    ArchiveTimestamp,
+    Hash,
+    PixelHash,
+    Blurhash,
+    AverageColourLightness,
+    AverageColourChromaticMagnitude,
+    AverageColourGreenRed,
+    AverageColourBlueYellow,
+    AverageColourHue,
+}
+
+impl SortType {
+    /// Returns the id hydrus uses for the sort type
+    pub fn id(&self) -> u8 {
+        match self {
+            SortType::FileSize => 0,
+            SortType::Duration => 1,
+            SortType::ImportTime => 2,
+            SortType::FileType => 3,
+            SortType::Random => 4,
+            SortType::Width => 5,
+            SortType::Height => 6,
+            SortType::Ratio => 7,
+            SortType::NumberOfPixels => 8,
+            SortType::NumberOfTags => 9,
+            SortType::NumberOfMediaViewers => 10,
+            SortType::MediaViewTime => 11,
+            SortType::Bitrate => 12,
+            SortType::HasAudio => 13,
+            SortType::ModifiedTime => 14,
+            SortType::Framerate => 15,
+            SortType::NumberOfFrames => 16,
+            SortType::LastViewedTime => 18,
+            SortType::ArchiveTimestamp => 19,
+            SortType::Hash => 20,
+            SortType::PixelHash => 21,
+            SortType::Blurhash => 22,
+            SortType::AverageColourLightness => 23,
+            SortType::AverageColourChromaticMagnitude => 24,
+            SortType::AverageColourGreenRed => 25,
+            SortType::AverageColourBlueYellow => 26,
+            SortType::AverageColourHue => 27,
+        }
+    }
+
+    /// Returns if the files can be sorted ascending or descending with the sort type
+    pub fn has_direction(&self) -> bool {
+        *self != SortType::Random
+    }
+}
+
+impl TryFrom<u8> for SortType {
+    type Error = Error;
+
+    fn try_from(id: u8) -> Result<Self> {
+        let sort_type = match id {
+            0 => SortType::FileSize,
+            1 => SortType::Duration,
+            2 => SortType::ImportTime,
+            3 => SortType::FileType,
+            4 => SortType::Random,
+            5 => SortType::Width,
+            6 => SortType::Height,
+            7 => SortType::Ratio,
+            8 => SortType::NumberOfPixels,
+            9 => SortType::NumberOfTags,
+            10 => SortType::NumberOfMediaViewers,
+            11 => SortType::MediaViewTime,
+            12 => SortType::Bitrate,
+            13 => SortType::HasAudio,
+            14 => SortType::ModifiedTime,
+            15 => SortType::Framerate,
+            16 => SortType::NumberOfFrames,
+            18 => SortType::LastViewedTime,
+            19 => SortType::ArchiveTimestamp,
+            20 => SortType::Hash,
+            21 => SortType::PixelHash,
+            22 => SortType::Blurhash,
+            23 => SortType::AverageColourLightness,
+            24 => SortType::AverageColourChromaticMagnitude,
+            25 => SortType::AverageColourGreenRed,
+            26 => SortType::AverageColourBlueYellow,
+            27 => SortType::AverageColourHue,
+            id => return Err(Error::InvalidSortType(id)),
+        };
+
+        Ok(sort_type)
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -32,7 +154,7 @@ pub struct FileSearchOptions {
     file_service_key: Option<String>,
     tag_service_name: Option<String>,
     tag_service_key: Option<String>,
-    file_sort_type: Option<u8>,
+    file_sort_type: Option<SortType>,
     file_sort_asc: Option<bool>,
 }
 
@@ -61,7 +183,7 @@ impl FileSearchOptions {
         self
     }
 
-    pub fn sort_type(mut self, sort_type: u8) -> Self {
+    pub fn sort_type(mut self, sort_type: SortType) -> Self {
         self.file_sort_type = Some(sort_type);
         self
     }
@@ -76,10 +198,18 @@ impl FileSearchOptions {
         self
     }
 
-    pub(crate) fn into_query_args(self) -> Vec<(&'static str, String)> {
+    /// Returns the query arguments of the options.
+    /// Fails if a sort direction is set for a sort type without direction
+    pub(crate) fn into_query_args(self) -> Result<Vec<(&'static str, String)>> {
         let mut args = Vec::new();
         if let Some(sort) = self.file_sort_type {
-            args.push(("file_sort_type", sort.to_string()));
+            if self.file_sort_asc.is_some() && !sort.has_direction() {
+                return Err(Error::BuildError(format!(
+                    "The sort type {:?} can't be sorted ascending or descending",
+                    sort
+                )));
+            }
+            args.push(("file_sort_type", sort.id().to_string()));
         }
         if let Some(file_service_name) = self.file_service_name {
             args.push(("file_service_name", file_service_name));
@@ -97,7 +227,7 @@ impl FileSearchOptions {
             args.push(("file_sort_asc", sort_asc.to_string()))
         }
 
-        args
+        Ok(args)
     }
 }
 
@@ -133,6 +263,41 @@ impl Endpoint for SearchFileHashes {
     }
 }
 
+/// The result of a search that returns both the ids and the hashes of the files
+#[derive(Clone, Debug, Deserialize)]
+pub struct SearchFilesWithHashesResponse {
+    pub file_ids: Vec<u64>,
+    pub hashes: Vec<String>,
+}
+
+impl SearchFilesWithHashesResponse {
+    /// Returns the total number of files found
+    pub fn len(&self) -> usize {
+        self.file_ids.len()
+    }
+
+    /// Returns if no files were found
+    pub fn is_empty(&self) -> bool {
+        self.file_ids.is_empty()
+    }
+
+    /// Returns the ids of the files paired with their hashes
+    pub fn files(&self) -> impl Iterator<Item = (u64, &String)> {
+        self.file_ids.iter().copied().zip(self.hashes.iter())
+    }
+}
+
+pub struct SearchFilesWithHashes;
+
+impl Endpoint for SearchFilesWithHashes {
+    type Request = ();
+    type Response = SearchFilesWithHashesResponse;
+
+    fn path() -> String {
+        String::from("get_files/search_files")
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct FileMetadataResponse<M: FileMetadataType> {
     pub metadata: Vec<M::Response>,
@@ -149,6 +314,133 @@ impl<M: FileMetadataType> Endpoint for FileMetadata<M> {
     }
 }
 
+/// The metadata of a file requested with [MetadataOptions].
+/// Fields are only populated if they are included by the options
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FileMetadataInfo {
+    #[serde(flatten)]
+    pub identifiers: FileMetadataIdentifiers,
+    pub size: Option<u64>,
+    pub mime: Option<String>,
+    pub ext: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration: Option<u64>,
+    /// The modification time in seconds. Contains milliseconds as fractional
+    /// part if they were requested
+    #[serde(deserialize_with = "deserialize_optional_seconds")]
+    pub time_modified: Option<f64>,
+    pub has_audio: Option<bool>,
+    pub num_frames: Option<u64>,
+    pub num_words: Option<u64>,
+    pub blurhash: Option<String>,
+    pub is_inbox: Option<bool>,
+    pub is_local: Option<bool>,
+    pub is_trashed: Option<bool>,
+    pub file_services: Option<FileMetadataServices>,
+    pub known_urls: Option<Vec<String>>,
+    pub detailed_known_urls: Option<Vec<DetailedKnownUrl>>,
+    pub service_keys_to_statuses_to_tags: Option<HashMap<String, HashMap<String, Vec<String>>>>,
+    pub service_keys_to_statuses_to_display_tags:
+        Option<HashMap<String, HashMap<String, Vec<String>>>>,
+    pub ratings: Option<HashMap<String, Option<RatingValue>>>,
+    pub notes: Option<HashMap<String, String>>,
+}
+
+/// Information about a known url of a file
+#[derive(Clone, Debug, Deserialize)]
+pub struct DetailedKnownUrl {
+    pub normalised_url: String,
+    pub url_type: u8,
+    pub url_type_string: String,
+    pub match_name: String,
+    pub can_parse: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FileMetadataInfoResponse {
+    pub metadata: Vec<FileMetadataInfo>,
+}
+
+pub struct FileMetadataWithOptions;
+
+impl Endpoint for FileMetadataWithOptions {
+    type Request = ();
+    type Response = FileMetadataInfoResponse;
+
+    fn path() -> String {
+        String::from("get_files/file_metadata")
+    }
+}
+
+/// Options to select the metadata that is returned for files
+#[derive(Clone, Debug, Default)]
+pub struct MetadataOptions {
+    pub(crate) only_identifiers: bool,
+    pub(crate) only_basic_information: bool,
+    pub(crate) detailed_url_information: Option<bool>,
+    pub(crate) include_blurhash: Option<bool>,
+    pub(crate) include_notes: Option<bool>,
+    pub(crate) include_milliseconds: Option<bool>,
+    pub(crate) hide_service_keys_tags: Option<bool>,
+    pub(crate) create_new_file_ids: Option<bool>,
+}
+
+impl MetadataOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns the file ids and hashes
+    pub fn only_identifiers(mut self, only_identifiers: bool) -> Self {
+        self.only_identifiers = only_identifiers;
+        self
+    }
+
+    /// Only returns the basic information like size, mime and dimensions
+    pub fn only_basic_information(mut self, only_basic_information: bool) -> Self {
+        self.only_basic_information = only_basic_information;
+        self
+    }
+
+    /// Includes information about the url class of each known url
+    pub fn detailed_url_information(mut self, detailed: bool) -> Self {
+        self.detailed_url_information = Some(detailed);
+        self
+    }
+
+    /// Includes the blurhash of files that have a thumbnail
+    pub fn include_blurhash(mut self, include: bool) -> Self {
+        self.include_blurhash = Some(include);
+        self
+    }
+
+    /// Includes the notes of the files
+    pub fn include_notes(mut self, include: bool) -> Self {
+        self.include_notes = Some(include);
+        self
+    }
+
+    /// Returns timestamps with milliseconds
+    pub fn include_milliseconds(mut self, include: bool) -> Self {
+        self.include_milliseconds = Some(include);
+        self
+    }
+
+    /// Omits the tags mapped by service key
+    pub fn hide_service_keys_tags(mut self, hide: bool) -> Self {
+        self.hide_service_keys_tags = Some(hide);
+        self
+    }
+
+    /// Creates file ids for hashes that are unknown to hydrus
+    pub fn create_new_file_ids(mut self, create: bool) -> Self {
+        self.create_new_file_ids = Some(create);
+        self
+    }
+}
+
 pub struct GetFile;
 
 impl Endpoint for GetFile {
@@ -160,7 +452,138 @@ impl Endpoint for GetFile {
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+pub struct GetThumbnail;
+
+impl Endpoint for GetThumbnail {
+    type Request = ();
+    type Response = ();
+
+    fn path() -> String {
+        String::from("get_files/thumbnail")
+    }
+}
+
+pub struct RenderFile;
+
+impl Endpoint for RenderFile {
+    type Request = ();
+    type Response = ();
+
+    fn path() -> String {
+        String::from("get_files/render")
+    }
+}
+
+/// The format hydrus renders a file to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderFormat {
+    Jpeg = 1,
+    Png = 2,
+    Apng = 23,
+    Webp = 33,
+    AnimatedWebp = 83,
+}
+
+impl RenderFormat {
+    /// Returns the mime type of the rendered file
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            RenderFormat::Jpeg => "image/jpeg",
+            RenderFormat::Png => "image/png",
+            RenderFormat::Apng => "image/apng",
+            RenderFormat::Webp | RenderFormat::AnimatedWebp => "image/webp",
+        }
+    }
+}
+
+/// Options for rendering a file. Without options still images are
+/// rendered as png in their original size
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    pub(crate) format: Option<RenderFormat>,
+    pub(crate) quality: Option<u8>,
+    pub(crate) size: Option<(u32, u32)>,
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the format the file is rendered to
+    pub fn format(mut self, format: RenderFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets the quality of jpeg and webp images (0-100) or
+    /// the compression level of png images (0-9)
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Scales the rendered image to the given size. This doesn't apply to ugoiras
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    pub(crate) fn into_query_args(self) -> Vec<(&'static str, String)> {
+        let mut args = Vec::new();
+
+        if let Some(format) = self.format {
+            args.push(("render_format", (format as u8).to_string()));
+        }
+        if let Some(quality) = self.quality {
+            args.push(("render_quality", quality.to_string()));
+        }
+        if let Some((width, height)) = self.size {
+            args.push(("width", width.to_string()));
+            args.push(("height", height.to_string()));
+        }
+
+        args
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetFilePathResponse {
+    pub path: String,
+    pub filetype: String,
+    pub size: u64,
+}
+
+pub struct GetFilePath;
+
+impl Endpoint for GetFilePath {
+    type Request = ();
+    type Response = GetFilePathResponse;
+
+    fn path() -> String {
+        String::from("get_files/file_path")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetThumbnailPathResponse {
+    pub path: String,
+    #[serde(default)]
+    pub filetype: Option<String>,
+}
+
+pub struct GetThumbnailPath;
+
+impl Endpoint for GetThumbnailPath {
+    type Request = ();
+    type Response = GetThumbnailPathResponse;
+
+    fn path() -> String {
+        String::from("get_files/thumbnail_path")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum SearchQueryEntry {
     Tag(String),
@@ -213,6 +636,12 @@ pub struct FileFullMetadata {
     #[deprecated]
     pub service_names_to_statuses_to_display_tags: HashMap<String, HashMap<String, Vec<String>>>,
     pub service_keys_to_statuses_to_display_tags: HashMap<String, HashMap<String, Vec<String>>>,
+    /// The raw ratings mapped by rating service key
+    #[serde(default)]
+    pub ratings: HashMap<String, Option<RatingValue>>,
+    /// The notes of the file mapped by name
+    #[serde(default)]
+    pub notes: HashMap<String, String>,
 }
 
 pub trait FileMetadataType: Clone + Debug {
@@ -220,6 +649,19 @@ pub trait FileMetadataType: Clone + Debug {
 
     fn only_identifiers() -> bool;
     fn only_basic_information() -> bool;
+
+    /// If the notes of the files should be included
+    fn include_notes() -> bool {
+        false
+    }
+
+    /// Returns the options the metadata is requested with
+    fn options() -> MetadataOptions {
+        MetadataOptions::new()
+            .only_identifiers(Self::only_identifiers())
+            .only_basic_information(Self::only_basic_information())
+            .include_notes(Self::include_notes())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -235,6 +677,10 @@ impl FileMetadataType for FullMetadata {
     fn only_basic_information() -> bool {
         false
     }
+
+    fn include_notes() -> bool {
+        true
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,140 @@
+use crate::error::Error;
+use rand::Rng;
+use reqwest::Method;
+use std::time::Duration;
+
+/// The status hydrus responds with when it is busy or the database is locked
+pub const STATUS_SERVICE_UNAVAILABLE: u16 = 503;
+
+/// Policy that defines if and how failed requests are retried.
+/// Retries are delayed with an exponential backoff.
+///
+/// Example:
+/// ```
+/// use hydrus_api::api_core::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(5)
+///     .initial_backoff(Duration::from_millis(200))
+///     .add_retryable_status(502);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable_statuses: Vec<u16>,
+    retry_connection_errors: bool,
+    idempotent_only: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable_statuses: vec![STATUS_SERVICE_UNAVAILABLE],
+            retry_connection_errors: true,
+            idempotent_only: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a new policy that retries requests up to three times when hydrus
+    /// is unavailable or the connection fails
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of attempts including the first request
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry. Every following retry doubles the delay
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Randomizes the delay between retries to avoid retrying
+    /// multiple requests at the same time
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the http status codes that cause a retry
+    pub fn retryable_statuses(mut self, statuses: Vec<u16>) -> Self {
+        self.retryable_statuses = statuses;
+        self
+    }
+
+    /// Adds a http status code that causes a retry
+    pub fn add_retryable_status(mut self, status: u16) -> Self {
+        self.retryable_statuses.push(status);
+        self
+    }
+
+    /// If failed connections and timeouts should be retried
+    pub fn retry_connection_errors(mut self, retry: bool) -> Self {
+        self.retry_connection_errors = retry;
+        self
+    }
+
+    /// If only idempotent requests (e.g. GET) should be retried.
+    /// This is enabled by default as retrying other requests might
+    /// apply their changes multiple times.
+    pub fn idempotent_only(mut self, idempotent_only: bool) -> Self {
+        self.idempotent_only = idempotent_only;
+        self
+    }
+
+    /// Returns the maximum number of attempts for a request with the given method
+    pub(crate) fn attempts_for(&self, method: &Method) -> u32 {
+        if self.idempotent_only && !method.is_idempotent() {
+            1
+        } else {
+            self.max_attempts
+        }
+    }
+
+    /// Returns if the request should be retried after the given error
+    pub(crate) fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::Reqwest(e) if e.is_connect() || e.is_timeout() => self.retry_connection_errors,
+            e => e
+                .status()
+                .map(|status| self.retryable_statuses.contains(&status))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Returns the delay before the next attempt after the given number of failed attempts
+    pub(crate) fn backoff(&self, failed_attempts: u32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+            half + half.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            backoff
+        }
+    }
+}
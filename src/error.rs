@@ -1,18 +1,62 @@
 use crate::api_core::common::FileIdentifier;
+use crate::api_core::endpoints::access_management::Permission;
 use std::error::Error as StdError;
 use std::fmt;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// An error response returned by hydrus
+#[derive(Clone, Debug)]
+pub struct HydrusError {
+    /// The http status code of the response
+    pub status: u16,
+    /// The error message
+    pub error: String,
+    /// The type of the exception raised by hydrus
+    pub exception_type: Option<String>,
+}
+
+impl fmt::Display for HydrusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(exception_type) = &self.exception_type {
+            write!(f, "({}) {}: {}", self.status, exception_type, self.error)
+        } else {
+            write!(f, "({}) {}", self.status, self.error)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     Reqwest(reqwest::Error),
+    Io(std::io::Error),
     Hydrus(String),
+    /// The request was malformed (400)
+    BadRequest(HydrusError),
+    /// The access or session key is missing or has expired (401, 419)
+    Unauthorized(HydrusError),
+    /// The access key is invalid or doesn't have the required permissions (403)
+    Forbidden(HydrusError),
+    /// The requested file or resource doesn't exist (404)
+    NotFound(HydrusError),
+    /// Hydrus is busy or the database is locked (503)
+    ServiceUnavailable(HydrusError),
+    /// Hydrus failed to process the request (5xx)
+    ServerError(HydrusError),
+    /// Any other error response of hydrus
+    Api(HydrusError),
     InvalidServiceType(String),
+    InvalidSortType(u8),
+    InvalidPermission(u32),
+    /// The access key lacks the permission required by the request.
+    /// Only returned if the client checks permissions before sending requests
+    MissingPermission(Permission),
     ImportVetoed(String),
     ImportFailed(String),
     FileNotFound(FileIdentifier),
     InvalidMime(String),
+    /// The response of hydrus doesn't match the request
+    InvalidResponse(String),
     BuildError(String),
     Serialization(String),
     Deserialization(String),
@@ -22,14 +66,28 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Reqwest(e) => e.fmt(f),
+            Self::Io(e) => write!(f, "IO error: {e}"),
             Self::Hydrus(msg) => msg.fmt(f),
+            Self::BadRequest(e) => write!(f, "Bad request {e}"),
+            Self::Unauthorized(e) => write!(f, "Unauthorized {e}"),
+            Self::Forbidden(e) => write!(f, "Forbidden {e}"),
+            Self::NotFound(e) => write!(f, "Not found {e}"),
+            Self::ServiceUnavailable(e) => write!(f, "Service unavailable {e}"),
+            Self::ServerError(e) => write!(f, "Server error {e}"),
+            Self::Api(e) => write!(f, "API error {e}"),
             Self::InvalidServiceType(service_type) => {
                 write!(f, "Invalid Service Type '{}'", service_type)
             }
+            Self::InvalidSortType(id) => write!(f, "Invalid sort type {id}"),
+            Self::InvalidPermission(id) => write!(f, "Invalid permission {id}"),
+            Self::MissingPermission(permission) => {
+                write!(f, "Missing permission '{}'", permission)
+            }
             Self::ImportFailed(msg) => write!(f, "File import failed: {msg}"),
             Self::ImportVetoed(msg) => write!(f, "File import vetoed: {msg}"),
             Self::FileNotFound(id) => write!(f, "File {:?} not found", id),
             Self::InvalidMime(mime) => write!(f, "Failed to parse invalid mime {mime}"),
+            Self::InvalidResponse(msg) => write!(f, "Invalid response: {msg}"),
             Self::BuildError(error) => write!(f, "Build error {error}"),
             Self::Serialization(msg) => write!(f, "Failed to serialize request {msg}"),
             Self::Deserialization(msg) => write!(f, "Failed to deserialize request {msg}"),
@@ -37,10 +95,50 @@ impl fmt::Display for Error {
     }
 }
 
+impl Error {
+    /// Creates the error variant matching the status of the hydrus error
+    pub(crate) fn from_hydrus_error(error: HydrusError) -> Self {
+        match error.status {
+            400 => Self::BadRequest(error),
+            401 | 419 => Self::Unauthorized(error),
+            403 => Self::Forbidden(error),
+            404 => Self::NotFound(error),
+            503 => Self::ServiceUnavailable(error),
+            500..=599 => Self::ServerError(error),
+            _ => Self::Api(error),
+        }
+    }
+
+    /// Returns the error response of hydrus if the error was returned by the API
+    pub fn hydrus_error(&self) -> Option<&HydrusError> {
+        match self {
+            Self::BadRequest(e)
+            | Self::Unauthorized(e)
+            | Self::Forbidden(e)
+            | Self::NotFound(e)
+            | Self::ServiceUnavailable(e)
+            | Self::ServerError(e)
+            | Self::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns the http status code if the error was returned by the API
+    pub fn status(&self) -> Option<u16> {
+        self.hydrus_error()
+            .map(|e| e.status)
+            .or_else(|| match self {
+                Self::Reqwest(e) => e.status().map(|s| s.as_u16()),
+                _ => None,
+            })
+    }
+}
+
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             Self::Reqwest(e) => e.source(),
+            Self::Io(e) => Some(e),
             _ => None,
         }
     }
@@ -51,3 +149,9 @@ impl From<reqwest::Error> for Error {
         Self::Reqwest(e)
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -90,6 +90,8 @@ pub use wrapper::hydrus::Hydrus;
 
 pub mod api_core;
 pub mod error;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod utils;
 pub mod wrapper;
 
@@ -0,0 +1,2188 @@
+use crate::api_core::endpoints::access_management::Permission;
+use crate::api_core::endpoints::adding_files::{
+    STATUS_IMPORT_ALREADY_EXISTS, STATUS_IMPORT_FAILED, STATUS_IMPORT_PREVIOUSLY_DELETED,
+    STATUS_IMPORT_SUCCESS,
+};
+use crate::api_core::endpoints::adding_urls::{
+    URL_TYPE_FILE, URL_TYPE_GALLERY, URL_TYPE_POST, URL_TYPE_WATCHABLE,
+};
+use crate::api_core::endpoints::editing_ratings::{Rating, RatingValue};
+use crate::api_core::endpoints::searching_and_fetching_files::SortType;
+use crate::mock::search::{glob_matches, searchable_tags, sort_files, Predicate};
+use crate::mock::store::*;
+use crate::wrapper::service::ServiceType;
+use crate::Client;
+use bytes::Bytes;
+use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use hyper::http::request::Parts;
+use hyper::{Body, HeaderMap, Method, Request, Response, StatusCode};
+use rand::seq::SliceRandom;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::convert::{Infallible, TryFrom};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+const ACCESS_KEY_HEADER: &str = "Hydrus-Client-API-Access-Key";
+const SESSION_KEY_HEADER: &str = "Hydrus-Client-API-Session-Key";
+
+#[cfg(feature = "cbor")]
+const CONTENT_TYPE_CONTENT: &str = "application/cbor";
+#[cfg(feature = "json")]
+const CONTENT_TYPE_CONTENT: &str = "application/json";
+
+/// The maximum length of a request uri. Hydrus rejects longer request lines.
+const MAX_URI_LENGTH: usize = 16384;
+
+/// The paths that can be requested while the database is locked
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "session_key",
+    "verify_access_key",
+    "manage_database/lock_off",
+];
+
+const API_VERSION: u32 = 31;
+const HYDRUS_VERSION: u32 = 478;
+
+/// The thumbnail returned for files that aren't images. It's a transparent 1x1 png.
+const PLACEHOLDER_THUMBNAIL: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F, 0x15, 0xC4,
+    0x89, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+    0x42, 0x60, 0x82,
+];
+
+type HandlerResult<T = Response<Body>> = std::result::Result<T, MockError>;
+
+/// Handles a single request to the mock
+pub(crate) async fn handle(
+    store: Arc<Mutex<MockStore>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let uri_length = parts.uri.to_string().len();
+    let response = match hyper::body::to_bytes(body).await {
+        Ok(_) if uri_length > MAX_URI_LENGTH => Err(MockError::new(
+            StatusCode::URI_TOO_LONG,
+            format!("The request uri is {} characters long", uri_length),
+        )),
+        Ok(body) => {
+            let request = MockRequest::new(parts, body);
+            let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+            tracing::trace!("Mock request {} {}", request.method, request.path);
+            route(&mut store, &request)
+        }
+        Err(e) => Err(MockError::bad_request(e)),
+    };
+
+    Ok(response.unwrap_or_else(MockError::into_response))
+}
+
+/// Calls the handler for the requested endpoint
+fn route(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    *store
+        .request_counts
+        .entry(request.path.clone())
+        .or_insert(0) += 1;
+    if request.credential(ACCESS_KEY_HEADER).is_some() {
+        *store
+            .access_key_request_counts
+            .entry(request.path.clone())
+            .or_insert(0) += 1;
+    }
+    if let Some((count, status)) = store.failing_requests.take() {
+        if count > 1 {
+            store.failing_requests = Some((count - 1, status));
+        }
+        if count > 0 {
+            let status = StatusCode::from_u16(status).map_err(MockError::server_error)?;
+            return Err(MockError::new(status, "Failed by the mock"));
+        }
+    }
+    match (&request.method, request.path.as_str()) {
+        (&Method::GET, "api_version") => return api_version(),
+        (&Method::GET, "request_new_permissions") => {
+            return request_new_permissions(store, request)
+        }
+        _ => {}
+    }
+    let access_key = authenticate(store, request)?;
+    if store.database_locked && !ALLOWED_WHILE_LOCKED.contains(&request.path.as_str()) {
+        return Err(MockError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The client's database is locked!",
+        ));
+    }
+
+    match (&request.method, request.path.as_str()) {
+        (&Method::GET, "session_key") => session_key(store, access_key),
+        (&Method::GET, "verify_access_key") => verify_access_key(store, &access_key),
+        (&Method::GET, "get_services") => get_services(store),
+        (&Method::GET, "get_service") => get_service(store, request),
+        (&Method::POST, "add_files/add_file") => add_file(store, request),
+        (&Method::POST, "add_files/delete_files") => modify_files(store, request, |file| {
+            file.is_trashed = true;
+            file.time_deleted = Some(now());
+        }),
+        (&Method::POST, "add_files/undelete_files") => modify_files(store, request, |file| {
+            file.is_trashed = false;
+            file.time_deleted = None;
+        }),
+        (&Method::POST, "add_files/archive_files") => {
+            modify_files(store, request, |file| file.is_inbox = false)
+        }
+        (&Method::POST, "add_files/unarchive_files") => {
+            modify_files(store, request, |file| file.is_inbox = true)
+        }
+        (&Method::GET, "add_tags/clean_tags") => clean_tags(request),
+        (&Method::POST, "add_tags/add_tags") => add_tags(store, request),
+        (&Method::GET, "add_tags/search_tags") => search_tags(store, request),
+        (&Method::GET, "add_tags/get_siblings_and_parents") => {
+            get_siblings_and_parents(store, request)
+        }
+        (&Method::GET, "add_urls/get_url_files") => get_url_files(store, request),
+        (&Method::GET, "add_urls/get_url_info") => get_url_info(store, request),
+        (&Method::POST, "add_urls/add_url") => add_url(store, request),
+        (&Method::POST, "add_urls/associate_url") => associate_url(store, request),
+        (&Method::POST, "add_notes/set_notes") => set_notes(store, request),
+        (&Method::POST, "add_notes/delete_notes") => delete_notes(store, request),
+        (&Method::POST, "edit_ratings/set_rating") => set_rating(store, request),
+        (&Method::GET, "get_files/search_files") => search_files(store, request),
+        (&Method::GET, "get_files/file_metadata") => file_metadata(store, request),
+        (&Method::GET, "get_files/file") => get_file(store, request),
+        (&Method::GET, "get_files/thumbnail") => get_thumbnail(store, request),
+        (&Method::GET, "get_files/file_path") => get_file_path(store, request),
+        (&Method::GET, "get_files/render") => render_file(store, request),
+        (&Method::GET, "get_files/thumbnail_path") => get_thumbnail_path(store, request),
+        (&Method::GET, "manage_cookies/get_cookies") => get_cookies(store, request),
+        (&Method::POST, "manage_cookies/set_cookies") => set_cookies(store, request),
+        (&Method::POST, "manage_headers/set_user_agent") => set_user_agent(store, request),
+        (&Method::GET, "manage_file_relationships/get_file_relationships") => {
+            get_file_relationships(store, request)
+        }
+        (&Method::GET, "manage_file_relationships/get_potentials_count") => {
+            let pairs = potential_pairs(store, request)?;
+            content(&json!({ "potential_duplicates_count": pairs.len() }))
+        }
+        (&Method::GET, "manage_file_relationships/get_potential_pairs") => {
+            let mut pairs = potential_pairs(store, request)?;
+            if let Some(max) = request.param("max_num_pairs").and_then(|m| m.parse().ok()) {
+                pairs.truncate(max);
+            }
+            content(&json!({ "potential_duplicate_pairs": pairs }))
+        }
+        (&Method::GET, "manage_file_relationships/get_random_potentials") => {
+            get_random_potentials(store, request)
+        }
+        (&Method::POST, "manage_file_relationships/set_file_relationships") => {
+            set_file_relationships(store, request)
+        }
+        (&Method::POST, "manage_file_relationships/set_kings") => set_kings(store, request),
+        (&Method::POST, "manage_file_relationships/remove_potentials") => {
+            let selection: FileSelectionBody = request.body()?;
+            let hashes = selection.hashes(store)?;
+            store
+                .potential_duplicates
+                .retain(|(a, b)| !hashes.contains(a) && !hashes.contains(b));
+            empty()
+        }
+        (&Method::GET, "manage_pages/get_pages") => get_pages(store),
+        (&Method::GET, "manage_pages/get_page_info") => get_page_info(store, request),
+        (&Method::POST, "manage_pages/focus_page") => focus_page(store, request),
+        (&Method::POST, "manage_pages/add_files") => add_files_to_page(store, request),
+        (&Method::POST, "manage_database/lock_on") => lock_database(store, true),
+        (&Method::POST, "manage_database/lock_off") => lock_database(store, false),
+        (&Method::POST, "manage_database/force_commit") => empty(),
+        (&Method::GET, "manage_database/mr_bones") => mr_bones(store),
+        (&Method::GET, "manage_database/get_client_options") => get_client_options(),
+        (&Method::GET, "manage_services/get_pending_counts") => get_pending_counts(store),
+        (&Method::POST, "manage_services/commit_pending") => commit_pending(store, request),
+        (&Method::POST, "manage_services/forget_pending") => forget_pending(store, request),
+        _ => Err(MockError::new(
+            StatusCode::NOT_FOUND,
+            "This is not a valid path",
+        )),
+    }
+}
+
+/// Checks the access or session key of the request and the permissions
+/// required for the endpoint. Returns the access key the request is authenticated with
+fn authenticate(store: &MockStore, request: &MockRequest) -> HandlerResult<String> {
+    let access_key = if let Some(session_key) = request.credential(SESSION_KEY_HEADER) {
+        match store.session_keys.get(session_key) {
+            Some(access_key) if request.path != "session_key" => access_key.clone(),
+            _ => {
+                return Err(MockError::new(
+                    StatusCode::from_u16(419).unwrap(),
+                    "Did not find an entry for that session key!",
+                ))
+            }
+        }
+    } else if let Some(access_key) = request.credential(ACCESS_KEY_HEADER) {
+        access_key.to_string()
+    } else {
+        return Err(MockError::new(
+            StatusCode::UNAUTHORIZED,
+            "No hydrus key or session key provided!",
+        ));
+    };
+    let permissions = store.permissions_of(&access_key).ok_or_else(|| {
+        MockError::new(
+            StatusCode::FORBIDDEN,
+            "Did not find an entry for that access key!",
+        )
+    })?;
+
+    match Permission::required_for(&request.path) {
+        Some(permission) if !permissions.contains(&permission.id()) => Err(MockError::new(
+            StatusCode::FORBIDDEN,
+            format!(
+                "The permission {} is not granted for this access key!",
+                permission.id()
+            ),
+        )),
+        _ => Ok(access_key),
+    }
+}
+
+fn api_version() -> HandlerResult {
+    content(&json!({
+        "version": API_VERSION,
+        "hydrus_version": HYDRUS_VERSION,
+    }))
+}
+
+fn session_key(store: &mut MockStore, access_key: String) -> HandlerResult {
+    let session_key = random_key();
+    store.session_keys.insert(session_key.clone(), access_key);
+
+    content(&json!({ "session_key": session_key }))
+}
+
+fn verify_access_key(store: &MockStore, access_key: &str) -> HandlerResult {
+    let permissions = store.permissions_of(access_key).unwrap_or_default();
+
+    content(&json!({
+        "basic_permissions": permissions,
+        "permits_everything": false,
+        "human_description": format!("API Permissions (mock): {:?}", permissions),
+    }))
+}
+
+fn request_new_permissions(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let name = request.required_param("name")?;
+    let permissions: Vec<u32> = request
+        .query_object("basic_permissions")?
+        .unwrap_or_default();
+    if let Some(id) = permissions
+        .iter()
+        .find(|id| Permission::try_from(**id).is_err())
+    {
+        return Err(MockError::bad_request(format!(
+            "The permission {} is not valid",
+            id
+        )));
+    }
+    tracing::debug!("Registering access key '{}' with {:?}", name, permissions);
+    let access_key = random_key();
+    store
+        .requested_access_keys
+        .insert(access_key.clone(), permissions);
+
+    content(&json!({ "access_key": access_key }))
+}
+
+fn get_services(store: &MockStore) -> HandlerResult {
+    let mut services: HashMap<String, Vec<Value>> = HashMap::new();
+
+    // hydrus doesn't list rating services in the grouped response
+    for service in store.services.iter().filter(|s| !s.is_rating_service()) {
+        services
+            .entry(service.service_type.to_string())
+            .or_default()
+            .push(json!({
+                "name": service.name,
+                "service_key": service.key,
+            }));
+    }
+
+    content(&services)
+}
+
+fn get_service(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let service = if let Some(key) = request.param("service_key") {
+        store.service_by_key(key)
+    } else {
+        store.service_by_name(request.required_param("service_name")?)
+    }
+    .ok_or_else(|| MockError::new(StatusCode::NOT_FOUND, "Could not find that service!"))?;
+    let mut info = json!({
+        "name": service.name,
+        "service_key": service.key,
+        "type": service.service_type.id(),
+        "type_pretty": service.type_pretty(),
+    });
+    if service.is_rating_service() {
+        info["star_shape"] = json!("circle");
+    }
+    if service.service_type == ServiceType::NumericalRatings {
+        info["min_stars"] = json!(0);
+        info["max_stars"] = json!(5);
+    }
+
+    content(&json!({ "service": info }))
+}
+
+fn add_file(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let bytes = if request.header(CONTENT_TYPE.as_str()) == Some("application/octet-stream") {
+        request.body.to_vec()
+    } else {
+        let body: AddFileBody = request.body()?;
+        std::fs::read(&body.path).map_err(|e| {
+            MockError::bad_request(format!("Could not read the file at {}: {}", body.path, e))
+        })?
+    };
+    let file = MockFile::new(bytes);
+    let hash = file.hash.clone();
+
+    let (status, note) = if detect_mime(&file.bytes).is_none() {
+        (STATUS_IMPORT_FAILED, "Unknown filetype!")
+    } else {
+        match store.file(&hash) {
+            Some(existing) if existing.is_trashed => (
+                STATUS_IMPORT_PREVIOUSLY_DELETED,
+                "File was previously deleted.",
+            ),
+            Some(_) => (
+                STATUS_IMPORT_ALREADY_EXISTS,
+                "File already in the database.",
+            ),
+            None => {
+                store.add_file(file);
+                (STATUS_IMPORT_SUCCESS, "")
+            }
+        }
+    };
+
+    content(&json!({
+        "status": status,
+        "hash": hash,
+        "note": note,
+    }))
+}
+
+/// Applies a modification to all selected files
+fn modify_files<F: Fn(&mut MockFile)>(
+    store: &mut MockStore,
+    request: &MockRequest,
+    modification: F,
+) -> HandlerResult {
+    let selection: FileSelectionBody = request.body()?;
+    selection.file_service(store)?;
+
+    for hash in selection.hashes(store)? {
+        if let Some(file) = store.file_mut(&hash) {
+            modification(file);
+        }
+    }
+
+    empty()
+}
+
+fn clean_tags(request: &MockRequest) -> HandlerResult {
+    let tags: Vec<String> = request.required_query_object("tags")?;
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|t| clean_tag(t))
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+
+    content(&json!({ "tags": tags }))
+}
+
+fn add_tags(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: AddTagsBody = request.body()?;
+    let mut mappings = Vec::new();
+
+    for (name, tags) in body.service_names_to_tags {
+        mappings.push((tag_service_key(store, None, Some(&name))?, 0, tags));
+    }
+    for (key, tags) in body.service_keys_to_tags {
+        mappings.push((tag_service_key(store, Some(&key), None)?, 0, tags));
+    }
+    let actions = body
+        .service_names_to_actions_to_tags
+        .into_iter()
+        .map(|(name, actions)| (None, Some(name), actions))
+        .chain(
+            body.service_keys_to_actions_to_tags
+                .into_iter()
+                .map(|(key, actions)| (Some(key), None, actions)),
+        );
+    for (key, name, actions) in actions {
+        let service_key = tag_service_key(store, key.as_deref(), name.as_deref())?;
+
+        for (action, tags) in actions {
+            let action = action
+                .parse::<u8>()
+                .ok()
+                .filter(|a| *a <= 5)
+                .ok_or_else(|| MockError::bad_request(format!("Invalid tag action {}", action)))?;
+            mappings.push((service_key.clone(), action, tags));
+        }
+    }
+
+    let hashes = body.hash.into_iter().chain(body.hashes);
+    for hash in hashes {
+        if let Some(file) = store.file_mut(&hash) {
+            for (service_key, action, tags) in &mappings {
+                for tag in tags {
+                    apply_tag_action(file, service_key, *action, &clean_tag(tag));
+                }
+            }
+        }
+    }
+
+    empty()
+}
+
+fn search_tags(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let search = clean_tag(request.required_param("search")?);
+    let tag_services = tag_domain(
+        store,
+        request.param("tag_service_key"),
+        request.param("tag_service_name"),
+    )?;
+    if search.trim_matches('*').is_empty() {
+        return content(&json!({ "tags": [] }));
+    }
+    let pattern = if search.ends_with('*') {
+        search
+    } else {
+        format!("{}*", search)
+    };
+    let mut counts: HashMap<String, u64> = HashMap::new();
+
+    for file in store.files.iter().filter(|f| !f.is_trashed) {
+        for tag in searchable_tags(file, &tag_services) {
+            let subtag = tag.split_once(':').map(|(_, s)| s);
+            let matches = glob_matches(&pattern, &tag)
+                || (!pattern.contains(':') && subtag.is_some_and(|s| glob_matches(&pattern, s)));
+            if matches {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+    }
+    let mut tags: Vec<(String, u64)> = counts.into_iter().collect();
+    tags.sort_by(|(tag_a, count_a), (tag_b, count_b)| {
+        count_b.cmp(count_a).then_with(|| tag_a.cmp(tag_b))
+    });
+    let tags: Vec<Value> = tags
+        .into_iter()
+        .map(|(value, count)| json!({ "value": value, "count": count }))
+        .collect();
+
+    content(&json!({ "tags": tags }))
+}
+
+fn get_siblings_and_parents(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let tags: Vec<String> = request.required_query_object("tags")?;
+    let no_siblings = HashMap::new();
+    let no_parents = Vec::new();
+    let mut response = Map::new();
+
+    for tag in tags {
+        let tag = clean_tag(&tag);
+        let mut services = Map::new();
+
+        for service in store.services.iter().filter(|s| s.is_tag_service()) {
+            let siblings = store.tag_siblings.get(&service.key).unwrap_or(&no_siblings);
+            let parents = store.tag_parents.get(&service.key).unwrap_or(&no_parents);
+            let ideal = ideal_tag(siblings, &tag);
+            let mut tag_siblings: Vec<&String> = siblings
+                .keys()
+                .filter(|t| ideal_tag(siblings, t) == ideal)
+                .chain([&tag, &ideal])
+                .collect();
+            tag_siblings.sort();
+            tag_siblings.dedup();
+
+            services.insert(
+                service.key.clone(),
+                json!({
+                    "siblings": tag_siblings,
+                    "ideal_tag": ideal,
+                    "descendants": related_tags(parents, &ideal, false),
+                    "ancestors": related_tags(parents, &ideal, true),
+                }),
+            );
+        }
+        response.insert(tag, Value::Object(services));
+    }
+
+    content(&json!({ "tags": response }))
+}
+
+fn get_url_files(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let normalised_url = normalise_url(request.required_param("url")?)?;
+    let statuses: Vec<Value> = store
+        .files
+        .iter()
+        .filter(|f| f.known_urls.contains(&normalised_url))
+        .map(|f| {
+            let (status, note) = if f.is_trashed {
+                (
+                    STATUS_IMPORT_PREVIOUSLY_DELETED,
+                    "url recognised: file was deleted",
+                )
+            } else {
+                (
+                    STATUS_IMPORT_ALREADY_EXISTS,
+                    "url recognised: file is in the database",
+                )
+            };
+            json!({
+                "status": status,
+                "hash": f.hash,
+                "note": note,
+            })
+        })
+        .collect();
+
+    content(&json!({
+        "normalised_url": normalised_url,
+        "url_file_statuses": statuses,
+    }))
+}
+
+fn get_url_info(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let url = request.required_param("url")?;
+
+    content(&url_info(store, url)?)
+}
+
+fn add_url(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: AddUrlBody = request.body()?;
+    let normalised_url = normalise_url(&body.url)?;
+
+    if let Some(page_key) = &body.destination_page_key {
+        store.root_page.find(page_key).ok_or_else(page_not_found)?;
+    } else if let Some(page_name) = &body.destination_page_name {
+        if store.root_page.find_by_name_mut(page_name).is_none() {
+            store.add_page(MockPage::new(page_name, PAGE_TYPE_URL_DOWNLOADER));
+        }
+    }
+
+    let mut additional_tags = Vec::new();
+    for (name, tags) in body.service_names_to_additional_tags {
+        additional_tags.push((tag_service_key(store, None, Some(&name))?, tags));
+    }
+    for (key, tags) in body.service_keys_to_additional_tags {
+        additional_tags.push((tag_service_key(store, Some(&key), None)?, tags));
+    }
+    for file in store
+        .files
+        .iter_mut()
+        .filter(|f| f.known_urls.contains(&normalised_url))
+    {
+        for (service_key, tags) in &additional_tags {
+            for tag in tags {
+                apply_tag_action(file, service_key, 0, &clean_tag(tag));
+            }
+        }
+    }
+    store.imported_urls.push(normalised_url.clone());
+
+    content(&json!({
+        "human_result_text": format!("\"{}\" URL added successfully.", normalised_url),
+        "normalised_url": normalised_url,
+    }))
+}
+
+fn associate_url(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: AssociateUrlBody = request.body()?;
+    let urls_to_add = body
+        .url_to_add
+        .into_iter()
+        .chain(body.urls_to_add)
+        .map(|u| normalise_url(&u))
+        .collect::<HandlerResult<Vec<String>>>()?;
+    let urls_to_delete = body
+        .url_to_delete
+        .into_iter()
+        .chain(body.urls_to_delete)
+        .map(|u| normalise_url(&u))
+        .collect::<HandlerResult<Vec<String>>>()?;
+
+    for hash in body.hash.into_iter().chain(body.hashes) {
+        if let Some(file) = store.file_mut(&hash) {
+            file.known_urls.retain(|u| !urls_to_delete.contains(u));
+            for url in &urls_to_add {
+                if !file.known_urls.contains(url) {
+                    file.known_urls.push(url.clone());
+                }
+            }
+        }
+    }
+
+    empty()
+}
+
+fn set_notes(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: NotesBody = request.body()?;
+    let merge_cleverly = body.merge_cleverly.unwrap_or(false);
+    let extend = body.extend_existing_note_if_possible.unwrap_or(true);
+    let conflict_resolution = body.conflict_resolution.unwrap_or(3);
+    let file = body.file_mut(store)?;
+    let mut set_notes = HashMap::new();
+
+    for (name, note) in &body.notes {
+        let (name, note) = if !merge_cleverly {
+            (name.clone(), note.clone())
+        } else {
+            match file.notes.get(name) {
+                None if file.notes.values().any(|n| n.contains(note.as_str())) => continue,
+                None => (name.clone(), note.clone()),
+                Some(existing) if existing.contains(note.as_str()) => continue,
+                Some(existing) if extend && note.starts_with(existing.as_str()) => {
+                    (name.clone(), note.clone())
+                }
+                Some(existing) => match conflict_resolution {
+                    0 => (name.clone(), note.clone()),
+                    1 => continue,
+                    2 => (name.clone(), format!("{}\n\n{}", existing, note)),
+                    _ => {
+                        let new_name = (1..)
+                            .map(|i| format!("{} ({})", name, i))
+                            .find(|n| !file.notes.contains_key(n))
+                            .unwrap();
+                        (new_name, note.clone())
+                    }
+                },
+            }
+        };
+        file.notes.insert(name.clone(), note.clone());
+        set_notes.insert(name, note);
+    }
+
+    content(&json!({ "notes": set_notes }))
+}
+
+fn delete_notes(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: NotesBody = request.body()?;
+    let file = body.file_mut(store)?;
+
+    for name in &body.note_names {
+        file.notes.remove(name);
+    }
+
+    empty()
+}
+
+fn set_rating(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: SetRatingBody = request.body()?;
+    let service_type = store
+        .service_by_key(&body.rating_service_key)
+        .filter(|s| s.is_rating_service())
+        .map(|s| s.service_type.clone())
+        .ok_or_else(|| {
+            MockError::bad_request(format!(
+                "Could not find a rating service with the key {}",
+                body.rating_service_key
+            ))
+        })?;
+    let rating = match (service_type, body.rating) {
+        (ServiceType::LikeRatings, None) => Rating::LikeDislike(None),
+        (ServiceType::LikeRatings, Some(RatingValue::Bool(like))) => {
+            Rating::LikeDislike(Some(like))
+        }
+        (ServiceType::NumericalRatings, None) => Rating::Numerical(None),
+        (ServiceType::NumericalRatings, Some(RatingValue::Number(stars))) => {
+            Rating::Numerical(Some(stars))
+        }
+        (ServiceType::IncDecRatings, Some(RatingValue::Number(count))) => Rating::IncDec(count),
+        (service_type, rating) => {
+            return Err(MockError::bad_request(format!(
+                "The rating {:?} is not valid for a {} service",
+                rating, service_type
+            )))
+        }
+    };
+    let file = file_mut(store, body.hash.as_ref(), body.file_id)?;
+    file.ratings.insert(body.rating_service_key, rating);
+
+    empty()
+}
+
+fn search_files(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let query: Vec<QueryEntry> = request.required_query_object("tags")?;
+    let predicates: Vec<Predicate> = query.into_iter().map(QueryEntry::into_predicate).collect();
+    let file_domain = file_domain(
+        store,
+        request.param("file_service_key"),
+        request.param("file_service_name"),
+    )?;
+    let tag_services = tag_domain(
+        store,
+        request.param("tag_service_key"),
+        request.param("tag_service_name"),
+    )?;
+
+    let mut files: Vec<&MockFile> = if predicates.is_empty() {
+        Vec::new()
+    } else {
+        store
+            .files
+            .iter()
+            .filter(|f| file_domain.contains(f))
+            .filter(|f| {
+                let tags = searchable_tags(f, &tag_services);
+                predicates.iter().all(|p| p.matches(f, &tags))
+            })
+            .collect()
+    };
+    let sort_type = match request.param("file_sort_type") {
+        Some(sort_type) => sort_type
+            .parse::<u8>()
+            .map_err(MockError::bad_request)
+            .and_then(|id| SortType::try_from(id).map_err(MockError::bad_request))?,
+        None => SortType::ImportTime,
+    };
+    let ascending = request
+        .param("file_sort_asc")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(true);
+    sort_files(&mut files, sort_type, ascending);
+
+    if let Some(limit) = predicates.iter().filter_map(Predicate::limit).min() {
+        files.truncate(limit);
+    }
+    let return_hashes: bool = request.query_object("return_hashes")?.unwrap_or(false);
+    let return_file_ids: bool = request
+        .query_object("return_file_ids")?
+        .unwrap_or(!return_hashes);
+
+    let mut response = Map::new();
+    if return_file_ids {
+        let file_ids: Vec<u64> = files.iter().map(|f| f.file_id).collect();
+        response.insert("file_ids".into(), json!(file_ids));
+    }
+    if return_hashes {
+        let hashes: Vec<&String> = files.iter().map(|f| &f.hash).collect();
+        response.insert("hashes".into(), json!(hashes));
+    }
+
+    content(&response)
+}
+
+fn file_metadata(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let flags = MetadataFlags::from_request(request)?;
+    let create_new_file_ids: bool = request
+        .query_object("create_new_file_ids")?
+        .unwrap_or(false);
+
+    let mut metadata = Vec::new();
+    if let Some(file_ids) = request.query_object::<Vec<u64>>("file_ids")? {
+        for file_id in file_ids {
+            let file = store.file_by_id(file_id).ok_or_else(file_not_found)?;
+            metadata.push(metadata_for_file(store, file, &flags));
+        }
+    } else if let Some(hashes) = request.query_object::<Vec<String>>("hashes")? {
+        for hash in hashes {
+            if let Some(file) = store.file(&hash) {
+                metadata.push(metadata_for_file(store, file, &flags));
+            } else if create_new_file_ids {
+                let file_id = store.unknown_file_id(&hash);
+                metadata.push(json!({ "file_id": file_id, "hash": hash }));
+            }
+        }
+    } else {
+        let file = request_file(store, request)?;
+        metadata.push(metadata_for_file(store, file, &flags));
+    }
+
+    content(&json!({ "metadata": metadata }))
+}
+
+fn get_file(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let file = request_file(store, request)?;
+
+    let range = request
+        .header(RANGE.as_str())
+        .filter(|_| !store.ignore_range_requests);
+
+    file_content(&file.bytes, &file.mime, range)
+}
+
+fn get_thumbnail(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let file = request_file(store, request)?;
+    let (bytes, mime) = thumbnail(file);
+
+    file_content(bytes, mime, None)
+}
+
+/// Validates the render options. The mock doesn't encode files and returns
+/// the original content with the mime type of the requested format
+fn render_file(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let file = request_file(store, request)?;
+    if !file.mime.starts_with("image/") {
+        return Err(MockError::bad_request(format!(
+            "Files of type {} can't be rendered",
+            file.mime
+        )));
+    }
+    let mime = match request.param("render_format") {
+        None | Some("2") => "image/png",
+        Some("1") => "image/jpeg",
+        Some("23") => "image/apng",
+        Some("33") | Some("83") => "image/webp",
+        Some(format) => {
+            return Err(MockError::bad_request(format!(
+                "Invalid render format {}",
+                format
+            )))
+        }
+    };
+    if let Some(quality) = request.param("render_quality") {
+        quality
+            .parse::<u8>()
+            .map_err(|_| MockError::bad_request("Invalid render quality"))?;
+    }
+    if request.param("width").is_some() != request.param("height").is_some() {
+        return Err(MockError::bad_request(
+            "Width and height need to be provided together",
+        ));
+    }
+
+    file_content(&file.bytes, mime, None)
+}
+
+fn get_file_path(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let file = request_file(store, request)?;
+    let path = stored_file_path(
+        store,
+        &format!("f{}", hash_prefix(file)),
+        &format!("{}{}", file.hash, file.ext),
+        &file.bytes,
+    )?;
+
+    content(&json!({
+        "path": path,
+        "filetype": file.mime,
+        "size": file.bytes.len(),
+    }))
+}
+
+fn get_thumbnail_path(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let file = request_file(store, request)?;
+    let (bytes, mime) = thumbnail(file);
+    let path = stored_file_path(
+        store,
+        &format!("t{}", hash_prefix(file)),
+        &format!("{}.thumbnail", file.hash),
+        bytes,
+    )?;
+
+    content(&json!({ "path": path, "filetype": mime }))
+}
+
+/// Returns the thumbnail of a file with its mime type
+fn thumbnail(file: &MockFile) -> (&[u8], &str) {
+    if file.mime.starts_with("image/") {
+        (&file.bytes, &file.mime)
+    } else {
+        (PLACEHOLDER_THUMBNAIL, "image/png")
+    }
+}
+
+/// Returns the first two characters of the hash that hydrus uses
+/// to distribute files across folders
+fn hash_prefix(file: &MockFile) -> &str {
+    file.hash.get(..2).unwrap_or(&file.hash)
+}
+
+/// Returns the path of a file in the file storage of the mock. The file is
+/// only written if the store has a files directory.
+fn stored_file_path(
+    store: &MockStore,
+    folder: &str,
+    name: &str,
+    bytes: &[u8],
+) -> HandlerResult<String> {
+    let directory = match &store.files_directory {
+        Some(directory) => directory.join(folder),
+        None => return Ok(format!("/hydrus/client_files/{}/{}", folder, name)),
+    };
+    let path = directory.join(name);
+    std::fs::create_dir_all(&directory)
+        .and_then(|_| std::fs::write(&path, bytes))
+        .map_err(MockError::server_error)?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn get_cookies(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let domain = request.required_param("domain")?.trim_start_matches('.');
+    let cookies: Vec<Value> = store
+        .cookies
+        .iter()
+        .filter(|c| {
+            let cookie_domain = c.domain.trim_start_matches('.');
+            cookie_domain == domain
+                || domain.ends_with(&format!(".{}", cookie_domain))
+                || cookie_domain.ends_with(&format!(".{}", domain))
+        })
+        .map(|c| json!([c.name, c.value, c.domain, c.path, c.expires]))
+        .collect();
+
+    content(&json!({ "cookies": cookies }))
+}
+
+fn set_cookies(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: SetCookiesBody = request.body()?;
+
+    for cookie in body.cookies {
+        let string_at = |i: usize| {
+            cookie
+                .get(i)
+                .and_then(Value::as_str)
+                .map(String::from)
+                .ok_or_else(|| MockError::bad_request("Invalid cookie"))
+        };
+        let name = string_at(0)?;
+        let domain = string_at(2)?;
+        let path = string_at(3)?;
+        let expires = cookie.get(4).and_then(Value::as_u64);
+        store
+            .cookies
+            .retain(|c| c.name != name || c.domain != domain || c.path != path);
+
+        if let Some(value) = cookie.get(1).and_then(Value::as_str) {
+            store.cookies.push(MockCookie {
+                name,
+                value: value.to_string(),
+                domain,
+                path,
+                expires,
+            });
+        }
+    }
+
+    empty()
+}
+
+fn set_user_agent(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: SetUserAgentBody = request.body()?;
+    store.user_agent = Some(body.user_agent).filter(|u| !u.is_empty());
+
+    empty()
+}
+
+fn get_pending_counts(store: &MockStore) -> HandlerResult {
+    let mut pending_counts = Map::new();
+
+    for service in store.services.iter().filter(|s| s.is_repository()) {
+        let count_tags = |status: &str| -> usize {
+            store
+                .files
+                .iter()
+                .filter_map(|f| f.tags.get(&service.key))
+                .filter_map(|statuses| statuses.get(status))
+                .map(|tags| tags.len())
+                .sum()
+        };
+        let counts = if service.service_type == ServiceType::TagRepositories {
+            json!({
+                "pending_tag_mappings": count_tags(TAG_STATUS_PENDING),
+                "petitioned_tag_mappings": count_tags(TAG_STATUS_PETITIONED),
+                "pending_tag_siblings": 0,
+                "petitioned_tag_siblings": 0,
+                "pending_tag_parents": 0,
+                "petitioned_tag_parents": 0,
+            })
+        } else {
+            json!({ "pending_files": 0, "petitioned_files": 0 })
+        };
+        pending_counts.insert(service.key.clone(), counts);
+    }
+
+    content(&json!({ "pending_counts": pending_counts }))
+}
+
+/// Uploads the pending tags by making them current and deleting the petitioned tags
+fn commit_pending(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let service_key = pending_service_key(store, request)?;
+
+    for statuses in store
+        .files
+        .iter_mut()
+        .filter_map(|f| f.tags.get_mut(&service_key))
+    {
+        let pending = statuses.remove(TAG_STATUS_PENDING).unwrap_or_default();
+        let petitioned = statuses.remove(TAG_STATUS_PETITIONED).unwrap_or_default();
+        if let Some(current) = statuses.get_mut(TAG_STATUS_CURRENT) {
+            current.retain(|t| !petitioned.contains(t));
+        }
+        statuses
+            .entry(TAG_STATUS_CURRENT.to_string())
+            .or_default()
+            .extend(pending);
+        statuses
+            .entry(TAG_STATUS_DELETED.to_string())
+            .or_default()
+            .extend(petitioned);
+    }
+
+    empty()
+}
+
+fn forget_pending(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let service_key = pending_service_key(store, request)?;
+
+    for statuses in store
+        .files
+        .iter_mut()
+        .filter_map(|f| f.tags.get_mut(&service_key))
+    {
+        statuses.remove(TAG_STATUS_PENDING);
+        statuses.remove(TAG_STATUS_PETITIONED);
+    }
+
+    empty()
+}
+
+/// Returns the key of the repository whose pending content is managed by the request
+fn pending_service_key(store: &MockStore, request: &MockRequest) -> HandlerResult<String> {
+    let body: PendingContentBody = request.body()?;
+
+    match store.service_by_key(&body.service_key) {
+        Some(service) if service.is_repository() => Ok(service.key.clone()),
+        Some(_) => Err(MockError::bad_request("That service is not a repository!")),
+        None => Err(MockError::bad_request(format!(
+            "Could not find the service {}",
+            body.service_key
+        ))),
+    }
+}
+
+fn lock_database(store: &mut MockStore, lock: bool) -> HandlerResult {
+    match (store.database_locked, lock) {
+        (true, true) => Err(MockError::new(
+            StatusCode::CONFLICT,
+            "The database is already locked!",
+        )),
+        (false, false) => Err(MockError::bad_request("The database is not locked!")),
+        _ => {
+            store.database_locked = lock;
+            empty()
+        }
+    }
+}
+
+fn mr_bones(store: &MockStore) -> HandlerResult {
+    let (deleted, files): (Vec<&MockFile>, Vec<&MockFile>) =
+        store.files.iter().partition(|f| f.is_trashed);
+    let (inbox, archive): (Vec<&MockFile>, Vec<&MockFile>) = files.iter().partition(|f| f.is_inbox);
+    let size = |files: &[&MockFile]| files.iter().map(|f| f.bytes.len()).sum::<usize>();
+
+    content(&json!({
+        "boned_stats": {
+            "num_inbox": inbox.len(),
+            "num_archive": archive.len(),
+            "num_deleted": deleted.len(),
+            "size_inbox": size(&inbox),
+            "size_archive": size(&archive),
+            "size_deleted": size(&deleted),
+            "earliest_import_time": store.files.iter().map(|f| f.time_imported).min(),
+            "total_viewtime": [0, 0, 0, 0],
+            "total_alternate_files": store.alternates.len(),
+            "total_duplicate_files": store
+                .duplicate_kings
+                .iter()
+                .filter(|(member, king)| member != king)
+                .count(),
+            "total_potential_pairs": store.potential_duplicates.len(),
+        }
+    }))
+}
+
+fn get_client_options() -> HandlerResult {
+    content(&json!({
+        "old_options": {},
+        "options": {
+            "booleans": { "advanced_mode": false, "thumbnail_fill": false },
+            "strings": { "app_display_name": "hydrus client" },
+            "noneable_strings": { "favourite_file_lookup_script": null },
+            "integers": { "thumbnail_cache_size": 33554432 },
+            "noneable_integers": { "forced_search_limit": null },
+            "media_zooms": [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0],
+            "slideshow_durations": [1.0, 5.0, 10.0, 30.0, 60.0],
+        }
+    }))
+}
+
+fn get_file_relationships(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let hashes: Vec<String> =
+        if let Some(file_ids) = request.query_object::<Vec<u64>>("file_ids")? {
+            file_ids
+                .into_iter()
+                .map(|id| {
+                    store
+                        .file_by_id(id)
+                        .map(|f| f.hash.clone())
+                        .ok_or_else(file_not_found)
+                })
+                .collect::<HandlerResult<_>>()?
+        } else {
+            request.required_query_object("hashes")?
+        };
+    let mut relationships = Map::new();
+
+    for hash in hashes {
+        store.file(&hash).ok_or_else(file_not_found)?;
+        let partners = |pairs: &[(String, String)]| -> Vec<String> {
+            pairs
+                .iter()
+                .filter_map(|(a, b)| match (a == &hash, b == &hash) {
+                    (true, _) => Some(b.clone()),
+                    (_, true) => Some(a.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let king = store.king(&hash);
+        let duplicates: Vec<String> = store
+            .duplicates(&hash)
+            .into_iter()
+            .filter(|h| h != &hash)
+            .collect();
+
+        relationships.insert(
+            hash.clone(),
+            json!({
+                "is_king": king == hash,
+                "king": king,
+                "king_is_on_file_domain": true,
+                "king_is_local": true,
+                "0": partners(&store.potential_duplicates),
+                "1": partners(&store.false_positives),
+                "3": partners(&store.alternates),
+                "8": duplicates,
+            }),
+        );
+    }
+
+    content(&json!({ "file_relationships": relationships }))
+}
+
+fn get_random_potentials(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let pairs = potential_pairs(store, request)?;
+    let hashes: Vec<&String> = match pairs.choose(&mut rand::thread_rng()) {
+        Some((hash, _)) => {
+            let mut group = vec![hash];
+            for (a, b) in &pairs {
+                if a == hash && !group.contains(&b) {
+                    group.push(b);
+                } else if b == hash && !group.contains(&a) {
+                    group.push(a);
+                }
+            }
+            group
+        }
+        None => Vec::new(),
+    };
+
+    content(&json!({ "random_potential_duplicate_hashes": hashes }))
+}
+
+fn set_file_relationships(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: SetFileRelationshipsBody = request.body()?;
+
+    for relationship in body.relationships {
+        let hash_a = relationship.hash_a;
+        let hash_b = relationship.hash_b;
+        store.file(&hash_a).ok_or_else(file_not_found)?;
+        store.file(&hash_b).ok_or_else(file_not_found)?;
+
+        if relationship.relationship != 0 {
+            store.potential_duplicates.retain(|(a, b)| {
+                !((a == &hash_a && b == &hash_b) || (a == &hash_b && b == &hash_a))
+            });
+        }
+        match relationship.relationship {
+            0 => store.add_potential_duplicates(&hash_a, &hash_b),
+            1 if !contains_pair(&store.false_positives, &hash_a, &hash_b) => {
+                store.false_positives.push((hash_a.clone(), hash_b.clone()))
+            }
+            3 if !contains_pair(&store.alternates, &hash_a, &hash_b) => {
+                store.alternates.push((hash_a.clone(), hash_b.clone()))
+            }
+            2 | 4 | 7 => {
+                let (better, worse) = if relationship.relationship == 7 {
+                    (&hash_b, &hash_a)
+                } else {
+                    (&hash_a, &hash_b)
+                };
+                let king = store.king(better);
+                for hash in store
+                    .duplicates(better)
+                    .into_iter()
+                    .chain(store.duplicates(worse))
+                {
+                    store.duplicate_kings.insert(hash, king.clone());
+                }
+                if relationship.do_default_content_merge {
+                    merge_tags(store, worse, better);
+                    if relationship.relationship == 2 {
+                        merge_tags(store, better, worse);
+                    }
+                }
+            }
+            1 | 3 => {}
+            r => {
+                return Err(MockError::bad_request(format!(
+                    "Invalid relationship {}",
+                    r
+                )))
+            }
+        }
+        for (hash, delete) in [
+            (&hash_a, relationship.delete_a),
+            (&hash_b, relationship.delete_b),
+        ] {
+            if let (Some(true), Some(file)) = (delete, store.file_mut(hash)) {
+                file.is_trashed = true;
+                file.time_deleted = Some(now());
+            }
+        }
+    }
+
+    empty()
+}
+
+fn set_kings(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let selection: FileSelectionBody = request.body()?;
+
+    for hash in selection.hashes(store)? {
+        store.file(&hash).ok_or_else(file_not_found)?;
+        for member in store.duplicates(&hash) {
+            store.duplicate_kings.insert(member, hash.clone());
+        }
+    }
+
+    empty()
+}
+
+/// Returns the potential duplicate pairs that match the searches of the request
+fn potential_pairs(
+    store: &MockStore,
+    request: &MockRequest,
+) -> HandlerResult<Vec<(String, String)>> {
+    let file_domain = file_domain(
+        store,
+        request.param("file_service_key"),
+        request.param("file_service_name"),
+    )?;
+    let first_search = PotentialsSearch::new(store, request, "1")?;
+    let second_search = PotentialsSearch::new(store, request, "2")?;
+    let search_type = request.param("potentials_search_type").unwrap_or("0");
+
+    let pairs = store
+        .potential_duplicates
+        .iter()
+        .filter_map(|(a, b)| {
+            let file_a = store.file(a).filter(|f| file_domain.contains(f))?;
+            let file_b = store.file(b).filter(|f| file_domain.contains(f))?;
+            let matches = match search_type {
+                "1" => first_search.matches(file_a) && first_search.matches(file_b),
+                "2" => {
+                    (first_search.matches(file_a) && second_search.matches(file_b))
+                        || (first_search.matches(file_b) && second_search.matches(file_a))
+                }
+                _ => first_search.matches(file_a) || first_search.matches(file_b),
+            };
+            Some((a.clone(), b.clone())).filter(|_| matches)
+        })
+        .collect();
+
+    Ok(pairs)
+}
+
+/// Copies the current tags of one file to another one
+fn merge_tags(store: &mut MockStore, from: &str, to: &str) {
+    let tags = match store.file(from) {
+        Some(file) => file.tags.clone(),
+        None => return,
+    };
+    if let Some(file) = store.file_mut(to) {
+        for (service_key, statuses) in tags {
+            for tag in statuses.get(TAG_STATUS_CURRENT).into_iter().flatten() {
+                apply_tag_action(file, &service_key, 0, tag);
+            }
+        }
+    }
+}
+
+fn get_pages(store: &MockStore) -> HandlerResult {
+    content(&json!({ "pages": page_information(&store.root_page) }))
+}
+
+fn get_page_info(store: &MockStore, request: &MockRequest) -> HandlerResult {
+    let page = store
+        .root_page
+        .find(request.required_param("page_key")?)
+        .ok_or_else(page_not_found)?;
+    let mut page_info = page_information(page);
+    page_info["media"] = json!({
+        "num_files": page.hashes.len(),
+        "hash_ids": page
+            .hashes
+            .iter()
+            .filter_map(|h| store.file(h))
+            .map(|f| f.file_id)
+            .collect::<Vec<u64>>(),
+    });
+
+    content(&json!({ "page_info": page_info }))
+}
+
+fn focus_page(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: PageBody = request.body()?;
+
+    if store.root_page.select(&body.page_key) {
+        empty()
+    } else {
+        Err(page_not_found())
+    }
+}
+
+fn add_files_to_page(store: &mut MockStore, request: &MockRequest) -> HandlerResult {
+    let body: PageBody = request.body()?;
+    let hashes = body.selection.hashes(store)?;
+    let page = store
+        .root_page
+        .find_mut(&body.page_key)
+        .ok_or_else(page_not_found)?;
+
+    if !page.is_media_page() {
+        return Err(MockError::bad_request("That page is not a media page!"));
+    }
+    for hash in hashes {
+        if !page.hashes.contains(&hash) {
+            page.hashes.push(hash);
+        }
+    }
+
+    empty()
+}
+
+/// One of the two searches potential duplicates can be filtered with
+struct PotentialsSearch {
+    predicates: Vec<Predicate>,
+    tag_services: Vec<String>,
+}
+
+impl PotentialsSearch {
+    fn new(store: &MockStore, request: &MockRequest, index: &str) -> HandlerResult<Self> {
+        let query: Vec<QueryEntry> = request
+            .query_object(&format!("tags_{}", index))?
+            .unwrap_or_default();
+        let tag_services = tag_domain(
+            store,
+            request.param(&format!("tag_service_key_{}", index)),
+            None,
+        )?;
+
+        Ok(Self {
+            predicates: query.into_iter().map(QueryEntry::into_predicate).collect(),
+            tag_services,
+        })
+    }
+
+    fn matches(&self, file: &MockFile) -> bool {
+        let tags = searchable_tags(file, &self.tag_services);
+        self.predicates.iter().all(|p| p.matches(file, &tags))
+    }
+}
+
+/// The file services a search is executed on
+enum FileDomain {
+    Local,
+    Trash,
+    All,
+}
+
+impl FileDomain {
+    fn contains(&self, file: &MockFile) -> bool {
+        match self {
+            FileDomain::Local => !file.is_trashed,
+            FileDomain::Trash => file.is_trashed,
+            FileDomain::All => true,
+        }
+    }
+}
+
+/// Resolves the file domain of a request. Searches default to the local files
+fn file_domain(
+    store: &MockStore,
+    key: Option<&str>,
+    name: Option<&str>,
+) -> HandlerResult<FileDomain> {
+    let service = match (key, name) {
+        (Some(key), _) => store.service_by_key(key),
+        (None, Some(name)) => store.service_by_name(name),
+        (None, None) => return Ok(FileDomain::Local),
+    }
+    .ok_or_else(service_not_found)?;
+
+    match service.service_type {
+        ServiceType::LocalFiles | ServiceType::AllMyFiles => Ok(FileDomain::Local),
+        ServiceType::Trash => Ok(FileDomain::Trash),
+        ServiceType::AllLocalFiles | ServiceType::AllKnownFiles => Ok(FileDomain::All),
+        _ => Err(MockError::bad_request(format!(
+            "{} is not a file service!",
+            service.name
+        ))),
+    }
+}
+
+/// Resolves the keys of the tag services searched by a request.
+/// Searches default to all known tags
+fn tag_domain(
+    store: &MockStore,
+    key: Option<&str>,
+    name: Option<&str>,
+) -> HandlerResult<Vec<String>> {
+    let service = match (key, name) {
+        (Some(key), _) => store.service_by_key(key),
+        (None, Some(name)) => store.service_by_name(name),
+        (None, None) => None,
+    };
+
+    match service {
+        Some(service) if service.is_tag_service() => Ok(vec![service.key.clone()]),
+        Some(service) if service.service_type != ServiceType::AllKnownTags => Err(
+            MockError::bad_request(format!("{} is not a tag service!", service.name)),
+        ),
+        None if key.is_some() || name.is_some() => Err(service_not_found()),
+        _ => Ok(store
+            .services
+            .iter()
+            .filter(|s| s.is_tag_service())
+            .map(|s| s.key.clone())
+            .collect()),
+    }
+}
+
+/// Resolves the key of a tag service that tags can be assigned with
+fn tag_service_key(
+    store: &MockStore,
+    key: Option<&str>,
+    name: Option<&str>,
+) -> HandlerResult<String> {
+    match tag_domain(store, key, name)?.pop() {
+        Some(key) if key != ALL_KNOWN_TAGS_KEY => Ok(key),
+        _ => Err(MockError::bad_request(
+            "Tags can't be assigned to this service!",
+        )),
+    }
+}
+
+/// Returns the file identified by the `file_id` or `hash` parameter of the request
+fn request_file<'a>(store: &'a MockStore, request: &MockRequest) -> HandlerResult<&'a MockFile> {
+    let file = if let Some(file_id) = request.param("file_id") {
+        let file_id = file_id
+            .parse()
+            .map_err(|_| MockError::bad_request("Invalid file id"))?;
+        store.file_by_id(file_id)
+    } else {
+        store.file(request.required_param("hash")?)
+    };
+
+    file.ok_or_else(file_not_found)
+}
+
+/// Returns the file identified by either its hash or its id
+fn file_mut<'a>(
+    store: &'a mut MockStore,
+    hash: Option<&String>,
+    file_id: Option<u64>,
+) -> HandlerResult<&'a mut MockFile> {
+    let hash = match (hash, file_id) {
+        (Some(hash), _) => hash.clone(),
+        (None, Some(file_id)) => store
+            .file_by_id(file_id)
+            .map(|f| f.hash.clone())
+            .ok_or_else(file_not_found)?,
+        (None, None) => return Err(MockError::bad_request("No file was specified")),
+    };
+
+    store.file_mut(&hash).ok_or_else(file_not_found)
+}
+
+/// Creates the information object of an url with its matching url class
+fn url_info(store: &MockStore, url: &str) -> HandlerResult<Value> {
+    let normalised_url = normalise_url(url)?;
+    let (match_name, url_type, can_parse) = store
+        .url_classes
+        .iter()
+        .find(|c| c.pattern.is_match(url) || c.pattern.is_match(&normalised_url))
+        .map(|c| (c.name.clone(), c.url_type, true))
+        .unwrap_or_else(MockUrlClass::unknown);
+
+    Ok(json!({
+        "normalised_url": normalised_url,
+        "url_type": url_type,
+        "url_type_string": url_type_string(url_type),
+        "match_name": match_name,
+        "can_parse": can_parse,
+    }))
+}
+
+/// The flags of a metadata request that select the returned fields
+struct MetadataFlags {
+    only_identifiers: bool,
+    only_basic: bool,
+    detailed_url_information: bool,
+    include_blurhash: bool,
+    include_notes: bool,
+    include_milliseconds: bool,
+    hide_service_keys_tags: bool,
+}
+
+impl MetadataFlags {
+    fn from_request(request: &MockRequest) -> HandlerResult<Self> {
+        let flag = |name: &str| -> HandlerResult<bool> {
+            Ok(request.query_object(name)?.unwrap_or(false))
+        };
+
+        Ok(Self {
+            only_identifiers: flag("only_return_identifiers")?,
+            only_basic: flag("only_return_basic_information")?,
+            detailed_url_information: flag("detailed_url_information")?,
+            include_blurhash: flag("include_blurhash")?,
+            include_notes: flag("include_notes")?,
+            include_milliseconds: flag("include_milliseconds")?,
+            hide_service_keys_tags: flag("hide_service_keys_tags")?,
+        })
+    }
+
+    /// Returns the timestamp as seconds with milliseconds if they were requested
+    fn timestamp(&self, timestamp: u64) -> Value {
+        if self.include_milliseconds {
+            json!(timestamp as f64)
+        } else {
+            json!(timestamp)
+        }
+    }
+}
+
+/// Creates the metadata object of a file
+fn metadata_for_file(store: &MockStore, file: &MockFile, flags: &MetadataFlags) -> Value {
+    let mut metadata = json!({
+        "file_id": file.file_id,
+        "hash": file.hash,
+    });
+    if flags.only_identifiers {
+        return metadata;
+    }
+    let basic = json!({
+        "size": file.bytes.len(),
+        "mime": file.mime,
+        "ext": file.ext,
+        "width": file.width,
+        "height": file.height,
+        "duration": file.duration,
+        "time_modified": flags.timestamp(file.time_modified),
+        "has_audio": file.has_audio,
+        "num_frames": file.num_frames,
+        "num_words": Value::Null,
+    });
+    merge(&mut metadata, basic);
+    if flags.include_blurhash {
+        merge(&mut metadata, json!({ "blurhash": file.blurhash }));
+    }
+    if flags.only_basic {
+        return metadata;
+    }
+
+    let local_services: Vec<&MockService> = store
+        .services
+        .iter()
+        .filter(|s| s.service_type == ServiceType::LocalFiles)
+        .collect();
+    let mut current = Map::new();
+    let mut deleted = Map::new();
+    let time_imported = flags.timestamp(file.time_imported);
+    current.insert(
+        ALL_LOCAL_FILES_KEY.into(),
+        json!({ "time_imported": time_imported }),
+    );
+    match file.time_deleted {
+        Some(time_deleted) if file.is_trashed => {
+            let time_deleted = flags.timestamp(time_deleted);
+            current.insert(TRASH_KEY.into(), json!({ "time_imported": time_deleted }));
+            for service in local_services {
+                deleted.insert(
+                    service.key.clone(),
+                    json!({ "time_deleted": time_deleted, "time_imported": time_imported }),
+                );
+            }
+        }
+        _ => {
+            for service in local_services {
+                current.insert(
+                    service.key.clone(),
+                    json!({ "time_imported": time_imported }),
+                );
+            }
+        }
+    }
+
+    let mut keys_to_tags = file.tags.clone();
+    let mut all_known_tags: HashMap<String, Vec<String>> = HashMap::new();
+    for statuses in file.tags.values() {
+        for (status, tags) in statuses {
+            let all_tags = all_known_tags.entry(status.clone()).or_default();
+            all_tags.extend(
+                tags.iter()
+                    .filter(|t| !all_tags.contains(t))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+    keys_to_tags.insert(ALL_KNOWN_TAGS_KEY.into(), all_known_tags);
+    let names_to_tags: HashMap<&str, &HashMap<String, Vec<String>>> = keys_to_tags
+        .iter()
+        .filter_map(|(key, tags)| store.service_by_key(key).map(|s| (s.name.as_str(), tags)))
+        .collect();
+
+    let ratings: Map<String, Value> = store
+        .services
+        .iter()
+        .filter(|s| s.is_rating_service())
+        .map(|s| {
+            let value = match file.ratings.get(&s.key) {
+                Some(rating) => json!(rating.value()),
+                None if s.service_type == ServiceType::IncDecRatings => json!(0),
+                None => Value::Null,
+            };
+            (s.key.clone(), value)
+        })
+        .collect();
+
+    let full = json!({
+        "is_inbox": file.is_inbox,
+        "is_local": true,
+        "is_trashed": file.is_trashed,
+        "file_services": {
+            "current": current,
+            "deleted": deleted,
+        },
+        "known_urls": file.known_urls,
+        "service_names_to_statuses_to_tags": names_to_tags,
+        "service_names_to_statuses_to_display_tags": names_to_tags,
+        "ratings": ratings,
+    });
+    merge(&mut metadata, full);
+    if !flags.hide_service_keys_tags {
+        merge(
+            &mut metadata,
+            json!({
+                "service_keys_to_statuses_to_tags": keys_to_tags,
+                "service_keys_to_statuses_to_display_tags": keys_to_tags,
+            }),
+        );
+    }
+    if flags.detailed_url_information {
+        let detailed_urls: Vec<Value> = file
+            .known_urls
+            .iter()
+            .map(|url| url_info(store, url))
+            .collect::<HandlerResult<_>>()
+            .unwrap_or_default();
+        merge(
+            &mut metadata,
+            json!({ "detailed_known_urls": detailed_urls }),
+        );
+    }
+    if flags.include_notes {
+        merge(&mut metadata, json!({ "notes": file.notes }));
+    }
+
+    metadata
+}
+
+/// Creates the page information object of a page and its children
+fn page_information(page: &MockPage) -> Value {
+    json!({
+        "name": page.name,
+        "page_key": page.page_key,
+        "page_type": page.page_type,
+        "selected": page.selected,
+        "pages": page.pages.iter().map(page_information).collect::<Vec<Value>>(),
+    })
+}
+
+/// Modifies the tags of a file the same way hydrus applies tag actions
+fn apply_tag_action(file: &mut MockFile, service_key: &str, action: u8, tag: &str) {
+    let statuses = file.tags.entry(service_key.to_string()).or_default();
+    let mut insert = |status: &str| {
+        let tags = statuses.entry(status.to_string()).or_default();
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    };
+    match action {
+        0 => insert(TAG_STATUS_CURRENT),
+        1 => insert(TAG_STATUS_DELETED),
+        2 => insert(TAG_STATUS_PENDING),
+        4 => insert(TAG_STATUS_PETITIONED),
+        _ => {}
+    }
+    let removed_status = match action {
+        0 => TAG_STATUS_DELETED,
+        1 => TAG_STATUS_CURRENT,
+        3 => TAG_STATUS_PENDING,
+        5 => TAG_STATUS_PETITIONED,
+        _ => return,
+    };
+    if let Some(tags) = statuses.get_mut(removed_status) {
+        tags.retain(|t| t != tag);
+    }
+}
+
+/// Follows the sibling relationships of a tag to its ideal tag
+fn ideal_tag(siblings: &HashMap<String, String>, tag: &str) -> String {
+    let mut ideal = tag;
+
+    // the number of steps is limited to not loop forever on cyclic siblings
+    for _ in 0..siblings.len() {
+        match siblings.get(ideal) {
+            Some(next) => ideal = next,
+            None => break,
+        }
+    }
+
+    ideal.to_string()
+}
+
+/// Collects the ancestors or descendants of a tag by following its parent relationships
+fn related_tags(parents: &[(String, String)], tag: &str, ancestors: bool) -> Vec<String> {
+    let mut related: Vec<String> = Vec::new();
+    let mut pending = vec![tag.to_string()];
+
+    while let Some(current) = pending.pop() {
+        for (child, parent) in parents {
+            let (from, to) = if ancestors {
+                (child, parent)
+            } else {
+                (parent, child)
+            };
+            if *from == current && to != tag && !related.contains(to) {
+                related.push(to.clone());
+                pending.push(to.clone());
+            }
+        }
+    }
+    related.sort();
+
+    related
+}
+
+/// Cleans a tag the way hydrus does it
+fn clean_tag(tag: &str) -> String {
+    tag.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Normalises an url by sorting its query parameters and removing the fragment
+fn normalise_url(url: &str) -> HandlerResult<String> {
+    let mut url = Url::parse(url.trim()).map_err(|e| MockError::bad_request(e.to_string()))?;
+    let mut query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+    if !query.is_empty() {
+        query.sort();
+        url.query_pairs_mut().clear().extend_pairs(query);
+    }
+    url.set_fragment(None);
+
+    Ok(url.to_string())
+}
+
+fn url_type_string(url_type: u8) -> &'static str {
+    match url_type {
+        t if t == URL_TYPE_POST => "post url",
+        t if t == URL_TYPE_FILE => "direct file url",
+        t if t == URL_TYPE_GALLERY => "gallery url",
+        t if t == URL_TYPE_WATCHABLE => "watchable url",
+        _ => "unknown url",
+    }
+}
+
+/// Creates a response with the content of a file and supports range requests
+fn file_content(bytes: &[u8], mime: &str, range: Option<&str>) -> HandlerResult {
+    let total_length = bytes.len() as u64;
+    let builder = Response::builder().header(CONTENT_TYPE, mime);
+
+    let response = match range.map(|r| parse_range(r, total_length)) {
+        Some(Some((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, total_length),
+            )
+            .header(CONTENT_LENGTH, end - start + 1)
+            .body(Body::from(bytes[start as usize..=end as usize].to_vec())),
+        Some(None) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{}", total_length))
+            .body(Body::empty()),
+        None => builder
+            .header(CONTENT_LENGTH, total_length)
+            .body(Body::from(bytes.to_vec())),
+    };
+
+    response.map_err(MockError::server_error)
+}
+
+/// Parses a range header into the inclusive start and end of the range
+fn parse_range(range: &str, total_length: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let last = total_length.checked_sub(1)?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (total_length.saturating_sub(suffix.parse().ok()?), last),
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(last)),
+    };
+
+    if start <= end {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+/// Merges the fields of an object into another object
+fn merge(target: &mut Value, source: Value) {
+    if let (Value::Object(target), Value::Object(source)) = (target, source) {
+        target.extend(source);
+    }
+}
+
+/// Creates a response with serialized content
+fn content<T: Serialize>(value: &T) -> HandlerResult {
+    let body = Client::serialize_body(value).map_err(MockError::server_error)?;
+
+    Response::builder()
+        .header(CONTENT_TYPE, CONTENT_TYPE_CONTENT)
+        .body(Body::from(body))
+        .map_err(MockError::server_error)
+}
+
+/// Creates an empty successful response
+fn empty() -> HandlerResult {
+    Ok(Response::new(Body::empty()))
+}
+
+fn file_not_found() -> MockError {
+    MockError::new(StatusCode::NOT_FOUND, "Could not find that file!")
+}
+
+fn page_not_found() -> MockError {
+    MockError::new(StatusCode::NOT_FOUND, "Could not find that page!")
+}
+
+fn service_not_found() -> MockError {
+    MockError::bad_request("Could not find that service!")
+}
+
+/// A request to the mock with its body
+struct MockRequest {
+    method: Method,
+    path: String,
+    params: HashMap<String, String>,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl MockRequest {
+    fn new(parts: Parts, body: Bytes) -> Self {
+        let params = parts
+            .uri
+            .query()
+            .and_then(|q| Url::parse(&format!("http://localhost/?{}", q)).ok())
+            .map(|url| url.query_pairs().into_owned().collect())
+            .unwrap_or_default();
+
+        Self {
+            method: parts.method,
+            path: parts.uri.path().trim_matches('/').to_string(),
+            params,
+            headers: parts.headers,
+            body,
+        }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(name)
+            .and_then(|h: &HeaderValue| h.to_str().ok())
+    }
+
+    /// Returns a key that can be passed either as header or as parameter
+    fn credential(&self, name: &str) -> Option<&str> {
+        self.header(name).or_else(|| self.param(name))
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    fn required_param(&self, name: &str) -> HandlerResult<&str> {
+        self.param(name)
+            .ok_or_else(|| MockError::bad_request(format!("The parameter {} is missing", name)))
+    }
+
+    /// Deserializes a parameter that contains a JSON or CBOR encoded object
+    fn query_object<T: DeserializeOwned>(&self, name: &str) -> HandlerResult<Option<T>> {
+        self.param(name)
+            .map(|value| deserialize_query_object(value).map_err(MockError::bad_request))
+            .transpose()
+    }
+
+    fn required_query_object<T: DeserializeOwned>(&self, name: &str) -> HandlerResult<T> {
+        self.query_object(name)?
+            .ok_or_else(|| MockError::bad_request(format!("The parameter {} is missing", name)))
+    }
+
+    /// Deserializes the JSON or CBOR body of the request
+    fn body<T: DeserializeOwned + Debug>(&self) -> HandlerResult<T> {
+        Client::deserialize_content(&self.body).map_err(MockError::bad_request)
+    }
+}
+
+/// Deserializes a query parameter from JSON
+#[cfg(feature = "json")]
+fn deserialize_query_object<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|e| e.to_string())
+}
+
+/// Deserializes a query parameter from base64 encoded CBOR
+#[cfg(feature = "cbor")]
+fn deserialize_query_object<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    let bytes = base64::decode(value).map_err(|e| e.to_string())?;
+    ciborium::de::from_reader(&bytes[..]).map_err(|e| e.to_string())
+}
+
+/// An error response of the mock in the format hydrus uses
+#[derive(Debug)]
+struct MockError {
+    status: StatusCode,
+    message: String,
+}
+
+impl MockError {
+    fn new<S: ToString>(status: StatusCode, message: S) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    fn bad_request<S: ToString>(message: S) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    fn server_error<S: ToString>(message: S) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let exception_type = match self.status.as_u16() {
+            400 => "BadRequestException",
+            401 => "MissingCredentialsException",
+            403 => "InsufficientCredentialsException",
+            404 => "NotFoundException",
+            419 => "SessionException",
+            _ => "ServerException",
+        };
+        let body = json!({
+            "error": self.message,
+            "exception_type": exception_type,
+            "status_code": self.status.as_u16(),
+        });
+        let mut response = content(&body).unwrap_or_else(|_| Response::new(Body::empty()));
+        *response.status_mut() = self.status;
+
+        response
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AddFileBody {
+    path: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileSelectionBody {
+    hash: Option<String>,
+    hashes: Vec<String>,
+    file_id: Option<u64>,
+    file_ids: Vec<u64>,
+    file_service_name: Option<String>,
+    file_service_key: Option<String>,
+}
+
+impl FileSelectionBody {
+    /// Returns the hashes of all selected files
+    fn hashes(&self, store: &MockStore) -> HandlerResult<Vec<String>> {
+        let mut hashes: Vec<String> = self.hash.iter().chain(&self.hashes).cloned().collect();
+
+        for file_id in self.file_id.iter().chain(&self.file_ids) {
+            let file = store.file_by_id(*file_id).ok_or_else(file_not_found)?;
+            hashes.push(file.hash.clone());
+        }
+
+        Ok(hashes)
+    }
+
+    /// Validates the selected file service
+    fn file_service(&self, store: &MockStore) -> HandlerResult<()> {
+        if self.file_service_key.is_some() || self.file_service_name.is_some() {
+            file_domain(
+                store,
+                self.file_service_key.as_deref(),
+                self.file_service_name.as_deref(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AddTagsBody {
+    hash: Option<String>,
+    hashes: Vec<String>,
+    service_names_to_tags: HashMap<String, Vec<String>>,
+    service_keys_to_tags: HashMap<String, Vec<String>>,
+    service_names_to_actions_to_tags: HashMap<String, HashMap<String, Vec<String>>>,
+    service_keys_to_actions_to_tags: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AddUrlBody {
+    url: String,
+    destination_page_key: Option<String>,
+    destination_page_name: Option<String>,
+    service_names_to_additional_tags: HashMap<String, Vec<String>>,
+    service_keys_to_additional_tags: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AssociateUrlBody {
+    url_to_add: Option<String>,
+    urls_to_add: Vec<String>,
+    url_to_delete: Option<String>,
+    urls_to_delete: Vec<String>,
+    hash: Option<String>,
+    hashes: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NotesBody {
+    notes: HashMap<String, String>,
+    note_names: Vec<String>,
+    hash: Option<String>,
+    file_id: Option<u64>,
+    merge_cleverly: Option<bool>,
+    extend_existing_note_if_possible: Option<bool>,
+    conflict_resolution: Option<u8>,
+}
+
+impl NotesBody {
+    /// Returns the file the notes belong to
+    fn file_mut<'a>(&self, store: &'a mut MockStore) -> HandlerResult<&'a mut MockFile> {
+        file_mut(store, self.hash.as_ref(), self.file_id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SetRatingBody {
+    hash: Option<String>,
+    file_id: Option<u64>,
+    rating_service_key: String,
+    rating: Option<RatingValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetCookiesBody {
+    cookies: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetUserAgentBody {
+    #[serde(rename = "user-agent")]
+    user_agent: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PendingContentBody {
+    service_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageBody {
+    page_key: String,
+    #[serde(flatten)]
+    selection: FileSelectionBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetFileRelationshipsBody {
+    relationships: Vec<RelationshipBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationshipBody {
+    hash_a: String,
+    hash_b: String,
+    relationship: u8,
+    #[serde(default)]
+    do_default_content_merge: bool,
+    delete_a: Option<bool>,
+    delete_b: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum QueryEntry {
+    Tag(String),
+    OrChain(Vec<String>),
+}
+
+impl QueryEntry {
+    fn into_predicate(self) -> Predicate {
+        match self {
+            QueryEntry::Tag(tag) => Predicate::parse(&tag),
+            QueryEntry::OrChain(tags) => {
+                Predicate::Or(tags.iter().map(|t| Predicate::parse(t)).collect())
+            }
+        }
+    }
+}
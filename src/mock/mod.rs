@@ -0,0 +1,141 @@
+//! An in-process mock of the hydrus client api that keeps all data in memory.
+//! It can be used to test code that uses the [Client] or [Hydrus] without
+//! a running hydrus client. The mock is only available with the `mock` feature.
+//!
+//! The mock implements the endpoints wrapped by this crate. Searches evaluate
+//! tags and the most common system predicates. Other system predicates
+//! are accepted but don't filter the results.
+//!
+//! Example:
+//! ```
+//! use hydrus_api::mock::{MockFile, MockHydrus, MY_TAGS_KEY};
+//! use hydrus_api::wrapper::tag::Tag;
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//!
+//! let mock = MockHydrus::start().unwrap();
+//! let file = MockFile::new(vec![0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 1, 0, 1, 0])
+//!     .add_tags(MY_TAGS_KEY, vec!["character:megumin".to_string()]);
+//! mock.store().add_file(file);
+//!
+//! let hydrus = mock.hydrus();
+//! let files = hydrus
+//!     .search()
+//!     .add_tag(Tag::from("character:megumin"))
+//!     .run()
+//!     .await
+//!     .unwrap();
+//! assert_eq!(files.len(), 1);
+//! # }
+//! ```
+
+mod handlers;
+mod search;
+mod store;
+
+pub use store::*;
+
+use crate::error::{Error, Result};
+use crate::{Client, Hydrus};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::Server;
+use std::convert::Infallible;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use tokio::sync::oneshot;
+
+/// A running mock of the hydrus client api.
+/// The server runs on its own thread and is stopped when the handle is dropped.
+pub struct MockHydrus {
+    address: SocketAddr,
+    store: Arc<Mutex<MockStore>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockHydrus {
+    /// Starts a new mock with the default services and no files
+    pub fn start() -> Result<Self> {
+        Self::with_store(MockStore::default())
+    }
+
+    /// Starts a new mock that serves the given store
+    pub fn with_store(store: MockStore) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let store = Arc::new(Mutex::new(store));
+
+        let server = {
+            let _guard = runtime.enter();
+            Server::from_tcp(listener).map_err(|e| Error::Io(io::Error::other(e)))?
+        };
+        let service_store = Arc::clone(&store);
+        let make_service = make_service_fn(move |_| {
+            let store = Arc::clone(&service_store);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handlers::handle(Arc::clone(&store), request)
+                }))
+            }
+        });
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let server = server.serve(make_service).with_graceful_shutdown(async {
+            shutdown_rx.await.ok();
+        });
+        let thread = std::thread::spawn(move || {
+            if let Err(e) = runtime.block_on(server) {
+                tracing::error!("Mock server failed: {}", e);
+            }
+        });
+        tracing::debug!("Started hydrus mock on {}", address);
+
+        Ok(Self {
+            address,
+            store,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns the base url of the mock api
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Returns the access key that is accepted by the mock
+    pub fn access_key(&self) -> String {
+        self.store().access_key.clone()
+    }
+
+    /// Returns a client that is connected to the mock
+    pub fn client(&self) -> Client {
+        Client::new(self.url(), self.access_key())
+    }
+
+    /// Returns a hydrus wrapper that is connected to the mock
+    pub fn hydrus(&self) -> Hydrus {
+        Hydrus::new(self.client())
+    }
+
+    /// Locks the store of the mock to inspect or modify its content
+    pub fn store(&self) -> MutexGuard<'_, MockStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockHydrus {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::api_core::endpoints::searching_and_fetching_files::SortType;
+use crate::mock::store::{MockFile, TAG_STATUS_CURRENT, TAG_STATUS_PENDING};
+use rand::seq::SliceRandom;
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// A single entry of a search query
+#[derive(Clone, Debug)]
+pub(crate) enum Predicate {
+    Tag { pattern: String, negated: bool },
+    System { predicate: String, negated: bool },
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Parses a single tag of a query
+    pub(crate) fn parse(tag: &str) -> Self {
+        let tag = tag.trim().to_lowercase();
+        let (tag, negated) = match tag.strip_prefix('-') {
+            Some(tag) => (tag.to_string(), true),
+            None => (tag, false),
+        };
+
+        match tag.strip_prefix("system:") {
+            Some(predicate) => Self::System {
+                predicate: predicate.trim().to_string(),
+                negated,
+            },
+            None => Self::Tag {
+                pattern: tag,
+                negated,
+            },
+        }
+    }
+
+    /// Returns the limit of a `system:limit` predicate
+    pub(crate) fn limit(&self) -> Option<usize> {
+        match self {
+            Self::System { predicate, .. } => predicate
+                .strip_prefix("limit")
+                .map(|l| l.trim_start_matches([' ', '=']))
+                .and_then(|l| l.trim().parse().ok()),
+            _ => None,
+        }
+    }
+
+    /// Returns if the file matches the predicate. The tags are the tags of the
+    /// file in the searched tag domain
+    pub(crate) fn matches(&self, file: &MockFile, tags: &[String]) -> bool {
+        match self {
+            Self::Tag { pattern, negated } => {
+                tags.iter().any(|tag| glob_matches(pattern, tag)) != *negated
+            }
+            Self::System { predicate, negated } => {
+                system_predicate_matches(predicate, file, tags) != *negated
+            }
+            Self::Or(predicates) => predicates.iter().any(|p| p.matches(file, tags)),
+        }
+    }
+}
+
+/// Returns the current and pending tags of a file for the given tag services
+pub(crate) fn searchable_tags(file: &MockFile, tag_services: &[String]) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    for service in tag_services {
+        for status in [TAG_STATUS_CURRENT, TAG_STATUS_PENDING] {
+            for tag in file.tags_with_status(service, status) {
+                if !tags.contains(&tag) {
+                    tags.push(tag)
+                }
+            }
+        }
+    }
+
+    tags
+}
+
+/// Sorts files by the given hydrus sort type
+pub(crate) fn sort_files(files: &mut Vec<&MockFile>, sort_type: SortType, ascending: bool) {
+    if sort_type == SortType::Random {
+        files.shuffle(&mut rand::thread_rng());
+        return;
+    }
+    files.sort_by(|a, b| {
+        let ordering = match sort_type {
+            SortType::FileType => a.mime.cmp(&b.mime),
+            SortType::HasAudio => a.has_audio.cmp(&b.has_audio),
+            SortType::Hash | SortType::PixelHash => a.hash.cmp(&b.hash),
+            SortType::Blurhash => a.blurhash.cmp(&b.blurhash),
+            _ => {
+                let a = sort_value(a, sort_type);
+                let b = sort_value(b, sort_type);
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            }
+        };
+        ordering.then(a.file_id.cmp(&b.file_id))
+    });
+    if !ascending {
+        files.reverse();
+    }
+}
+
+/// Returns the numeric value a file is sorted by
+fn sort_value(file: &MockFile, sort_type: SortType) -> f64 {
+    let width = file.width.unwrap_or_default() as f64;
+    let height = file.height.unwrap_or_default() as f64;
+
+    match sort_type {
+        SortType::FileSize => file.bytes.len() as f64,
+        SortType::Duration => file.duration.unwrap_or_default() as f64,
+        SortType::Width => width,
+        SortType::Height => height,
+        SortType::Ratio if height > 0.0 => width / height,
+        SortType::Ratio => 0.0,
+        SortType::NumberOfPixels => width * height,
+        SortType::NumberOfTags => file
+            .tags
+            .values()
+            .filter_map(|statuses| statuses.get(TAG_STATUS_CURRENT))
+            .map(|tags| tags.len())
+            .sum::<usize>() as f64,
+        SortType::ModifiedTime => file.time_modified as f64,
+        SortType::NumberOfFrames => file.num_frames.unwrap_or_default() as f64,
+        _ => file.time_imported as f64,
+    }
+}
+
+/// Evaluates the system predicates supported by the mock.
+/// Unsupported predicates match every file.
+fn system_predicate_matches(predicate: &str, file: &MockFile, tags: &[String]) -> bool {
+    let size = file.bytes.len() as u64;
+
+    match predicate {
+        "everything" => true,
+        "inbox" => file.is_inbox,
+        "archive" => !file.is_inbox,
+        "has tags" => !tags.is_empty(),
+        "no tags" | "untagged" => tags.is_empty(),
+        "has duration" => file.duration.is_some(),
+        "no duration" => file.duration.is_none(),
+        "has audio" => file.has_audio,
+        "no audio" => !file.has_audio,
+        p if p.starts_with("limit") => true,
+        p if p.starts_with("number of tags ") => {
+            compare(&p["number of tags ".len()..], tags.len() as u64)
+        }
+        p if p.starts_with("width ") => {
+            compare(&p["width ".len()..], file.width.unwrap_or(0) as u64)
+        }
+        p if p.starts_with("height ") => {
+            compare(&p["height ".len()..], file.height.unwrap_or(0) as u64)
+        }
+        p if p.starts_with("filesize ") => compare(&p["filesize ".len()..], size),
+        p if p.starts_with("hash = ") => p["hash = ".len()..]
+            .split([' ', ','])
+            .any(|hash| hash == file.hash),
+        p if p.starts_with("filetype = ") => p["filetype = ".len()..]
+            .split(',')
+            .any(|mime| mime.trim() == file.mime),
+        p if p.starts_with("has url matching regex ") => {
+            url_matches(&p["has url matching regex ".len()..], file)
+        }
+        p if p.starts_with("does not have url matching regex ") => {
+            !url_matches(&p["does not have url matching regex ".len()..], file)
+        }
+        p if p.contains("url with class ") => true,
+        p if p.starts_with("has url ") => {
+            file.known_urls.iter().any(|u| u == &p["has url ".len()..])
+        }
+        p if p.starts_with("does not have url ") => !file
+            .known_urls
+            .iter()
+            .any(|u| u == &p["does not have url ".len()..]),
+        p if p.starts_with("num notes ") => {
+            compare(&p["num notes ".len()..], file.notes.len() as u64)
+        }
+        p if p.starts_with("has note with name ") => {
+            file.notes.contains_key(&p["has note with name ".len()..])
+        }
+        p if p.starts_with("no note with name ") => {
+            !file.notes.contains_key(&p["no note with name ".len()..])
+        }
+        p if p.starts_with("number of frames ") => compare(
+            &p["number of frames ".len()..],
+            file.num_frames.unwrap_or(0),
+        ),
+        p => {
+            tracing::debug!("System predicate '{}' is not evaluated by the mock", p);
+            true
+        }
+    }
+}
+
+/// Compares a value with an expression like `> 5` or `~= 10 KB`
+fn compare(expression: &str, value: u64) -> bool {
+    let mut parts = expression.split_whitespace();
+    let (operator, operand) = match (parts.next(), parts.next()) {
+        (Some(operator), Some(operand)) => (operator, operand),
+        _ => return true,
+    };
+    let multiplier = match parts.next() {
+        Some("kb") => 1024,
+        Some("mb") => 1024 * 1024,
+        Some("gb") => 1024 * 1024 * 1024,
+        _ => 1,
+    };
+    let operand = match operand.parse::<u64>() {
+        Ok(operand) => operand * multiplier,
+        Err(_) => return true,
+    };
+
+    match operator {
+        "<" => value < operand,
+        ">" => value > operand,
+        "=" => value == operand,
+        "~=" => (value as f64 - operand as f64).abs() <= operand as f64 * 0.15,
+        _ => true,
+    }
+}
+
+/// Returns if any url of the file matches the regex
+fn url_matches(regex: &str, file: &MockFile) -> bool {
+    Regex::new(regex)
+        .map(|regex| file.known_urls.iter().any(|url| regex.is_match(url)))
+        .unwrap_or(false)
+}
+
+/// Matches a tag against a pattern that can contain `*` wildcards
+pub(crate) fn glob_matches(pattern: &str, tag: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == tag;
+    }
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let mut remaining = match tag.strip_prefix(first) {
+        Some(remaining) => remaining,
+        None => return false,
+    };
+    let parts: Vec<&str> = parts.collect();
+
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    true
+}
@@ -0,0 +1,756 @@
+use crate::api_core::endpoints::adding_urls::URL_TYPE_UNKNOWN;
+use crate::api_core::endpoints::editing_ratings::Rating;
+use crate::error::{Error, Result};
+use crate::wrapper::service::ServiceType;
+use rand::RngCore;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The key of the default *my files* service
+pub const MY_FILES_KEY: &str = "6c6f63616c2066696c6573";
+/// The key of the default *my tags* service
+pub const MY_TAGS_KEY: &str = "6c6f63616c2074616773";
+/// The key of the *all local files* service
+pub const ALL_LOCAL_FILES_KEY: &str = "616c6c206c6f63616c2066696c6573";
+/// The key of the *all known files* service
+pub const ALL_KNOWN_FILES_KEY: &str = "616c6c206b6e6f776e2066696c6573";
+/// The key of the *all known tags* service
+pub const ALL_KNOWN_TAGS_KEY: &str = "616c6c206b6e6f776e2074616773";
+/// The key of the *trash* service
+pub const TRASH_KEY: &str = "7472617368";
+/// The key of the default *favourites* like/dislike rating service
+pub const FAVOURITES_KEY: &str = "6661766f757269746573";
+
+/// The tag status of current tags
+pub const TAG_STATUS_CURRENT: &str = "0";
+/// The tag status of tags pending to a repository
+pub const TAG_STATUS_PENDING: &str = "1";
+/// The tag status of deleted tags
+pub const TAG_STATUS_DELETED: &str = "2";
+/// The tag status of tags petitioned from a repository
+pub const TAG_STATUS_PETITIONED: &str = "3";
+
+/// The page type of a search page
+pub const PAGE_TYPE_FILE_SEARCH: u32 = 5;
+/// The page type of an url downloader page
+pub const PAGE_TYPE_URL_DOWNLOADER: u32 = 6;
+/// The page type of a page of pages
+pub const PAGE_TYPE_PAGE_OF_PAGES: u32 = 9;
+/// The page type of the top level notebook
+pub const PAGE_TYPE_TOP_NOTEBOOK: u32 = 10;
+
+/// The in-memory data of a hydrus mock
+#[derive(Clone, Debug)]
+pub struct MockStore {
+    pub(crate) access_key: String,
+    pub(crate) session_keys: HashMap<String, String>,
+    pub(crate) permissions: Vec<u32>,
+    pub(crate) requested_access_keys: HashMap<String, Vec<u32>>,
+    pub(crate) services: Vec<MockService>,
+    pub(crate) files: Vec<MockFile>,
+    pub(crate) unknown_file_ids: HashMap<String, u64>,
+    pub(crate) tag_siblings: HashMap<String, HashMap<String, String>>,
+    pub(crate) tag_parents: HashMap<String, Vec<(String, String)>>,
+    pub(crate) potential_duplicates: Vec<(String, String)>,
+    pub(crate) false_positives: Vec<(String, String)>,
+    pub(crate) alternates: Vec<(String, String)>,
+    pub(crate) duplicate_kings: HashMap<String, String>,
+    pub(crate) url_classes: Vec<MockUrlClass>,
+    pub(crate) imported_urls: Vec<String>,
+    pub(crate) cookies: Vec<MockCookie>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) root_page: MockPage,
+    pub(crate) files_directory: Option<PathBuf>,
+    pub(crate) database_locked: bool,
+    pub(crate) ignore_range_requests: bool,
+    pub(crate) failing_requests: Option<(usize, u16)>,
+    pub(crate) request_counts: HashMap<String, usize>,
+    pub(crate) access_key_request_counts: HashMap<String, usize>,
+}
+
+impl Default for MockStore {
+    fn default() -> Self {
+        let services = vec![
+            MockService::new("my files", MY_FILES_KEY, ServiceType::LocalFiles),
+            MockService::new("my tags", MY_TAGS_KEY, ServiceType::LocalTags),
+            MockService::new(
+                "all local files",
+                ALL_LOCAL_FILES_KEY,
+                ServiceType::AllLocalFiles,
+            ),
+            MockService::new(
+                "all known files",
+                ALL_KNOWN_FILES_KEY,
+                ServiceType::AllKnownFiles,
+            ),
+            MockService::new(
+                "all known tags",
+                ALL_KNOWN_TAGS_KEY,
+                ServiceType::AllKnownTags,
+            ),
+            MockService::new("trash", TRASH_KEY, ServiceType::Trash),
+            MockService::new("favourites", FAVOURITES_KEY, ServiceType::LikeRatings),
+        ];
+        let mut root_page = MockPage::new("top pages notebook", PAGE_TYPE_TOP_NOTEBOOK);
+        root_page.selected = true;
+        root_page
+            .pages
+            .push(MockPage::new("files", PAGE_TYPE_FILE_SEARCH));
+
+        Self {
+            access_key: random_key(),
+            session_keys: HashMap::new(),
+            permissions: (0..=13).collect(),
+            requested_access_keys: HashMap::new(),
+            services,
+            files: Vec::new(),
+            unknown_file_ids: HashMap::new(),
+            tag_siblings: HashMap::new(),
+            tag_parents: HashMap::new(),
+            potential_duplicates: Vec::new(),
+            false_positives: Vec::new(),
+            alternates: Vec::new(),
+            duplicate_kings: HashMap::new(),
+            url_classes: Vec::new(),
+            imported_urls: Vec::new(),
+            cookies: Vec::new(),
+            user_agent: None,
+            root_page,
+            files_directory: None,
+            database_locked: false,
+            ignore_range_requests: false,
+            failing_requests: None,
+            request_counts: HashMap::new(),
+            access_key_request_counts: HashMap::new(),
+        }
+    }
+}
+
+impl MockStore {
+    /// Creates a new store with the default services
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the access key that is accepted by the mock
+    pub fn set_access_key<S: ToString>(&mut self, access_key: S) {
+        self.access_key = access_key.to_string();
+    }
+
+    /// Sets the basic permissions of the access key
+    pub fn set_permissions(&mut self, permissions: Vec<u32>) {
+        self.permissions = permissions;
+    }
+
+    /// Returns the permissions of the access key or the access keys
+    /// requested with `request_new_permissions`
+    pub(crate) fn permissions_of(&self, access_key: &str) -> Option<&[u32]> {
+        if access_key == self.access_key {
+            Some(&self.permissions)
+        } else {
+            self.requested_access_keys
+                .get(access_key)
+                .map(|permissions| permissions.as_slice())
+        }
+    }
+
+    /// Sets the directory the file storage of the mock is written to. Without
+    /// a directory the file paths returned by the mock can't be read.
+    pub fn set_files_directory<P: Into<PathBuf>>(&mut self, directory: P) {
+        self.files_directory = Some(directory.into());
+    }
+
+    /// Returns if the database is locked with `manage_database/lock_on`
+    pub fn is_database_locked(&self) -> bool {
+        self.database_locked
+    }
+
+    /// Responds with the complete file to requests with a `Range` header
+    /// like servers that don't support range requests
+    pub fn set_ignore_range_requests(&mut self, ignore: bool) {
+        self.ignore_range_requests = ignore;
+    }
+
+    /// Responds to the next `count` requests with the given http status
+    /// before they reach the endpoint
+    pub fn fail_next_requests(&mut self, count: usize, status: u16) {
+        self.failing_requests = Some((count, status));
+    }
+
+    /// Returns the number of requests the mock received for the given endpoint path
+    pub fn request_count(&self, path: &str) -> usize {
+        self.request_counts.get(path).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of requests the mock received for the given endpoint path
+    /// that were authenticated with the access key
+    pub fn access_key_request_count(&self, path: &str) -> usize {
+        self.access_key_request_counts
+            .get(path)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Invalidates all session keys so that the next request
+    /// with a session key fails as expired
+    pub fn expire_sessions(&mut self) {
+        self.session_keys.clear();
+    }
+
+    /// Returns all services of the mock
+    pub fn services(&self) -> &[MockService] {
+        &self.services
+    }
+
+    /// Adds a service and returns its key
+    pub fn add_service<S: ToString>(&mut self, name: S, service_type: ServiceType) -> String {
+        let key = random_key();
+        self.services
+            .push(MockService::new(name, key.clone(), service_type));
+
+        key
+    }
+
+    /// Returns the service with the given name
+    pub fn service_by_name(&self, name: &str) -> Option<&MockService> {
+        self.services.iter().find(|s| s.name == name)
+    }
+
+    /// Returns the service with the given key
+    pub fn service_by_key(&self, key: &str) -> Option<&MockService> {
+        self.services.iter().find(|s| s.key == key)
+    }
+
+    /// Adds a file to the store and returns its file id.
+    /// If a file with the same hash exists it is replaced.
+    pub fn add_file(&mut self, mut file: MockFile) -> u64 {
+        if let Some(existing) = self.files.iter_mut().find(|f| f.hash == file.hash) {
+            file.file_id = existing.file_id;
+            *existing = file;
+
+            existing.file_id
+        } else {
+            file.file_id = self.next_file_id();
+            let file_id = file.file_id;
+            self.files.push(file);
+
+            file_id
+        }
+    }
+
+    /// Returns the file id that was created for a hash of a file
+    /// that isn't in the store
+    pub fn unknown_file_id(&mut self, hash: &str) -> u64 {
+        if let Some(file_id) = self.unknown_file_ids.get(hash) {
+            return *file_id;
+        }
+        let file_id = self.next_file_id();
+        self.unknown_file_ids.insert(hash.to_string(), file_id);
+
+        file_id
+    }
+
+    fn next_file_id(&self) -> u64 {
+        self.files
+            .iter()
+            .map(|f| f.file_id)
+            .chain(self.unknown_file_ids.values().copied())
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Returns all files of the store ordered by their id
+    pub fn files(&self) -> &[MockFile] {
+        &self.files
+    }
+
+    /// Returns the file with the given hash
+    pub fn file(&self, hash: &str) -> Option<&MockFile> {
+        self.files.iter().find(|f| f.hash == hash)
+    }
+
+    /// Returns the file with the given hash for modification
+    pub fn file_mut(&mut self, hash: &str) -> Option<&mut MockFile> {
+        self.files.iter_mut().find(|f| f.hash == hash)
+    }
+
+    /// Returns the file with the given id
+    pub fn file_by_id(&self, file_id: u64) -> Option<&MockFile> {
+        self.files.iter().find(|f| f.file_id == file_id)
+    }
+
+    /// Adds a sibling relationship on the tag service so that the tag
+    /// is displayed as the ideal tag
+    pub fn add_tag_sibling<S1: ToString, S2: ToString, S3: ToString>(
+        &mut self,
+        service_key: S1,
+        tag: S2,
+        ideal_tag: S3,
+    ) {
+        self.tag_siblings
+            .entry(service_key.to_string())
+            .or_default()
+            .insert(tag.to_string(), ideal_tag.to_string());
+    }
+
+    /// Adds a parent relationship on the tag service
+    pub fn add_tag_parent<S1: ToString, S2: ToString, S3: ToString>(
+        &mut self,
+        service_key: S1,
+        child: S2,
+        parent: S3,
+    ) {
+        self.tag_parents
+            .entry(service_key.to_string())
+            .or_default()
+            .push((child.to_string(), parent.to_string()));
+    }
+
+    /// Marks two files as potential duplicates
+    pub fn add_potential_duplicates<S1: ToString, S2: ToString>(&mut self, hash_a: S1, hash_b: S2) {
+        let pair = (hash_a.to_string(), hash_b.to_string());
+
+        if !contains_pair(&self.potential_duplicates, &pair.0, &pair.1) {
+            self.potential_duplicates.push(pair);
+        }
+    }
+
+    /// Returns all pairs of potential duplicates
+    pub fn potential_duplicates(&self) -> &[(String, String)] {
+        &self.potential_duplicates
+    }
+
+    /// Returns the best file of the duplicate group of the file
+    pub fn king(&self, hash: &str) -> String {
+        self.duplicate_kings
+            .get(hash)
+            .cloned()
+            .unwrap_or_else(|| hash.to_string())
+    }
+
+    /// Returns all files in the duplicate group of the file including the file itself.
+    /// The group of a file without duplicates only contains the file
+    pub fn duplicates(&self, hash: &str) -> Vec<String> {
+        let king = self.king(hash);
+        let mut duplicates: Vec<String> = self
+            .duplicate_kings
+            .iter()
+            .filter(|(_, k)| **k == king)
+            .map(|(h, _)| h.clone())
+            .collect();
+        if !duplicates.iter().any(|h| h == hash) {
+            duplicates.push(hash.to_string());
+        }
+        duplicates.sort();
+
+        duplicates
+    }
+
+    /// Adds an url class that is used to classify urls
+    pub fn add_url_class(&mut self, url_class: MockUrlClass) {
+        self.url_classes.push(url_class);
+    }
+
+    /// Returns all urls that were sent to the mock for importing
+    pub fn imported_urls(&self) -> &[String] {
+        &self.imported_urls
+    }
+
+    /// Returns all cookies that have been set
+    pub fn cookies(&self) -> &[MockCookie] {
+        &self.cookies
+    }
+
+    /// Returns the user agent that has been set
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Returns the top level page
+    pub fn root_page(&self) -> &MockPage {
+        &self.root_page
+    }
+
+    /// Adds a page below the top level page and returns its key
+    pub fn add_page(&mut self, page: MockPage) -> String {
+        let key = page.page_key.clone();
+        self.root_page.pages.push(page);
+
+        key
+    }
+
+    /// Returns the page with the given key
+    pub fn page(&self, page_key: &str) -> Option<&MockPage> {
+        self.root_page.find(page_key)
+    }
+}
+
+/// A file or tag service of the mock
+#[derive(Clone, Debug)]
+pub struct MockService {
+    pub name: String,
+    pub key: String,
+    pub service_type: ServiceType,
+}
+
+impl MockService {
+    pub fn new<S1: ToString, S2: ToString>(name: S1, key: S2, service_type: ServiceType) -> Self {
+        Self {
+            name: name.to_string(),
+            key: key.to_string(),
+            service_type,
+        }
+    }
+
+    /// Returns if tags can be assigned with the service
+    pub fn is_tag_service(&self) -> bool {
+        matches!(
+            self.service_type,
+            ServiceType::LocalTags | ServiceType::TagRepositories
+        )
+    }
+
+    /// Returns if content can be pending to the service
+    pub fn is_repository(&self) -> bool {
+        matches!(
+            self.service_type,
+            ServiceType::TagRepositories | ServiceType::FileRepositories
+        )
+    }
+
+    /// Returns the human readable service type
+    pub fn type_pretty(&self) -> &'static str {
+        match self.service_type {
+            ServiceType::LocalTags => "local tag service",
+            ServiceType::TagRepositories => "hydrus tag repository",
+            ServiceType::LocalFiles => "local file domain",
+            ServiceType::FileRepositories => "hydrus file repository",
+            ServiceType::AllLocalFiles => "virtual combined local file service",
+            ServiceType::AllKnownFiles => "virtual combined file service",
+            ServiceType::AllKnownTags => "virtual combined tag service",
+            ServiceType::Trash => "local trash file domain",
+            ServiceType::LikeRatings => "local like/dislike rating service",
+            ServiceType::NumericalRatings => "local numerical rating service",
+            ServiceType::IncDecRatings => "local inc/dec rating service",
+            ServiceType::Ipfs => "ipfs daemon",
+            ServiceType::AllDeletedFiles => "virtual deleted file domain",
+            ServiceType::RepositoryUpdates => "local update file domain",
+            ServiceType::AllMyFiles => "virtual combined local media service",
+            ServiceType::ServerAdministration => "hydrus server administration service",
+        }
+    }
+
+    /// Returns if files can be rated with the service
+    pub fn is_rating_service(&self) -> bool {
+        matches!(
+            self.service_type,
+            ServiceType::LikeRatings | ServiceType::NumericalRatings | ServiceType::IncDecRatings
+        )
+    }
+}
+
+/// A file stored in the mock
+#[derive(Clone, Debug)]
+pub struct MockFile {
+    pub file_id: u64,
+    pub hash: String,
+    pub bytes: Vec<u8>,
+    pub mime: String,
+    pub ext: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration: Option<u64>,
+    pub num_frames: Option<u64>,
+    pub has_audio: bool,
+    pub is_inbox: bool,
+    pub is_trashed: bool,
+    pub time_imported: u64,
+    pub time_modified: u64,
+    pub time_deleted: Option<u64>,
+    pub known_urls: Vec<String>,
+    pub blurhash: Option<String>,
+    /// Tags mapped by service key and tag status
+    pub tags: HashMap<String, HashMap<String, Vec<String>>>,
+    pub notes: HashMap<String, String>,
+    /// Ratings mapped by rating service key
+    pub ratings: HashMap<String, Rating>,
+}
+
+impl MockFile {
+    /// Creates a new file from its content. The hash, mime type and
+    /// dimensions are detected from the content
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+        let (mime, ext) = detect_mime(&bytes).unwrap_or(("application/octet-stream", ""));
+        let (width, height) = detect_dimensions(&bytes)
+            .map(|(w, h)| (Some(w), Some(h)))
+            .unwrap_or((None, None));
+        let now = now();
+
+        Self {
+            file_id: 0,
+            hash,
+            mime: mime.to_string(),
+            ext: ext.to_string(),
+            bytes,
+            width,
+            height,
+            duration: None,
+            num_frames: None,
+            has_audio: false,
+            is_inbox: true,
+            is_trashed: false,
+            time_imported: now,
+            time_modified: now,
+            time_deleted: None,
+            known_urls: Vec::new(),
+            blurhash: None,
+            tags: HashMap::new(),
+            notes: HashMap::new(),
+            ratings: HashMap::new(),
+        }
+    }
+
+    /// Overrides the hash that was calculated from the content
+    pub fn with_hash<S: ToString>(mut self, hash: S) -> Self {
+        self.hash = hash.to_string();
+        self
+    }
+
+    /// Sets the dimensions of the file in pixels
+    pub fn with_dimensions(mut self, width: u32, height: u32) -> Self {
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    /// Sets the blurhash of the file's thumbnail
+    pub fn with_blurhash<S: ToString>(mut self, blurhash: S) -> Self {
+        self.blurhash = Some(blurhash.to_string());
+        self
+    }
+
+    /// Adds current tags for the service with the given key
+    pub fn add_tags<S: ToString>(mut self, service_key: S, tags: Vec<String>) -> Self {
+        let current = self
+            .tags
+            .entry(service_key.to_string())
+            .or_default()
+            .entry(TAG_STATUS_CURRENT.to_string())
+            .or_default();
+        for tag in tags {
+            if !current.contains(&tag) {
+                current.push(tag);
+            }
+        }
+        self
+    }
+
+    /// Associates the file with an url
+    pub fn add_url<S: ToString>(mut self, url: S) -> Self {
+        self.known_urls.push(url.to_string());
+        self
+    }
+
+    /// Adds a note to the file
+    pub fn add_note<S1: ToString, S2: ToString>(mut self, name: S1, content: S2) -> Self {
+        self.notes.insert(name.to_string(), content.to_string());
+        self
+    }
+
+    /// Rates the file on the service with the given key
+    pub fn with_rating<S: ToString>(mut self, service_key: S, rating: Rating) -> Self {
+        self.ratings.insert(service_key.to_string(), rating);
+        self
+    }
+
+    /// Moves the file out of the inbox
+    pub fn archived(mut self) -> Self {
+        self.is_inbox = false;
+        self
+    }
+
+    /// Moves the file to the trash
+    pub fn trashed(mut self) -> Self {
+        self.is_trashed = true;
+        self.time_deleted = Some(now());
+        self
+    }
+
+    /// Returns the tags with the given status for a service
+    pub fn tags_with_status(&self, service_key: &str, status: &str) -> Vec<String> {
+        self.tags
+            .get(service_key)
+            .and_then(|statuses| statuses.get(status))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// A rule to classify urls
+#[derive(Clone, Debug)]
+pub struct MockUrlClass {
+    pub name: String,
+    pub url_type: u8,
+    pub pattern: Regex,
+}
+
+impl MockUrlClass {
+    /// Creates a new url class that matches urls with the given regex
+    pub fn new<S: ToString>(name: S, url_type: u8, pattern: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern).map_err(|e| Error::BuildError(e.to_string()))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            url_type,
+            pattern,
+        })
+    }
+
+    /// Returns the url class for urls that don't match any other class
+    pub(crate) fn unknown() -> (String, u8, bool) {
+        (String::from("unknown url"), URL_TYPE_UNKNOWN, false)
+    }
+}
+
+/// A cookie stored in the mock
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub expires: Option<u64>,
+}
+
+/// A page of the mock
+#[derive(Clone, Debug)]
+pub struct MockPage {
+    pub name: String,
+    pub page_key: String,
+    pub page_type: u32,
+    pub selected: bool,
+    pub hashes: Vec<String>,
+    pub pages: Vec<MockPage>,
+}
+
+impl MockPage {
+    /// Creates a new page with a random key
+    pub fn new<S: ToString>(name: S, page_type: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            page_key: random_key(),
+            page_type,
+            selected: false,
+            hashes: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Returns if files can be added to the page
+    pub fn is_media_page(&self) -> bool {
+        !matches!(
+            self.page_type,
+            PAGE_TYPE_PAGE_OF_PAGES | PAGE_TYPE_TOP_NOTEBOOK
+        )
+    }
+
+    /// Searches the page and its children for the page with the given key
+    pub fn find(&self, page_key: &str) -> Option<&MockPage> {
+        if self.page_key == page_key {
+            Some(self)
+        } else {
+            self.pages.iter().find_map(|p| p.find(page_key))
+        }
+    }
+
+    /// Searches the page and its children for the page with the given key
+    pub fn find_mut(&mut self, page_key: &str) -> Option<&mut MockPage> {
+        if self.page_key == page_key {
+            Some(self)
+        } else {
+            self.pages.iter_mut().find_map(|p| p.find_mut(page_key))
+        }
+    }
+
+    /// Searches the page and its children for a page with the given name
+    pub fn find_by_name_mut(&mut self, name: &str) -> Option<&mut MockPage> {
+        if self.name == name {
+            Some(self)
+        } else {
+            self.pages.iter_mut().find_map(|p| p.find_by_name_mut(name))
+        }
+    }
+
+    /// Selects the page with the given key and deselects all other pages
+    pub(crate) fn select(&mut self, page_key: &str) -> bool {
+        let mut found = self.page_key == page_key;
+        self.selected = found;
+
+        for page in &mut self.pages {
+            found |= page.select(page_key);
+        }
+        if found {
+            self.selected = true;
+        }
+
+        found
+    }
+}
+
+/// Returns if the list contains the pair of hashes in any order
+pub(crate) fn contains_pair(pairs: &[(String, String)], hash_a: &str, hash_b: &str) -> bool {
+    pairs
+        .iter()
+        .any(|(a, b)| (a == hash_a && b == hash_b) || (a == hash_b && b == hash_a))
+}
+
+/// Returns the current unix timestamp in seconds
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Creates a random hex encoded key
+pub(crate) fn random_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Detects the mime type and extension of supported file types from their magic bytes
+pub(crate) fn detect_mime(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some(("image/png", ".png")),
+        [0xFF, 0xD8, 0xFF, ..] => Some(("image/jpeg", ".jpg")),
+        [b'G', b'I', b'F', b'8', ..] => Some(("image/gif", ".gif")),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+            Some(("image/webp", ".webp"))
+        }
+        _ => None,
+    }
+}
+
+/// Reads the dimensions from the header of png and gif files
+fn detect_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', _, _, _, _, _, _, _, _, _, _, _, _, w0, w1, w2, w3, h0, h1, h2, h3, ..] => {
+            Some((
+                u32::from_be_bytes([*w0, *w1, *w2, *w3]),
+                u32::from_be_bytes([*h0, *h1, *h2, *h3]),
+            ))
+        }
+        [b'G', b'I', b'F', b'8', _, _, w0, w1, h0, h1, ..] => Some((
+            u16::from_le_bytes([*w0, *w1]) as u32,
+            u16::from_le_bytes([*h0, *h1]) as u32,
+        )),
+        _ => None,
+    }
+}
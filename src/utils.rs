@@ -1,6 +1,9 @@
 use crate::api_core::common::FileIdentifier;
+use crate::error::{Error, Result};
 use crate::wrapper::tag::Tag;
 use chrono::{Datelike, Duration};
+use serde::{Deserialize, Deserializer};
+use std::ops::{Bound, RangeBounds};
 
 /// Converts a list of tags into a list of string tags
 pub fn tag_list_to_string_list(tags: Vec<Tag>) -> Vec<String> {
@@ -41,6 +44,80 @@ pub(crate) fn format_duration(duration: Duration) -> String {
     expression
 }
 
+/// A timestamp in seconds that might contain milliseconds as fractional part
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Seconds {
+    Integer(u64),
+    Float(f64),
+}
+
+impl From<Seconds> for f64 {
+    fn from(seconds: Seconds) -> Self {
+        match seconds {
+            Seconds::Integer(i) => i as f64,
+            Seconds::Float(f) => f,
+        }
+    }
+}
+
+/// Deserializes a timestamp in seconds that might contain milliseconds
+/// as fractional part. The milliseconds are truncated.
+pub(crate) fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u64, D::Error> {
+    let seconds = match Seconds::deserialize(deserializer)? {
+        Seconds::Integer(i) => i,
+        Seconds::Float(f) => f as u64,
+    };
+
+    Ok(seconds)
+}
+
+/// Deserializes an optional timestamp in seconds with milliseconds
+/// as fractional part
+pub(crate) fn deserialize_optional_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<f64>, D::Error> {
+    let seconds = Option::<Seconds>::deserialize(deserializer)?;
+
+    Ok(seconds.map(f64::from))
+}
+
+/// Splits file ids into chunks of at most `chunk_size` ids whose serialized
+/// list stays below `max_query_length` characters when url encoded
+pub(crate) fn chunk_file_ids(
+    file_ids: Vec<u64>,
+    chunk_size: usize,
+    max_query_length: usize,
+) -> Vec<Vec<u64>> {
+    // the brackets of the list are encoded as %5B and %5D
+    const LIST_LENGTH: usize = 6;
+    // the separating comma is encoded as %2C
+    const SEPARATOR_LENGTH: usize = 3;
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::new();
+    let mut query_length = LIST_LENGTH;
+
+    for id in file_ids {
+        let id_length = id.to_string().len() + SEPARATOR_LENGTH;
+
+        if !chunk.is_empty()
+            && (chunk.len() >= chunk_size || query_length + id_length > max_query_length)
+        {
+            chunks.push(std::mem::take(&mut chunk));
+            query_length = LIST_LENGTH;
+        }
+        query_length += id_length;
+        chunk.push(id);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
 pub(crate) fn split_file_identifiers_into_hashes_and_ids(
     files: Vec<FileIdentifier>,
 ) -> (Vec<u64>, Vec<String>) {
@@ -55,3 +132,47 @@ pub(crate) fn split_file_identifiers_into_hashes_and_ids(
     }
     (ids, hashes)
 }
+
+/// Returns the first byte of a range of bytes or `None` if it overflows
+pub(crate) fn range_start<R: RangeBounds<u64>>(range: &R) -> Option<u64> {
+    match range.start_bound() {
+        Bound::Included(start) => Some(*start),
+        Bound::Excluded(start) => start.checked_add(1),
+        Bound::Unbounded => Some(0),
+    }
+}
+
+/// Formats a range of bytes as the value of a http `Range` header.
+/// Empty ranges are rejected as they can't be expressed in the header.
+pub(crate) fn format_range_header<R: RangeBounds<u64>>(range: R) -> Result<String> {
+    let end = match range.end_bound() {
+        Bound::Included(end) => Some(Some(*end)),
+        Bound::Excluded(end) => end.checked_sub(1).map(Some),
+        Bound::Unbounded => Some(None),
+    };
+
+    match (range_start(&range), end) {
+        (Some(start), Some(None)) => Ok(format!("bytes={}-", start)),
+        (Some(start), Some(Some(end))) if start <= end => Ok(format!("bytes={}-{}", start, end)),
+        _ => Err(Error::BuildError(String::from(
+            "The requested byte range is empty",
+        ))),
+    }
+}
+
+/// Returns the first byte of a `Content-Range` header value
+/// in the format `bytes <start>-<end>/<length>`
+pub(crate) fn parse_content_range_start(content_range: &str) -> Option<u64> {
+    let range = content_range.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+
+    start.trim().parse().ok()
+}
+
+/// Returns the complete length of a file from a `Content-Range` header value
+/// in the format `bytes <start>-<end>/<length>`
+pub(crate) fn parse_content_range_length(content_range: &str) -> Option<u64> {
+    let (_, length) = content_range.rsplit_once('/')?;
+
+    length.trim().parse().ok()
+}
@@ -0,0 +1,142 @@
+use crate::api_core::common::FileSelection;
+use crate::api_core::endpoints::managing_file_relationships::{
+    DuplicateRelationship, FileRelationship,
+};
+use crate::error::Result;
+use crate::Client;
+
+pub struct DuplicatesBuilder {
+    client: Client,
+    pairs: Vec<(String, String, DuplicateRelationship)>,
+    relationships: Vec<FileRelationship>,
+    kings: Vec<String>,
+    removed_potentials: Vec<String>,
+    merge_content: bool,
+    delete_worse: bool,
+}
+
+impl DuplicatesBuilder {
+    pub(crate) fn new(client: Client) -> Self {
+        Self {
+            client,
+            pairs: Vec::new(),
+            relationships: Vec::new(),
+            kings: Vec::new(),
+            removed_potentials: Vec::new(),
+            merge_content: true,
+            delete_worse: false,
+        }
+    }
+
+    /// Marks the first file as a better duplicate of the second one
+    pub fn set_better<S1: ToString, S2: ToString>(self, better: S1, worse: S2) -> Self {
+        self.add_pair(better, worse, DuplicateRelationship::ABetter)
+    }
+
+    /// Marks both files as duplicates of the same quality
+    pub fn set_same_quality<S1: ToString, S2: ToString>(self, hash_a: S1, hash_b: S2) -> Self {
+        self.add_pair(hash_a, hash_b, DuplicateRelationship::SameQuality)
+    }
+
+    /// Marks both files as alternates of each other
+    pub fn set_alternates<S1: ToString, S2: ToString>(self, hash_a: S1, hash_b: S2) -> Self {
+        self.add_pair(hash_a, hash_b, DuplicateRelationship::Alternates)
+    }
+
+    /// Marks both files as not being duplicates
+    pub fn set_false_positives<S1: ToString, S2: ToString>(self, hash_a: S1, hash_b: S2) -> Self {
+        self.add_pair(hash_a, hash_b, DuplicateRelationship::FalsePositives)
+    }
+
+    /// Queues both files to be compared in the duplicate filter
+    pub fn set_potential_duplicates<S1: ToString, S2: ToString>(
+        self,
+        hash_a: S1,
+        hash_b: S2,
+    ) -> Self {
+        self.add_pair(hash_a, hash_b, DuplicateRelationship::PotentialDuplicates)
+    }
+
+    /// Adds a relationship with custom merge and delete options
+    pub fn add_relationship(mut self, relationship: FileRelationship) -> Self {
+        self.relationships.push(relationship);
+
+        self
+    }
+
+    /// If the content of duplicates should be merged with the default merge options.
+    /// This is enabled by default
+    pub fn merge_content(mut self, merge_content: bool) -> Self {
+        self.merge_content = merge_content;
+
+        self
+    }
+
+    /// If the worse file of a pair should be deleted
+    pub fn delete_worse(mut self, delete_worse: bool) -> Self {
+        self.delete_worse = delete_worse;
+
+        self
+    }
+
+    /// Sets the file as the best file of its duplicate group
+    pub fn set_king<S: ToString>(mut self, hash: S) -> Self {
+        self.kings.push(hash.to_string());
+
+        self
+    }
+
+    /// Removes all potential duplicate pairs the file is part of
+    pub fn remove_potentials<S: ToString>(mut self, hash: S) -> Self {
+        self.removed_potentials.push(hash.to_string());
+
+        self
+    }
+
+    /// Applies all changes to the duplicates
+    pub async fn run(self) -> Result<()> {
+        let merge_content = self.merge_content;
+        let delete_worse = self.delete_worse;
+        let mut relationships: Vec<FileRelationship> = self
+            .pairs
+            .into_iter()
+            .map(|(hash_a, hash_b, relationship)| {
+                let mut file_relationship = FileRelationship::new(hash_a, hash_b, relationship);
+                file_relationship.do_default_content_merge = merge_content;
+                if delete_worse && relationship == DuplicateRelationship::ABetter {
+                    file_relationship.delete_b = Some(true);
+                }
+                file_relationship
+            })
+            .collect();
+        relationships.extend(self.relationships);
+
+        if !relationships.is_empty() {
+            self.client.set_file_relationships(relationships).await?;
+        }
+        if !self.kings.is_empty() {
+            self.client
+                .set_kings(FileSelection::by_hashes(self.kings))
+                .await?;
+        }
+        if !self.removed_potentials.is_empty() {
+            self.client
+                .remove_potentials(FileSelection::by_hashes(self.removed_potentials))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn add_pair<S1: ToString, S2: ToString>(
+        mut self,
+        hash_a: S1,
+        hash_b: S2,
+        relationship: DuplicateRelationship,
+    ) -> Self {
+        self.pairs
+            .push((hash_a.to_string(), hash_b.to_string(), relationship));
+
+        self
+    }
+}
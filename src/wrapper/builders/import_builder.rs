@@ -1,4 +1,4 @@
-use crate::api_core::common::ServiceIdentifier;
+use crate::api_core::common::{FileUploadStream, ServiceIdentifier};
 use crate::api_core::endpoints::adding_files::{STATUS_IMPORT_FAILED, STATUS_IMPORT_VETOED};
 use crate::api_core::endpoints::adding_urls::AddUrlRequestBuilder;
 use crate::error::{Error, Result};
@@ -8,8 +8,11 @@ use crate::wrapper::page::PageIdentifier;
 use crate::wrapper::tag::Tag;
 use crate::wrapper::url::Url;
 use crate::Client;
+use bytes::Bytes;
+use futures_util::Stream;
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{self, Read};
+use tokio::io::AsyncRead;
 
 pub struct ImportBuilder {
     pub(crate) client: Client,
@@ -31,6 +34,7 @@ impl ImportBuilder {
 pub enum FileImport {
     Path(String),
     Binary(Vec<u8>),
+    Stream(FileUploadStream),
 }
 
 impl FileImport {
@@ -38,10 +42,25 @@ impl FileImport {
         Self::Path(path.to_string())
     }
 
-    pub fn binary<R: Read>(reader: &mut R) -> Self {
+    /// Reads the complete content of the reader into memory
+    pub fn binary<R: Read>(reader: &mut R) -> Result<Self> {
         let mut bytes = Vec::new();
-        let _ = reader.read_to_end(&mut bytes);
-        Self::Binary(bytes)
+        reader.read_to_end(&mut bytes)?;
+
+        Ok(Self::Binary(bytes))
+    }
+
+    /// Streams the file content to hydrus with an optional known length
+    pub fn stream<S>(stream: S, length: Option<u64>) -> Self
+    where
+        S: Stream<Item = io::Result<Bytes>> + Send + 'static,
+    {
+        Self::Stream(FileUploadStream::new(stream, length))
+    }
+
+    /// Streams the file content from an async reader with an optional known length
+    pub fn reader<R: AsyncRead + Send + 'static>(reader: R, length: Option<u64>) -> Self {
+        Self::Stream(FileUploadStream::from_reader(reader, length))
     }
 }
 
@@ -55,6 +74,7 @@ impl FileImportBuilder {
         let response = match self.file {
             FileImport::Path(path) => self.client.add_file(path).await?,
             FileImport::Binary(b) => self.client.add_binary_file(b).await?,
+            FileImport::Stream(s) => self.client.add_binary_file_stream(s).await?,
         };
 
         if response.status == STATUS_IMPORT_FAILED {
@@ -1,7 +1,10 @@
 pub mod delete_files_builder;
+pub mod duplicates_builder;
 pub mod import_builder;
 pub mod notes_builder;
 pub mod or_chain_builder;
+pub mod render_builder;
 pub mod search_builder;
 pub mod tag_builder;
+pub mod tag_suggestions_builder;
 pub mod tagging_builder;
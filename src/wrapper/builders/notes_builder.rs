@@ -1,4 +1,5 @@
 use crate::api_core::common::FileIdentifier;
+use crate::api_core::endpoints::adding_notes::{NoteConflictResolution, SetNotesRequest};
 use crate::error::Result;
 use crate::Client;
 use std::collections::HashMap;
@@ -8,6 +9,9 @@ pub struct AddNotesBuilder {
     client: Client,
     file: FileIdentifier,
     notes: HashMap<String, String>,
+    merge_cleverly: Option<bool>,
+    extend_existing_note_if_possible: Option<bool>,
+    conflict_resolution: Option<NoteConflictResolution>,
 }
 
 impl AddNotesBuilder {
@@ -17,6 +21,9 @@ impl AddNotesBuilder {
             client,
             file,
             notes: HashMap::new(),
+            merge_cleverly: None,
+            extend_existing_note_if_possible: None,
+            conflict_resolution: None,
         }
     }
 
@@ -40,8 +47,43 @@ impl AddNotesBuilder {
         self
     }
 
+    /// Merges the notes with existing notes of the same name instead of overwriting them.
+    /// Notes whose content already exists on the file are skipped
+    pub fn merge_cleverly(mut self, merge_cleverly: bool) -> Self {
+        self.merge_cleverly = Some(merge_cleverly);
+
+        self
+    }
+
+    /// Replaces existing notes that the new notes start with instead of
+    /// treating them as a conflict
+    pub fn extend_existing_note_if_possible(mut self, extend: bool) -> Self {
+        self.extend_existing_note_if_possible = Some(extend);
+
+        self
+    }
+
+    /// Sets how conflicts with existing notes of the same name are resolved
+    pub fn conflict_resolution(mut self, resolution: NoteConflictResolution) -> Self {
+        self.conflict_resolution = Some(resolution);
+
+        self
+    }
+
     /// Adds all notes mentioned in the builder to the given file
     pub async fn run(self) -> Result<()> {
-        self.client.set_notes(self.file, self.notes).await
+        let mut request = SetNotesRequest::new(self.file, self.notes);
+        if let Some(merge_cleverly) = self.merge_cleverly {
+            request = request.merge_cleverly(merge_cleverly);
+        }
+        if let Some(extend) = self.extend_existing_note_if_possible {
+            request = request.extend_existing_note_if_possible(extend);
+        }
+        if let Some(resolution) = self.conflict_resolution {
+            request = request.conflict_resolution(resolution);
+        }
+        self.client.set_notes_with_request(request).await?;
+
+        Ok(())
     }
 }
@@ -1,7 +1,7 @@
 use crate::wrapper::or_chain::OrChain;
 use crate::wrapper::tag::Tag;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct OrChainBuilder {
     tags: Vec<Tag>,
 }
@@ -0,0 +1,48 @@
+use crate::api_core::common::{FileIdentifier, FileRecord};
+use crate::api_core::endpoints::searching_and_fetching_files::{RenderFormat, RenderOptions};
+use crate::error::Result;
+use crate::Client;
+
+/// Builder to render a file to a format that can be displayed by browsers
+pub struct RenderBuilder {
+    client: Client,
+    file: FileIdentifier,
+    options: RenderOptions,
+}
+
+impl RenderBuilder {
+    pub(crate) fn new(client: Client, file: FileIdentifier) -> Self {
+        Self {
+            client,
+            file,
+            options: RenderOptions::new(),
+        }
+    }
+
+    /// Sets the format the file is rendered to
+    pub fn format(mut self, format: RenderFormat) -> Self {
+        self.options = self.options.format(format);
+
+        self
+    }
+
+    /// Sets the quality of jpeg and webp images (0-100) or
+    /// the compression level of png images (0-9)
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.options = self.options.quality(quality);
+
+        self
+    }
+
+    /// Scales the rendered image to the given size
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.options = self.options.size(width, height);
+
+        self
+    }
+
+    /// Renders the file
+    pub async fn run(self) -> Result<FileRecord> {
+        self.client.render_file(self.file, self.options).await
+    }
+}
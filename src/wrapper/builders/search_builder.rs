@@ -1,32 +1,20 @@
+pub use crate::api_core::endpoints::searching_and_fetching_files::SortType;
 use crate::api_core::endpoints::searching_and_fetching_files::{
-    FileSearchOptions, SearchQueryEntry,
+    FileMetadataType, FileSearchOptions, SearchQueryEntry,
 };
 use crate::error::Result;
-use crate::wrapper::hydrus_file::HydrusFile;
+use crate::wrapper::hydrus_file::{HydrusFile, IntoHydrusFile};
 use crate::wrapper::or_chain::OrChain;
+use crate::wrapper::search_expression::SearchExpression;
 use crate::wrapper::service::ServiceName;
 use crate::wrapper::tag::Tag;
 use crate::Client;
+use futures_util::{Stream, TryStreamExt};
 
-pub enum SortType {
-    FileSize,
-    Duration,
-    ImportTime,
-    FileType,
-    Random,
-    Width,
-    Height,
-    Ratio,
-    NumberOfPixels,
-    NumberOfTags,
-    NumberOfMediaViewers,
-    MediaViewTime,
-    Bitrate,
-    HasAudio,
-    ModifiedTime,
-    Framerate,
-    NumberOfFrames,
-}
+/// The default number of files whose metadata is fetched with one request
+const DEFAULT_METADATA_CHUNK_SIZE: usize = 256;
+/// The default number of concurrent metadata requests
+const DEFAULT_METADATA_CONCURRENCY: usize = 4;
 
 #[derive(Clone, Debug)]
 pub struct SearchBuilder {
@@ -34,6 +22,8 @@ pub struct SearchBuilder {
     tags: Vec<Tag>,
     or_chains: Vec<OrChain>,
     options: FileSearchOptions,
+    metadata_chunk_size: usize,
+    metadata_concurrency: usize,
 }
 
 impl SearchBuilder {
@@ -43,6 +33,8 @@ impl SearchBuilder {
             tags: Vec::new(),
             or_chains: Vec::new(),
             options: FileSearchOptions::new(),
+            metadata_chunk_size: DEFAULT_METADATA_CHUNK_SIZE,
+            metadata_concurrency: DEFAULT_METADATA_CONCURRENCY,
         }
     }
 
@@ -64,9 +56,23 @@ impl SearchBuilder {
         self
     }
 
+    /// Adds a boolean search expression. It fails if the expression
+    /// can't be expressed as a hydrus search
+    pub fn add_expression(mut self, expression: SearchExpression) -> Result<Self> {
+        for mut clause in expression.into_clauses()? {
+            if clause.len() == 1 {
+                self.tags.push(clause.remove(0));
+            } else {
+                self.or_chains.push(OrChain::new(clause));
+            }
+        }
+
+        Ok(self)
+    }
+
     /// Sets the sort type
     pub fn sort_by(mut self, sort_type: SortType) -> Self {
-        self.options = self.options.sort_type(sort_type as u8);
+        self.options = self.options.sort_type(sort_type);
         self
     }
 
@@ -108,9 +114,56 @@ impl SearchBuilder {
         self
     }
 
+    /// Sets the maximum number of files whose metadata is fetched with one request
+    /// when running the search with metadata
+    pub fn metadata_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.metadata_chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the maximum number of metadata requests that run at the same time
+    /// when running the search with metadata
+    pub fn metadata_concurrency(mut self, concurrency: usize) -> Self {
+        self.metadata_concurrency = concurrency;
+        self
+    }
+
     /// Runs the search
     pub async fn run(self) -> Result<Vec<HydrusFile>> {
         let client = self.client.clone();
+        let (entries, options) = self.into_query();
+        let response = client.search_files_with_hashes(entries, options).await?;
+        let files = response
+            .files()
+            .map(|(file_id, hash)| HydrusFile::from_id_and_hash(client.clone(), file_id, hash))
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Runs the search and returns a stream of files with their metadata.
+    /// The metadata is fetched in chunks while the stream is consumed.
+    /// The files only keep their metadata when it's fetched as
+    /// [FullMetadata](crate::api_core::endpoints::searching_and_fetching_files::FullMetadata).
+    /// Other metadata types are only used to identify the files.
+    pub async fn run_with_metadata<M>(self) -> Result<impl Stream<Item = Result<HydrusFile>>>
+    where
+        M: FileMetadataType + 'static,
+        M::Response: IntoHydrusFile,
+    {
+        let client = self.client.clone();
+        let (chunk_size, concurrency) = (self.metadata_chunk_size, self.metadata_concurrency);
+        let (entries, options) = self.into_query();
+        let response = client.search_files(entries, options).await?;
+        let files = client
+            .get_file_metadata_chunked::<M>(response.file_ids, chunk_size, concurrency)
+            .map_ok(move |metadata| metadata.into_hydrus_file(client.clone()));
+
+        Ok(files)
+    }
+
+    /// Returns the query entries and options of the search
+    fn into_query(self) -> (Vec<SearchQueryEntry>, FileSearchOptions) {
         let mut entries: Vec<SearchQueryEntry> = self
             .tags
             .into_iter()
@@ -123,13 +176,7 @@ impl SearchBuilder {
                 .map(|c| SearchQueryEntry::OrChain(c.into_string_list()))
                 .collect(),
         );
-        let response = client.search_file_hashes(entries, self.options).await?;
-        let files = response
-            .hashes
-            .into_iter()
-            .map(|hash| HydrusFile::from_hash(client.clone(), hash))
-            .collect();
 
-        Ok(files)
+        (entries, self.options)
     }
 }
@@ -53,7 +53,7 @@ impl TagBuilder {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct SystemTagBuilder {
     name: String,
     negated: bool,
@@ -301,6 +301,128 @@ impl SystemTagBuilder {
         ))
     }
 
+    /// Files that have a range of numbers in the namespace (e.g. 'page')
+    pub fn tag_namespace_as_number_between<S: Display>(
+        self,
+        namespace: S,
+        min: u64,
+        max: u64,
+    ) -> Self {
+        self.change_name(format!(
+            "tag as number {} between {} and {}",
+            namespace, min, max
+        ))
+    }
+
+    /// Files with a specific number of notes
+    pub fn number_of_notes(self, comparator: Comparator, value: u64) -> Self {
+        self.change_name(format!("num notes {} {}", comparator, value))
+    }
+
+    /// Files that have a note with the given name
+    pub fn has_note_with_name<S: Display>(self, name: S) -> Self {
+        self.change_name(format!("has note with name {}", name))
+    }
+
+    /// Files that don't have a note with the given name
+    pub fn no_note_with_name<S: Display>(self, name: S) -> Self {
+        self.change_name(format!("no note with name {}", name))
+    }
+
+    /// Files that have been rated on the given rating service
+    pub fn has_rating(self, service: ServiceName) -> Self {
+        self.change_name(format!("has a rating for {}", service))
+    }
+
+    /// Files that haven't been rated on the given rating service
+    pub fn no_rating(self, service: ServiceName) -> Self {
+        self.change_name(format!("does not have a rating for {}", service))
+    }
+
+    /// Files with a specific rating on the given rating service.
+    /// The comparator is ignored for like and dislike ratings.
+    pub fn rating(
+        self,
+        service: ServiceName,
+        comparator: Comparator,
+        value: RatingCondition,
+    ) -> Self {
+        self.change_name(format_rating(&service.0, &comparator, &value))
+    }
+
+    /// Files that have last been viewed before / after / at / around a specific date
+    pub fn last_viewed_time<D: Datelike>(self, comparator: Comparator, datetime: D) -> Self {
+        self.change_name(format!(
+            "last viewed time {} {}",
+            comparator,
+            format_datetime(datetime)
+        ))
+    }
+
+    /// Files that have been archived before / after / at / around a specific date
+    pub fn archived_time<D: Datelike>(self, comparator: Comparator, datetime: D) -> Self {
+        self.change_name(format!(
+            "archived time {} {}",
+            comparator,
+            format_datetime(datetime)
+        ))
+    }
+
+    /// Files with exif data
+    pub fn has_exif(self) -> Self {
+        self.change_name("has exif")
+    }
+
+    /// Files without exif data
+    pub fn no_exif(self) -> Self {
+        self.change_name("no exif")
+    }
+
+    /// Files with an embedded icc profile
+    pub fn has_icc_profile(self) -> Self {
+        self.change_name("has icc profile")
+    }
+
+    /// Files without an embedded icc profile
+    pub fn no_icc_profile(self) -> Self {
+        self.change_name("no icc profile")
+    }
+
+    /// Files with human-readable embedded metadata (e.g. png text chunks)
+    pub fn has_embedded_metadata(self) -> Self {
+        self.change_name("has human-readable embedded metadata")
+    }
+
+    /// Files without human-readable embedded metadata
+    pub fn no_embedded_metadata(self) -> Self {
+        self.change_name("no human-readable embedded metadata")
+    }
+
+    /// Files with a specific framerate in frames per second
+    pub fn framerate(self, comparator: Comparator, value: u64) -> Self {
+        self.change_name(format!("framerate {} {}fps", comparator, value))
+    }
+
+    /// Files with a specific number of frames
+    pub fn number_of_frames(self, comparator: Comparator, value: u64) -> Self {
+        self.change_name(format!("number of frames {} {}", comparator, value))
+    }
+
+    /// Files with a specific number of words (e.g. documents)
+    pub fn number_of_words(self, comparator: Comparator, value: u64) -> Self {
+        self.change_name(format!("number of words {} {}", comparator, value))
+    }
+
+    /// Files that have exactly the given url
+    pub fn has_url<S: Display>(self, url: S) -> Self {
+        self.change_name(format!("has url {}", url))
+    }
+
+    /// Files that don't have the given url
+    pub fn does_not_have_url<S: Display>(self, url: S) -> Self {
+        self.change_name(format!("does not have url {}", url))
+    }
+
     fn change_name<S: ToString>(mut self, value: S) -> Self {
         self.name = value.to_string();
 
@@ -477,3 +599,41 @@ impl Display for FileRelationshipType {
         name.fmt(f)
     }
 }
+
+#[derive(Clone, Debug, PartialOrd, PartialEq)]
+pub enum RatingCondition {
+    /// Liked on a like/dislike service
+    Like,
+    /// Disliked on a like/dislike service
+    Dislike,
+    /// A number of stars out of the maximum stars of a numerical service
+    Stars(u32, u32),
+    /// The count of an inc/dec service
+    Count(u32),
+}
+
+/// Formats a rating predicate. Like and dislike ratings are expressed as
+/// `is like` as hydrus doesn't compare them.
+pub(crate) fn format_rating(
+    service: &str,
+    comparator: &Comparator,
+    value: &RatingCondition,
+) -> String {
+    match value {
+        RatingCondition::Like | RatingCondition::Dislike => {
+            format!("rating for {} is {}", service, value)
+        }
+        _ => format!("rating for {} {} {}", service, comparator, value),
+    }
+}
+
+impl Display for RatingCondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RatingCondition::Like => "like".fmt(f),
+            RatingCondition::Dislike => "dislike".fmt(f),
+            RatingCondition::Stars(stars, max) => write!(f, "{}/{}", stars, max),
+            RatingCondition::Count(count) => count.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::api_core::common::ServiceIdentifier;
+use crate::api_core::endpoints::adding_tags::TagDisplayType;
+use crate::error::Result;
+use crate::wrapper::service::ServiceName;
+use crate::wrapper::tag::Tag;
+use crate::Client;
+
+/// A tag that matches the search with the number of files it's assigned to
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagSuggestion {
+    pub tag: Tag,
+    pub count: u64,
+}
+
+pub struct TagSuggestionsBuilder {
+    client: Client,
+    search: String,
+    service: ServiceIdentifier,
+    display_type: TagDisplayType,
+}
+
+impl TagSuggestionsBuilder {
+    pub(crate) fn new(client: Client) -> Self {
+        Self {
+            client,
+            search: String::new(),
+            service: ServiceName::all_known_tags().into(),
+            display_type: TagDisplayType::default(),
+        }
+    }
+
+    /// Sets the text the suggested tags should start with.
+    /// It can contain `*` as wildcard
+    pub fn search<S: ToString>(mut self, search: S) -> Self {
+        self.search = search.to_string();
+        self
+    }
+
+    /// Sets the tag service to search in. Defaults to all known tags
+    pub fn service(mut self, service: ServiceIdentifier) -> Self {
+        self.service = service;
+        self
+    }
+
+    /// Sets if storage or display tags should be suggested
+    pub fn display_type(mut self, display_type: TagDisplayType) -> Self {
+        self.display_type = display_type;
+        self
+    }
+
+    /// Returns the suggested tags
+    pub async fn run(self) -> Result<Vec<TagSuggestion>> {
+        let response = self
+            .client
+            .search_tags(self.search, self.service, self.display_type)
+            .await?;
+        let suggestions = response
+            .tags
+            .into_iter()
+            .map(|t| TagSuggestion {
+                tag: Tag::from(t.value),
+                count: t.count,
+            })
+            .collect();
+
+        Ok(suggestions)
+    }
+}
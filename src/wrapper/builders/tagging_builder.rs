@@ -62,7 +62,7 @@ impl TaggingBuilder {
             for (action, tags) in action_tag_mappings {
                 for tag in tags {
                     request = request.add_tag_with_action(
-                        service.clone().into(),
+                        service.clone(),
                         tag.to_string(),
                         action.clone(),
                     );
@@ -0,0 +1,115 @@
+use crate::api_core::endpoints::managing_database::{BonedStats, ClientOptions};
+use crate::error::{Error, Result};
+use crate::Client;
+use std::future::Future;
+
+/// Manages the database of hydrus
+#[derive(Clone, Debug)]
+pub struct Database {
+    client: Client,
+}
+
+impl Database {
+    pub(crate) fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Locks the database until the returned lock is unlocked or dropped
+    pub async fn lock(&self) -> Result<DatabaseLock> {
+        self.client.lock_database().await?;
+
+        Ok(DatabaseLock {
+            client: Some(self.client.clone()),
+        })
+    }
+
+    /// Locks the database while the given future runs. The database is unlocked
+    /// afterwards even if the future fails.
+    /// ```no_run
+    /// # use hydrus_api::{Client, Hydrus};
+    /// # async fn backup() -> Result<(), hydrus_api::error::Error> {
+    /// let hydrus = Hydrus::new(Client::new("http://127.0.0.1:45869", "<access_key>"));
+    /// hydrus
+    ///     .database()
+    ///     .with_lock(|| async {
+    ///         // copy the database files
+    ///         Ok(())
+    ///     })
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn with_lock<F, Fut, T, E>(&self, f: F) -> std::result::Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: From<Error>,
+    {
+        let lock = self.lock().await?;
+        let result = f().await;
+        let unlock_result = lock.unlock().await;
+
+        let value = result?;
+        unlock_result?;
+
+        Ok(value)
+    }
+
+    /// Forces hydrus to write all pending changes to the database
+    pub async fn force_commit(&self) -> Result<()> {
+        self.client.force_commit().await
+    }
+
+    /// Returns statistics about the files in the database
+    pub async fn statistics(&self) -> Result<BonedStats> {
+        let response = self.client.mr_bones().await?;
+
+        Ok(response.boned_stats)
+    }
+
+    /// Returns the options of the client
+    pub async fn client_options(&self) -> Result<ClientOptions> {
+        let response = self.client.get_client_options().await?;
+
+        Ok(response.options)
+    }
+}
+
+/// A lock on the database of hydrus. The database stays locked until
+/// [DatabaseLock::unlock] is called. If the lock is dropped without being
+/// unlocked the database is unlocked in the background.
+#[derive(Debug)]
+pub struct DatabaseLock {
+    client: Option<Client>,
+}
+
+impl DatabaseLock {
+    /// Unlocks the database
+    pub async fn unlock(mut self) -> Result<()> {
+        match self.client.take() {
+            Some(client) => client.unlock_database().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for DatabaseLock {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    tracing::debug!("Unlocking the database of the dropped lock");
+                    handle.spawn(async move {
+                        if let Err(e) = client.unlock_database().await {
+                            tracing::error!("Failed to unlock the database: {}", e);
+                        }
+                    });
+                }
+                Err(_) => {
+                    tracing::error!(
+                        "The database lock was dropped outside of a tokio runtime and stays locked"
+                    )
+                }
+            }
+        }
+    }
+}
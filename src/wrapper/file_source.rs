@@ -0,0 +1,82 @@
+use crate::api_core::common::{FileIdentifier, FileRecord};
+use crate::error::{Error, Result};
+use crate::Client;
+use std::path::PathBuf;
+
+/// Reads the content of files from the file storage of hydrus if hydrus
+/// runs on the same machine. If hydrus can't provide a path or the files
+/// can't be read from disk they are fetched from the api instead.
+#[derive(Clone, Debug)]
+pub struct FileSource {
+    client: Client,
+}
+
+impl FileSource {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Reads the content of a file
+    pub async fn file(&self, id: FileIdentifier) -> Result<FileRecord> {
+        match self.client.get_file_path(id.clone()).await {
+            Ok(response) => {
+                if let Some(record) = read_local_file(response.path, response.filetype).await {
+                    return Ok(record);
+                }
+            }
+            Err(e) if is_path_unavailable(&e) => {
+                tracing::debug!("Failed to retrieve file path: {}", e)
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.client.get_file(id).await
+    }
+
+    /// Reads the thumbnail of a file
+    pub async fn thumbnail(&self, id: FileIdentifier) -> Result<FileRecord> {
+        match self.client.get_thumbnail_path(id.clone()).await {
+            Ok(response) => {
+                let mime_type = response
+                    .filetype
+                    .unwrap_or_else(|| String::from("image/jpeg"));
+
+                if let Some(record) = read_local_file(response.path, mime_type).await {
+                    return Ok(record);
+                }
+            }
+            Err(e) if is_path_unavailable(&e) => {
+                tracing::debug!("Failed to retrieve thumbnail path: {}", e)
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.client.get_thumbnail(id).await
+    }
+}
+
+/// Returns if hydrus can't provide a path for the file so that it needs
+/// to be requested from the API. Other errors are returned to the caller
+fn is_path_unavailable(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::BadRequest(_) | Error::Forbidden(_) | Error::NotFound(_)
+    )
+}
+
+/// Reads a file from the local file system. Returns `None` if the file can't be read
+async fn read_local_file<P: Into<PathBuf>>(path: P, mime_type: String) -> Option<FileRecord> {
+    let path = path.into();
+
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => Some(FileRecord {
+            bytes,
+            mime_type,
+            path: Some(path),
+        }),
+        Err(e) => {
+            tracing::debug!("Failed to read local file {:?}: {}", path, e);
+            None
+        }
+    }
+}
@@ -1,14 +1,20 @@
-use crate::api_core::common::FileIdentifier;
+use crate::api_core::common::{FileIdentifier, ServiceIdentifier};
+use crate::api_core::endpoints::access_management::Permission;
 use crate::api_core::endpoints::searching_and_fetching_files::FullMetadata;
 use crate::error::Result;
 use crate::wrapper::address::Address;
 use crate::wrapper::builders::delete_files_builder::DeleteFilesBuilder;
+use crate::wrapper::builders::duplicates_builder::DuplicatesBuilder;
 use crate::wrapper::builders::import_builder::ImportBuilder;
 use crate::wrapper::builders::search_builder::SearchBuilder;
+use crate::wrapper::builders::tag_suggestions_builder::TagSuggestionsBuilder;
 use crate::wrapper::builders::tagging_builder::TaggingBuilder;
+use crate::wrapper::database::Database;
+use crate::wrapper::file_source::FileSource;
 use crate::wrapper::hydrus_file::HydrusFile;
 use crate::wrapper::page::HydrusPage;
-use crate::wrapper::service::Services;
+use crate::wrapper::service::{Service, Services};
+use crate::wrapper::tag::Tag;
 use crate::wrapper::url::Url;
 use crate::wrapper::version::Version;
 use crate::Client;
@@ -35,6 +41,13 @@ impl Hydrus {
         })
     }
 
+    /// Returns the permissions granted to the access key
+    pub async fn permissions(&self) -> Result<Vec<Permission>> {
+        let response = self.client.verify_access_key().await?;
+
+        Ok(response.permissions())
+    }
+
     /// Returns a list of available services
     pub async fn services(&self) -> Result<Services> {
         let response = self.client.get_services().await?;
@@ -42,6 +55,18 @@ impl Hydrus {
         Ok(Services::from_response(self.client.clone(), response))
     }
 
+    /// Returns a single service by its name or key
+    pub async fn service(&self, id: ServiceIdentifier) -> Result<Service> {
+        let response = self.client.get_service(id).await?;
+
+        Service::from_info(self.client.clone(), response.service)
+    }
+
+    /// Returns a handle to manage the database of hydrus
+    pub fn database(&self) -> Database {
+        Database::new(self.client.clone())
+    }
+
     /// Creates an import builder to build an import request to hydrus
     pub fn import(&self) -> ImportBuilder {
         ImportBuilder {
@@ -84,16 +109,71 @@ impl Hydrus {
         DeleteFilesBuilder::new(self.client.clone())
     }
 
+    /// Starts a request to process duplicates by setting relationships between files
+    pub fn duplicates(&self) -> DuplicatesBuilder {
+        DuplicatesBuilder::new(self.client.clone())
+    }
+
     /// Starts a request to bulk add tags to files
     pub fn tagging(&self) -> TaggingBuilder {
         TaggingBuilder::new(self.client.clone())
     }
 
+    /// Starts a request to search for tags that can be used for autocompletion
+    pub fn tag_suggestions(&self) -> TagSuggestionsBuilder {
+        TagSuggestionsBuilder::new(self.client.clone())
+    }
+
+    /// Resolves the tags to their ideal siblings on the given tag service.
+    /// Tags without siblings are returned unchanged
+    pub async fn ideal_tags(&self, tags: Vec<Tag>, service: &Service) -> Result<Vec<Tag>> {
+        let tag_names: Vec<String> = tags
+            .iter()
+            .map(|t| {
+                Tag {
+                    negated: false,
+                    ..t.clone()
+                }
+                .to_string()
+            })
+            .collect();
+        let response = self
+            .client
+            .get_siblings_and_parents(tag_names.clone())
+            .await?;
+
+        let ideal_tags = tags
+            .into_iter()
+            .zip(tag_names)
+            .map(|(tag, name)| {
+                match response
+                    .tags
+                    .get(&name)
+                    .and_then(|services| services.get(&service.key))
+                {
+                    Some(relations) => Tag {
+                        negated: tag.negated,
+                        ..Tag::from(&relations.ideal_tag)
+                    },
+                    None => tag,
+                }
+            })
+            .collect();
+
+        Ok(ideal_tags)
+    }
+
     /// Starts a request to search for files
     pub fn search(&self) -> SearchBuilder {
         SearchBuilder::new(self.client.clone())
     }
 
+    /// Returns a file source that reads files from disk if hydrus runs
+    /// on the same machine
+    pub fn file_source(&self) -> FileSource {
+        FileSource::new(self.client.clone())
+    }
+
     /// Returns a hydrus page by page key
     pub async fn page<S: AsRef<str> + Debug>(&self, page_key: S) -> Result<HydrusPage> {
         let info_response = self.client.get_page_info(page_key).await?;
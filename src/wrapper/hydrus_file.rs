@@ -1,18 +1,28 @@
 use crate::api_core::common::{
-    FileIdentifier, FileRecord, FileSelection, FileServiceSelection, ServiceIdentifier,
+    FileIdentifier, FileRecord, FileSelection, FileServiceSelection, FileStream, ServiceIdentifier,
 };
 use crate::api_core::endpoints::adding_tags::{AddTagsRequestBuilder, TagAction};
-use crate::api_core::endpoints::searching_and_fetching_files::{FileFullMetadata, FullMetadata};
+use crate::api_core::endpoints::editing_ratings::{Rating, RatingValue};
+use crate::api_core::endpoints::managing_file_relationships::FileRelationships;
+use crate::api_core::endpoints::searching_and_fetching_files::{
+    FileBasicMetadata, FileFullMetadata, FileMetadataIdentifiers, FileMetadataInfo, FullMetadata,
+    MetadataOptions,
+};
 use crate::error::{Error, Result};
 use crate::utils::tag_list_to_string_list;
 use crate::wrapper::builders::delete_files_builder::DeleteFilesBuilder;
 use crate::wrapper::builders::notes_builder::AddNotesBuilder;
-use crate::wrapper::service::ServiceName;
+use crate::wrapper::builders::render_builder::RenderBuilder;
+use crate::wrapper::file_source::FileSource;
+use crate::wrapper::service::{ServiceName, ServiceType};
 use crate::wrapper::tag::Tag;
 use crate::Client;
 use chrono::{NaiveDateTime, TimeZone, Utc};
 use mime::Mime;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ops::RangeBounds;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub enum FileStatus {
@@ -34,20 +44,49 @@ impl From<u8> for FileStatus {
     }
 }
 
+/// Metadata of a file that a [HydrusFile] can be created from.
+/// Only full metadata is kept by the file, other metadata is only used
+/// to identify it.
+pub trait IntoHydrusFile {
+    fn into_hydrus_file(self, client: Client) -> HydrusFile;
+}
+
+impl IntoHydrusFile for FileFullMetadata {
+    fn into_hydrus_file(self, client: Client) -> HydrusFile {
+        HydrusFile::from_metadata(client, self)
+    }
+}
+
+/// Discards the basic metadata as the file can only hold full metadata
+impl IntoHydrusFile for FileBasicMetadata {
+    fn into_hydrus_file(self, client: Client) -> HydrusFile {
+        self.identifiers.into_hydrus_file(client)
+    }
+}
+
+impl IntoHydrusFile for FileMetadataIdentifiers {
+    fn into_hydrus_file(self, client: Client) -> HydrusFile {
+        HydrusFile::from_id_and_hash(client, self.file_id, self.hash)
+    }
+}
+
 #[derive(Clone)]
 pub struct HydrusFile {
     pub(crate) client: Client,
     pub id: FileIdentifier,
     pub status: FileStatus,
+    /// The hash of the file if it is already known without metadata
+    pub(crate) hash: Option<String>,
     pub(crate) metadata: Option<FileFullMetadata>,
 }
 
 impl HydrusFile {
-    pub(crate) fn from_hash<S: ToString>(client: Client, hash: S) -> Self {
+    pub(crate) fn from_id_and_hash<S: ToString>(client: Client, file_id: u64, hash: S) -> Self {
         Self {
             client,
-            id: FileIdentifier::Hash(hash.to_string()),
-            status: FileStatus::Unknown,
+            id: FileIdentifier::ID(file_id),
+            status: FileStatus::InDatabase,
+            hash: Some(hash.to_string()),
             metadata: None,
         }
     }
@@ -61,6 +100,7 @@ impl HydrusFile {
             client,
             id: FileIdentifier::Hash(hash.to_string()),
             status: status.into(),
+            hash: None,
             metadata: None,
         }
     }
@@ -76,6 +116,7 @@ impl HydrusFile {
             client,
             id: FileIdentifier::Hash(metadata.basic_metadata.identifiers.hash.clone()),
             status,
+            hash: None,
             metadata: Some(metadata),
         }
     }
@@ -88,8 +129,12 @@ impl HydrusFile {
     }
 
     /// Returns the hash of the file
-    /// if the file identifier is an id it calls hydrus to resolve the file
+    /// if the file identifier is an id and the hash isn't known yet
+    /// it calls hydrus to resolve the file
     pub async fn hash(&mut self) -> Result<String> {
+        if let Some(hash) = &self.hash {
+            return Ok(hash.clone());
+        }
         match &self.id {
             FileIdentifier::ID(_) => {
                 let metadata = self.metadata().await?;
@@ -103,7 +148,7 @@ impl HydrusFile {
     pub async fn size(&mut self) -> Result<Option<u64>> {
         let metadata = self.metadata().await?;
 
-        Ok(metadata.basic_metadata.size.clone())
+        Ok(metadata.basic_metadata.size)
     }
 
     /// Returns the mime of the file
@@ -143,14 +188,14 @@ impl HydrusFile {
     pub async fn duration(&mut self) -> Result<Option<u64>> {
         let metadata = self.metadata().await?;
 
-        Ok(metadata.basic_metadata.duration.clone())
+        Ok(metadata.basic_metadata.duration)
     }
 
     /// Returns the number of frames of the file if it's a video
     pub async fn num_frames(&mut self) -> Result<Option<u64>> {
         let metadata = self.metadata().await?;
 
-        Ok(metadata.basic_metadata.num_frames.clone())
+        Ok(metadata.basic_metadata.num_frames)
     }
 
     /// Returns if the file has audio
@@ -194,7 +239,7 @@ impl HydrusFile {
         let naive_time_modified = metadata
             .basic_metadata
             .time_modified
-            .map(|m| Utc.timestamp_millis(m as i64).naive_utc());
+            .map(|m| Utc.timestamp_millis_opt(m as i64).unwrap().naive_utc());
 
         Ok(naive_time_modified)
     }
@@ -217,7 +262,7 @@ impl HydrusFile {
                     .get(service_key.as_ref())
                     .map(|s| s.time_imported)
             })
-            .map(|millis| Utc.timestamp_millis(millis as i64).naive_utc());
+            .map(|millis| Utc.timestamp_millis_opt(millis as i64).unwrap().naive_utc());
 
         Ok(naive_time_imported)
     }
@@ -233,7 +278,7 @@ impl HydrusFile {
             .deleted
             .get(service_key.as_ref())
             .map(|service| service.time_deleted)
-            .map(|millis| Utc.timestamp_millis(millis as i64).naive_utc());
+            .map(|millis| Utc.timestamp_millis_opt(millis as i64).unwrap().naive_utc());
 
         Ok(naive_time_deleted)
     }
@@ -297,8 +342,8 @@ impl HydrusFile {
         for (service, status_tags) in &metadata.service_names_to_statuses_to_tags {
             let mut tag_list = Vec::new();
 
-            for (_, tags) in status_tags {
-                tag_list.append(&mut tags.into_iter().map(|t| t.into()).collect())
+            for tags in status_tags.values() {
+                tag_list.append(&mut tags.iter().map(|t| t.into()).collect())
             }
             tag_mappings.insert(ServiceName(service.clone()), tag_list);
         }
@@ -314,8 +359,8 @@ impl HydrusFile {
         for (service, status_tags) in &metadata.service_keys_to_statuses_to_tags {
             let mut tag_list = Vec::new();
 
-            for (_, tags) in status_tags {
-                tag_list.append(&mut tags.into_iter().map(|t| t.into()).collect())
+            for tags in status_tags.values() {
+                tag_list.append(&mut tags.iter().map(|t| t.into()).collect())
             }
             tag_mappings.insert(ServiceIdentifier::Key(service.clone()), tag_list);
         }
@@ -363,11 +408,73 @@ impl HydrusFile {
         self.client.add_tags(reqwest.build()).await
     }
 
+    /// Returns the duplicate relationships of the file
+    pub async fn relationships(&mut self) -> Result<FileRelationships> {
+        let hash = self.hash().await?;
+        let mut response = self
+            .client
+            .get_file_relationships(vec![], vec![hash.clone()])
+            .await?;
+
+        response
+            .file_relationships
+            .remove(&hash)
+            .ok_or_else(|| Error::FileNotFound(self.id.clone()))
+    }
+
+    /// Returns the ratings of the file mapped by rating service.
+    /// Services that don't have a rating for the file are included with
+    /// an empty rating
+    pub async fn ratings(&mut self) -> Result<HashMap<ServiceIdentifier, Rating>> {
+        let values = self.metadata().await?.ratings.clone();
+        let mut ratings = HashMap::new();
+
+        for (service_key, value) in values {
+            let service = self
+                .client
+                .get_service(ServiceIdentifier::key(&service_key))
+                .await?
+                .service;
+            let service_type = ServiceType::try_from(service.service_type)?;
+
+            if let Some(rating) = rating_from_value(&service_type, value) {
+                ratings.insert(ServiceIdentifier::Key(service_key), rating);
+            }
+        }
+
+        Ok(ratings)
+    }
+
+    /// Sets the rating of the file on the given rating service
+    pub async fn set_rating<S: ToString>(
+        &self,
+        rating_service_key: S,
+        rating: Rating,
+    ) -> Result<()> {
+        self.client
+            .set_rating(self.id.clone(), rating_service_key.to_string(), rating)
+            .await
+    }
+
     /// Creates a builder to add notes to the file
     pub fn add_notes(&self) -> AddNotesBuilder {
         AddNotesBuilder::new(self.client.clone(), self.id.clone())
     }
 
+    /// Returns the notes of the file mapped by name
+    pub async fn notes(&mut self) -> Result<&HashMap<String, String>> {
+        let metadata = self.metadata().await?;
+
+        Ok(&metadata.notes)
+    }
+
+    /// Returns the content of the note with the given name
+    pub async fn note<S: AsRef<str>>(&mut self, name: S) -> Result<Option<&String>> {
+        let notes = self.notes().await?;
+
+        Ok(notes.get(name.as_ref()))
+    }
+
     /// Deletes a single note from the file
     pub async fn delete_note<S1: ToString>(&self, name: S1) -> Result<()> {
         self.client
@@ -389,6 +496,75 @@ impl HydrusFile {
         self.client.get_file(self.id.clone()).await
     }
 
+    /// Retrieves the file content as a stream of bytes
+    pub async fn stream(&self) -> Result<FileStream> {
+        self.client.get_file_stream(self.id.clone()).await
+    }
+
+    /// Retrieves a byte range of the file content as a stream of bytes
+    pub async fn stream_range<R: RangeBounds<u64>>(&self, range: R) -> Result<FileStream> {
+        self.client
+            .get_file_stream_range(self.id.clone(), range)
+            .await
+    }
+
+    /// Retrieves the thumbnail of the file
+    pub async fn thumbnail(&self) -> Result<FileRecord> {
+        self.client.get_thumbnail(self.id.clone()).await
+    }
+
+    /// Creates a builder to render the file to a different format
+    pub fn render(&self) -> RenderBuilder {
+        RenderBuilder::new(self.client.clone(), self.id.clone())
+    }
+
+    /// Returns the path of the file in the file storage of hydrus
+    pub async fn local_path(&self) -> Result<PathBuf> {
+        let response = self.client.get_file_path(self.id.clone()).await?;
+
+        Ok(PathBuf::from(response.path))
+    }
+
+    /// Returns the path of the thumbnail in the file storage of hydrus
+    pub async fn thumbnail_path(&self) -> Result<PathBuf> {
+        let response = self.client.get_thumbnail_path(self.id.clone()).await?;
+
+        Ok(PathBuf::from(response.path))
+    }
+
+    /// Reads the file from disk if hydrus runs on the same machine
+    /// and retrieves it from the api otherwise
+    pub async fn read(&self) -> Result<FileRecord> {
+        FileSource::new(self.client.clone())
+            .file(self.id.clone())
+            .await
+    }
+
+    /// Retrieves the metadata of the file with the fields selected by the options.
+    /// The result isn't cached
+    pub async fn metadata_with_options(
+        &self,
+        options: MetadataOptions,
+    ) -> Result<FileMetadataInfo> {
+        let mut response = match self.id.clone() {
+            FileIdentifier::ID(id) => {
+                self.client
+                    .get_file_metadata_with_options(vec![id], vec![], options)
+                    .await?
+            }
+            FileIdentifier::Hash(hash) => {
+                self.client
+                    .get_file_metadata_with_options(vec![], vec![hash], options)
+                    .await?
+            }
+        };
+
+        response
+            .metadata
+            .pop()
+            .ok_or_else(|| Error::FileNotFound(self.id.clone()))
+    }
+
     /// Returns the metadata for the given file
     /// if there's already known metadata about the file it uses that
     async fn metadata(&mut self) -> Result<&FileFullMetadata> {
@@ -408,3 +584,22 @@ impl HydrusFile {
         Ok(self.metadata.as_ref().unwrap())
     }
 }
+
+/// Interprets a raw rating value with the type of the service it belongs to
+fn rating_from_value(service_type: &ServiceType, value: Option<RatingValue>) -> Option<Rating> {
+    match (service_type, value) {
+        (ServiceType::LikeRatings, None) => Some(Rating::LikeDislike(None)),
+        (ServiceType::LikeRatings, Some(RatingValue::Bool(like))) => {
+            Some(Rating::LikeDislike(Some(like)))
+        }
+        (ServiceType::NumericalRatings, None) => Some(Rating::Numerical(None)),
+        (ServiceType::NumericalRatings, Some(RatingValue::Number(stars))) => {
+            Some(Rating::Numerical(Some(stars)))
+        }
+        (ServiceType::IncDecRatings, None) => Some(Rating::IncDec(0)),
+        (ServiceType::IncDecRatings, Some(RatingValue::Number(count))) => {
+            Some(Rating::IncDec(count))
+        }
+        _ => None,
+    }
+}
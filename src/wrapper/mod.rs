@@ -1,9 +1,13 @@
 pub mod address;
 pub mod builders;
+pub mod database;
+pub mod file_source;
 pub mod hydrus;
 pub mod hydrus_file;
 pub mod or_chain;
 pub mod page;
+pub mod search_expression;
+pub mod search_predicate;
 pub mod service;
 pub mod tag;
 pub mod url;
@@ -0,0 +1,289 @@
+use crate::api_core::endpoints::searching_and_fetching_files::SearchQueryEntry;
+use crate::error::{Error, Result};
+use crate::utils::tag_list_to_string_list;
+use crate::wrapper::builders::tag_builder::SystemTagBuilder;
+use crate::wrapper::search_predicate::SearchPredicate;
+use crate::wrapper::tag::Tag;
+use std::ops::{BitAnd, BitOr, Not};
+
+/// The maximum number of clauses an expression can be expanded to.
+/// Distributing OR over AND grows exponentially with the number of operands.
+const MAX_CLAUSES: usize = 1024;
+
+/// A boolean search expression that can be converted into the
+/// conjunctive normal form hydrus expects (tags combined with AND
+/// where each entry can be an OR chain of tags).
+///
+/// Example:
+/// ```
+/// use hydrus_api::wrapper::search_expression::SearchExpression;
+/// use hydrus_api::wrapper::tag::Tag;
+///
+/// let expression = SearchExpression::from(Tag::from("beach"))
+///     & !(Tag::from("summer") & Tag::from("winter"));
+/// let entries = expression.into_query_entries().unwrap();
+/// assert_eq!(entries.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchExpression {
+    Tag(Tag),
+    And(Vec<SearchExpression>),
+    Or(Vec<SearchExpression>),
+    Not(Box<SearchExpression>),
+}
+
+impl SearchExpression {
+    /// Converts the expression into the entries of a search request
+    pub fn into_query_entries(self) -> Result<Vec<SearchQueryEntry>> {
+        let entries = self
+            .into_clauses()?
+            .into_iter()
+            .map(|mut clause| {
+                if clause.len() == 1 {
+                    SearchQueryEntry::Tag(clause.remove(0).to_string())
+                } else {
+                    SearchQueryEntry::OrChain(tag_list_to_string_list(clause))
+                }
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Converts the expression into a list of clauses that all need to match.
+    /// A clause matches if any of its tags matches.
+    /// Expressions without any clauses match all files.
+    pub(crate) fn into_clauses(self) -> Result<Vec<Vec<Tag>>> {
+        let clauses = self
+            .into_negation_normal_form(false)?
+            .conjunctive_clauses()?;
+
+        if clauses.is_empty() {
+            Ok(vec![vec![SystemTagBuilder::new().everything().build()]])
+        } else {
+            Ok(clauses)
+        }
+    }
+
+    /// Moves all negations to the tags
+    fn into_negation_normal_form(self, negated: bool) -> Result<Self> {
+        let expression = match self {
+            SearchExpression::Tag(tag) if negated => SearchExpression::Tag(negate_tag(tag)?),
+            SearchExpression::Tag(tag) => SearchExpression::Tag(tag),
+            SearchExpression::Not(inner) => inner.into_negation_normal_form(!negated)?,
+            SearchExpression::And(expressions) => {
+                let expressions = expressions
+                    .into_iter()
+                    .map(|e| e.into_negation_normal_form(negated))
+                    .collect::<Result<_>>()?;
+                if negated {
+                    SearchExpression::Or(expressions)
+                } else {
+                    SearchExpression::And(expressions)
+                }
+            }
+            SearchExpression::Or(expressions) => {
+                let expressions = expressions
+                    .into_iter()
+                    .map(|e| e.into_negation_normal_form(negated))
+                    .collect::<Result<_>>()?;
+                if negated {
+                    SearchExpression::And(expressions)
+                } else {
+                    SearchExpression::Or(expressions)
+                }
+            }
+        };
+
+        Ok(expression)
+    }
+
+    /// Distributes the OR expressions over the AND expressions of an
+    /// expression in negation normal form
+    fn conjunctive_clauses(self) -> Result<Vec<Vec<Tag>>> {
+        match self {
+            SearchExpression::Tag(tag) => Ok(vec![vec![tag]]),
+            SearchExpression::Not(_) => Err(Error::BuildError(String::from(
+                "Negations need to be resolved before building clauses",
+            ))),
+            SearchExpression::And(expressions) => {
+                let mut clauses = Vec::new();
+                for expression in expressions {
+                    for clause in expression.conjunctive_clauses()? {
+                        if !clauses.contains(&clause) {
+                            clauses.push(clause);
+                        }
+                    }
+                    if clauses.len() > MAX_CLAUSES {
+                        return Err(too_many_clauses());
+                    }
+                }
+
+                Ok(clauses)
+            }
+            SearchExpression::Or(expressions) => {
+                if expressions.is_empty() {
+                    return Err(Error::BuildError(String::from(
+                        "An OR expression needs at least one operand",
+                    )));
+                }
+                let mut clauses: Vec<Vec<Tag>> = vec![Vec::new()];
+
+                for expression in expressions {
+                    let operand_clauses = expression.conjunctive_clauses()?;
+                    let combined_len = clauses.len().saturating_mul(operand_clauses.len());
+                    if combined_len > MAX_CLAUSES {
+                        return Err(too_many_clauses());
+                    }
+                    let mut combined = Vec::with_capacity(combined_len);
+
+                    for clause in &clauses {
+                        for operand_clause in &operand_clauses {
+                            let mut clause = clause.clone();
+                            for tag in operand_clause {
+                                if !clause.contains(tag) {
+                                    clause.push(tag.clone());
+                                }
+                            }
+                            if !is_tautology(&clause) && !combined.contains(&clause) {
+                                combined.push(clause);
+                            }
+                        }
+                    }
+                    clauses = combined;
+                }
+
+                Ok(clauses)
+            }
+        }
+    }
+}
+
+/// Negates a tag. System predicates can only be negated if hydrus
+/// has an inverse predicate for them
+fn negate_tag(tag: Tag) -> Result<Tag> {
+    if tag.namespace.as_deref() != Some("system") || tag.negated {
+        return Ok(Tag {
+            negated: !tag.negated,
+            ..tag
+        });
+    }
+    let predicate = tag.to_string().parse::<SearchPredicate>()?;
+
+    match predicate {
+        SearchPredicate::System { predicate, .. } => predicate
+            .inverse()
+            .and_then(|inverse| {
+                SearchPredicate::System {
+                    negated: false,
+                    predicate: inverse,
+                }
+                .into_tag()
+            })
+            .ok_or_else(|| {
+                Error::BuildError(format!(
+                    "The system predicate '{}' can't be negated",
+                    tag.name
+                ))
+            }),
+        _ => Err(Error::BuildError(format!(
+            "Invalid system predicate '{}'",
+            tag
+        ))),
+    }
+}
+
+fn too_many_clauses() -> Error {
+    Error::BuildError(format!(
+        "The expression expands to more than {} clauses",
+        MAX_CLAUSES
+    ))
+}
+
+/// Returns if the clause contains a tag and its negation
+fn is_tautology(clause: &[Tag]) -> bool {
+    clause.iter().any(|tag| {
+        clause.iter().any(|other| {
+            tag.negated != other.negated
+                && tag.name == other.name
+                && tag.namespace == other.namespace
+        })
+    })
+}
+
+impl From<Tag> for SearchExpression {
+    fn from(tag: Tag) -> Self {
+        Self::Tag(tag)
+    }
+}
+
+impl<T: Into<SearchExpression>> BitAnd<T> for SearchExpression {
+    type Output = SearchExpression;
+
+    fn bitand(self, rhs: T) -> Self::Output {
+        match (self, rhs.into()) {
+            (SearchExpression::And(mut lhs), SearchExpression::And(mut rhs)) => {
+                lhs.append(&mut rhs);
+                SearchExpression::And(lhs)
+            }
+            (SearchExpression::And(mut lhs), rhs) => {
+                lhs.push(rhs);
+                SearchExpression::And(lhs)
+            }
+            (lhs, rhs) => SearchExpression::And(vec![lhs, rhs]),
+        }
+    }
+}
+
+impl<T: Into<SearchExpression>> BitOr<T> for SearchExpression {
+    type Output = SearchExpression;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        match (self, rhs.into()) {
+            (SearchExpression::Or(mut lhs), SearchExpression::Or(mut rhs)) => {
+                lhs.append(&mut rhs);
+                SearchExpression::Or(lhs)
+            }
+            (SearchExpression::Or(mut lhs), rhs) => {
+                lhs.push(rhs);
+                SearchExpression::Or(lhs)
+            }
+            (lhs, rhs) => SearchExpression::Or(vec![lhs, rhs]),
+        }
+    }
+}
+
+impl Not for SearchExpression {
+    type Output = SearchExpression;
+
+    fn not(self) -> Self::Output {
+        match self {
+            SearchExpression::Not(inner) => *inner,
+            expression => SearchExpression::Not(Box::new(expression)),
+        }
+    }
+}
+
+impl<T: Into<SearchExpression>> BitAnd<T> for Tag {
+    type Output = SearchExpression;
+
+    fn bitand(self, rhs: T) -> Self::Output {
+        SearchExpression::from(self) & rhs
+    }
+}
+
+impl<T: Into<SearchExpression>> BitOr<T> for Tag {
+    type Output = SearchExpression;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        SearchExpression::from(self) | rhs
+    }
+}
+
+impl Not for Tag {
+    type Output = SearchExpression;
+
+    fn not(self) -> Self::Output {
+        !SearchExpression::from(self)
+    }
+}
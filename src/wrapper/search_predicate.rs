@@ -0,0 +1,1224 @@
+use crate::api_core::endpoints::searching_and_fetching_files::SearchQueryEntry;
+use crate::error::Error;
+use crate::utils::{format_datetime, format_duration};
+use crate::wrapper::builders::tag_builder::{
+    format_rating, Comparator, CurrentlyOrPending, DurationUnit, FileRelationshipType,
+    FileSizeUnit, IsComparator, PixelUnit, RatingCondition, ViewType, WiderTallerEqual,
+};
+use crate::wrapper::tag::Tag;
+use chrono::{Duration, NaiveDate};
+use mime::Mime;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+type ParseResult<T> = std::result::Result<T, QueryParseError>;
+
+/// A single entry of a hydrus search query
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchPredicate {
+    /// A regular tag that can be namespaced, negated or contain wildcards
+    Tag(Tag),
+    /// A system predicate like `system:inbox`
+    System {
+        negated: bool,
+        predicate: SystemPredicate,
+    },
+    /// Files matching any of the predicates
+    Or(Vec<SearchPredicate>),
+}
+
+impl SearchPredicate {
+    /// Returns the predicate as tag. OR chains can't be represented by a single tag.
+    pub fn into_tag(self) -> Option<Tag> {
+        match self {
+            SearchPredicate::Tag(tag) => Some(tag),
+            SearchPredicate::System { negated, predicate } => Some(Tag {
+                negated,
+                name: predicate.to_string(),
+                namespace: Some(String::from("system")),
+            }),
+            SearchPredicate::Or(_) => None,
+        }
+    }
+
+    /// Converts the predicate into an entry of a search request
+    pub fn into_query_entry(self) -> SearchQueryEntry {
+        match self {
+            SearchPredicate::Or(predicates) => {
+                SearchQueryEntry::OrChain(predicates.iter().map(|p| p.to_string()).collect())
+            }
+            predicate => SearchQueryEntry::Tag(predicate.to_string()),
+        }
+    }
+}
+
+impl Display for SearchPredicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchPredicate::Tag(tag) => tag.fmt(f),
+            SearchPredicate::System { negated, predicate } => {
+                let negation = if *negated { "-" } else { "" };
+                write!(f, "{}system:{}", negation, predicate)
+            }
+            SearchPredicate::Or(predicates) => {
+                let predicates: Vec<String> = predicates.iter().map(|p| p.to_string()).collect();
+                predicates.join(" OR ").fmt(f)
+            }
+        }
+    }
+}
+
+impl FromStr for SearchPredicate {
+    type Err = QueryParseError;
+
+    /// Parses a single entry of a search query
+    fn from_str(s: &str) -> ParseResult<Self> {
+        parse_entry(s, 0)
+    }
+}
+
+/// A system predicate with the same representation [crate::wrapper::builders::tag_builder::SystemTagBuilder] uses
+#[derive(Clone, Debug, PartialEq)]
+pub enum SystemPredicate {
+    Everything,
+    Inbox,
+    Archive,
+    HasDuration,
+    NoDuration,
+    BestQualityOfGroup,
+    NotBestQualityOfGroup,
+    HasAudio,
+    NoAudio,
+    HasTags,
+    NoTags,
+    Untagged,
+    Duration {
+        comparator: Comparator,
+        value: u64,
+        unit: DurationUnit,
+    },
+    NumberOfTags {
+        comparator: Comparator,
+        value: u64,
+    },
+    Height {
+        comparator: Comparator,
+        value: u64,
+    },
+    Width {
+        comparator: Comparator,
+        value: u64,
+    },
+    Filesize {
+        comparator: Comparator,
+        value: u64,
+        unit: FileSizeUnit,
+    },
+    SimilarTo {
+        hashes: Vec<String>,
+        distance: u32,
+    },
+    Limit(u64),
+    Filetype(Vec<Mime>),
+    Hash(Vec<String>),
+    ModifiedDate {
+        comparator: Comparator,
+        date: NaiveDate,
+    },
+    TimeImported {
+        comparator: Comparator,
+        date: NaiveDate,
+    },
+    FileService {
+        comparator: IsComparator,
+        status: CurrentlyOrPending,
+        service: String,
+    },
+    NumberOfRelationships {
+        comparator: Comparator,
+        value: u64,
+        relationship: FileRelationshipType,
+    },
+    Ratio {
+        comparator: WiderTallerEqual,
+        ratio: (u64, u64),
+    },
+    NumberOfPixels {
+        comparator: Comparator,
+        value: u64,
+        unit: PixelUnit,
+    },
+    Views {
+        view_type: ViewType,
+        comparator: Comparator,
+        value: u64,
+    },
+    Viewtime {
+        view_type: ViewType,
+        comparator: Comparator,
+        duration: Duration,
+    },
+    HasUrlMatchingRegex(String),
+    DoesNotHaveUrlMatchingRegex(String),
+    HasUrlWithClass(String),
+    DoesNotHaveUrlWithClass(String),
+    TagAsNumber {
+        namespace: String,
+        comparator: Comparator,
+        value: u64,
+    },
+    TagAsNumberBetween {
+        namespace: String,
+        min: u64,
+        max: u64,
+    },
+    NumberOfNotes {
+        comparator: Comparator,
+        value: u64,
+    },
+    HasNoteWithName(String),
+    NoNoteWithName(String),
+    HasRating(String),
+    NoRating(String),
+    Rating {
+        service: String,
+        comparator: Comparator,
+        value: RatingCondition,
+    },
+    LastViewedTime {
+        comparator: Comparator,
+        date: NaiveDate,
+    },
+    ArchivedTime {
+        comparator: Comparator,
+        date: NaiveDate,
+    },
+    HasExif,
+    NoExif,
+    HasIccProfile,
+    NoIccProfile,
+    HasEmbeddedMetadata,
+    NoEmbeddedMetadata,
+    Framerate {
+        comparator: Comparator,
+        value: u64,
+    },
+    NumberOfFrames {
+        comparator: Comparator,
+        value: u64,
+    },
+    NumberOfWords {
+        comparator: Comparator,
+        value: u64,
+    },
+    HasUrl(String),
+    DoesNotHaveUrl(String),
+}
+
+impl Display for SystemPredicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SystemPredicate::Everything => "everything".fmt(f),
+            SystemPredicate::Inbox => "inbox".fmt(f),
+            SystemPredicate::Archive => "archive".fmt(f),
+            SystemPredicate::HasDuration => "has duration".fmt(f),
+            SystemPredicate::NoDuration => "no duration".fmt(f),
+            SystemPredicate::BestQualityOfGroup => "best quality of group".fmt(f),
+            SystemPredicate::NotBestQualityOfGroup => "isn't best quality of group".fmt(f),
+            SystemPredicate::HasAudio => "has audio".fmt(f),
+            SystemPredicate::NoAudio => "no audio".fmt(f),
+            SystemPredicate::HasTags => "has tags".fmt(f),
+            SystemPredicate::NoTags => "no tags".fmt(f),
+            SystemPredicate::Untagged => "untagged".fmt(f),
+            SystemPredicate::Duration {
+                comparator,
+                value,
+                unit,
+            } => write!(f, "duration {} {} {}", comparator, value, unit),
+            SystemPredicate::NumberOfTags { comparator, value } => {
+                write!(f, "number of tags {} {}", comparator, value)
+            }
+            SystemPredicate::Height { comparator, value } => {
+                write!(f, "height {} {}", comparator, value)
+            }
+            SystemPredicate::Width { comparator, value } => {
+                write!(f, "width {} {}", comparator, value)
+            }
+            SystemPredicate::Filesize {
+                comparator,
+                value,
+                unit,
+            } => write!(f, "filesize {} {} {}", comparator, value, unit),
+            SystemPredicate::SimilarTo { hashes, distance } => write!(
+                f,
+                "similar to {} with distance {}",
+                hashes.join(", "),
+                distance
+            ),
+            SystemPredicate::Limit(value) => write!(f, "limit = {}", value),
+            SystemPredicate::Filetype(mimes) => {
+                let mimes: Vec<String> = mimes.iter().map(|m| m.to_string()).collect();
+                write!(f, "filetype = {}", mimes.join(", "))
+            }
+            SystemPredicate::Hash(hashes) => write!(f, "hash = {}", hashes.join(" ")),
+            SystemPredicate::ModifiedDate { comparator, date } => {
+                write!(f, "modified date {} {}", comparator, format_datetime(*date))
+            }
+            SystemPredicate::TimeImported { comparator, date } => {
+                write!(f, "time imported {} {}", comparator, format_datetime(*date))
+            }
+            SystemPredicate::FileService {
+                comparator,
+                status,
+                service,
+            } => write!(f, "file service {} {} {}", comparator, status, service),
+            SystemPredicate::NumberOfRelationships {
+                comparator,
+                value,
+                relationship,
+            } => write!(
+                f,
+                "num file relationships {} {} {}",
+                comparator, value, relationship
+            ),
+            SystemPredicate::Ratio { comparator, ratio } => {
+                write!(f, "ratio {} {}:{}", comparator, ratio.0, ratio.1)
+            }
+            SystemPredicate::NumberOfPixels {
+                comparator,
+                value,
+                unit,
+            } => write!(f, "num pixels {} {} {}", comparator, value, unit),
+            SystemPredicate::Views {
+                view_type,
+                comparator,
+                value,
+            } => write!(f, "{} views {} {}", view_type, comparator, value),
+            SystemPredicate::Viewtime {
+                view_type,
+                comparator,
+                duration,
+            } => write!(
+                f,
+                "{} viewtime {} {}",
+                view_type,
+                comparator,
+                format_duration(*duration)
+            ),
+            SystemPredicate::HasUrlMatchingRegex(regex) => {
+                write!(f, "has url matching regex {}", regex)
+            }
+            SystemPredicate::DoesNotHaveUrlMatchingRegex(regex) => {
+                write!(f, "does not have url matching regex {}", regex)
+            }
+            SystemPredicate::HasUrlWithClass(class) => write!(f, "has url with class {}", class),
+            SystemPredicate::DoesNotHaveUrlWithClass(class) => {
+                write!(f, "does not have url with class {}", class)
+            }
+            SystemPredicate::TagAsNumber {
+                namespace,
+                comparator,
+                value,
+            } => write!(f, "tag as number {} {} {}", namespace, comparator, value),
+            SystemPredicate::TagAsNumberBetween {
+                namespace,
+                min,
+                max,
+            } => write!(f, "tag as number {} between {} and {}", namespace, min, max),
+            SystemPredicate::NumberOfNotes { comparator, value } => {
+                write!(f, "num notes {} {}", comparator, value)
+            }
+            SystemPredicate::HasNoteWithName(name) => write!(f, "has note with name {}", name),
+            SystemPredicate::NoNoteWithName(name) => write!(f, "no note with name {}", name),
+            SystemPredicate::HasRating(service) => write!(f, "has a rating for {}", service),
+            SystemPredicate::NoRating(service) => {
+                write!(f, "does not have a rating for {}", service)
+            }
+            SystemPredicate::Rating {
+                service,
+                comparator,
+                value,
+            } => format_rating(service, comparator, value).fmt(f),
+            SystemPredicate::LastViewedTime { comparator, date } => {
+                write!(
+                    f,
+                    "last viewed time {} {}",
+                    comparator,
+                    format_datetime(*date)
+                )
+            }
+            SystemPredicate::ArchivedTime { comparator, date } => {
+                write!(f, "archived time {} {}", comparator, format_datetime(*date))
+            }
+            SystemPredicate::HasExif => "has exif".fmt(f),
+            SystemPredicate::NoExif => "no exif".fmt(f),
+            SystemPredicate::HasIccProfile => "has icc profile".fmt(f),
+            SystemPredicate::NoIccProfile => "no icc profile".fmt(f),
+            SystemPredicate::HasEmbeddedMetadata => "has human-readable embedded metadata".fmt(f),
+            SystemPredicate::NoEmbeddedMetadata => "no human-readable embedded metadata".fmt(f),
+            SystemPredicate::Framerate { comparator, value } => {
+                write!(f, "framerate {} {}fps", comparator, value)
+            }
+            SystemPredicate::NumberOfFrames { comparator, value } => {
+                write!(f, "number of frames {} {}", comparator, value)
+            }
+            SystemPredicate::NumberOfWords { comparator, value } => {
+                write!(f, "number of words {} {}", comparator, value)
+            }
+            SystemPredicate::HasUrl(url) => write!(f, "has url {}", url),
+            SystemPredicate::DoesNotHaveUrl(url) => write!(f, "does not have url {}", url),
+        }
+    }
+}
+
+impl SystemPredicate {
+    /// Returns the predicate that matches exactly the files this predicate doesn't match
+    /// if hydrus has one
+    pub fn inverse(&self) -> Option<Self> {
+        let inverse = match self {
+            SystemPredicate::Inbox => SystemPredicate::Archive,
+            SystemPredicate::Archive => SystemPredicate::Inbox,
+            SystemPredicate::HasDuration => SystemPredicate::NoDuration,
+            SystemPredicate::NoDuration => SystemPredicate::HasDuration,
+            SystemPredicate::BestQualityOfGroup => SystemPredicate::NotBestQualityOfGroup,
+            SystemPredicate::NotBestQualityOfGroup => SystemPredicate::BestQualityOfGroup,
+            SystemPredicate::HasAudio => SystemPredicate::NoAudio,
+            SystemPredicate::NoAudio => SystemPredicate::HasAudio,
+            SystemPredicate::HasTags => SystemPredicate::NoTags,
+            SystemPredicate::NoTags | SystemPredicate::Untagged => SystemPredicate::HasTags,
+            SystemPredicate::HasExif => SystemPredicate::NoExif,
+            SystemPredicate::NoExif => SystemPredicate::HasExif,
+            SystemPredicate::HasIccProfile => SystemPredicate::NoIccProfile,
+            SystemPredicate::NoIccProfile => SystemPredicate::HasIccProfile,
+            SystemPredicate::HasEmbeddedMetadata => SystemPredicate::NoEmbeddedMetadata,
+            SystemPredicate::NoEmbeddedMetadata => SystemPredicate::HasEmbeddedMetadata,
+            SystemPredicate::HasUrl(url) => SystemPredicate::DoesNotHaveUrl(url.clone()),
+            SystemPredicate::DoesNotHaveUrl(url) => SystemPredicate::HasUrl(url.clone()),
+            SystemPredicate::HasUrlMatchingRegex(regex) => {
+                SystemPredicate::DoesNotHaveUrlMatchingRegex(regex.clone())
+            }
+            SystemPredicate::DoesNotHaveUrlMatchingRegex(regex) => {
+                SystemPredicate::HasUrlMatchingRegex(regex.clone())
+            }
+            SystemPredicate::HasUrlWithClass(class) => {
+                SystemPredicate::DoesNotHaveUrlWithClass(class.clone())
+            }
+            SystemPredicate::DoesNotHaveUrlWithClass(class) => {
+                SystemPredicate::HasUrlWithClass(class.clone())
+            }
+            SystemPredicate::HasNoteWithName(name) => SystemPredicate::NoNoteWithName(name.clone()),
+            SystemPredicate::NoNoteWithName(name) => SystemPredicate::HasNoteWithName(name.clone()),
+            SystemPredicate::HasRating(service) => SystemPredicate::NoRating(service.clone()),
+            SystemPredicate::NoRating(service) => SystemPredicate::HasRating(service.clone()),
+            SystemPredicate::FileService {
+                comparator,
+                status,
+                service,
+            } => SystemPredicate::FileService {
+                comparator: match comparator {
+                    IsComparator::Is => IsComparator::IsNot,
+                    IsComparator::IsNot => IsComparator::Is,
+                },
+                status: status.clone(),
+                service: service.clone(),
+            },
+            SystemPredicate::NumberOfTags { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::NumberOfTags { comparator, value }
+            }
+            SystemPredicate::Height { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::Height { comparator, value }
+            }
+            SystemPredicate::Width { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::Width { comparator, value }
+            }
+            SystemPredicate::NumberOfRelationships {
+                comparator,
+                value,
+                relationship,
+            } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::NumberOfRelationships {
+                    comparator,
+                    value,
+                    relationship: relationship.clone(),
+                }
+            }
+            SystemPredicate::Views {
+                view_type,
+                comparator,
+                value,
+            } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::Views {
+                    view_type: view_type.clone(),
+                    comparator,
+                    value,
+                }
+            }
+            SystemPredicate::NumberOfNotes { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::NumberOfNotes { comparator, value }
+            }
+            SystemPredicate::NumberOfFrames { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::NumberOfFrames { comparator, value }
+            }
+            SystemPredicate::NumberOfWords { comparator, value } => {
+                let (comparator, value) = inverse_count_comparison(comparator, *value)?;
+                SystemPredicate::NumberOfWords { comparator, value }
+            }
+            _ => return None,
+        };
+
+        Some(inverse)
+    }
+}
+
+/// Inverts a comparison of whole numbers, e.g. `> 500` becomes `< 501`.
+/// Equality and approximate comparisons can't be inverted with a single comparison.
+fn inverse_count_comparison(comparator: &Comparator, value: u64) -> Option<(Comparator, u64)> {
+    match comparator {
+        Comparator::Greater => Some((Comparator::Less, value.checked_add(1)?)),
+        Comparator::Less => Some((Comparator::Greater, value.checked_sub(1)?)),
+        Comparator::Equal | Comparator::Approximate => None,
+    }
+}
+
+/// An error in a search query with the byte range of the query it refers to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryParseError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+impl From<QueryParseError> for Error {
+    fn from(e: QueryParseError) -> Self {
+        Error::BuildError(e.to_string())
+    }
+}
+
+/// Parses a hydrus search query with one predicate per line.
+/// The predicates of an OR chain are separated by ` OR `.
+pub fn parse_query(query: &str) -> ParseResult<Vec<SearchPredicate>> {
+    let mut predicates = Vec::new();
+    let mut offset = 0;
+
+    for line in query.split('\n') {
+        if !line.trim().is_empty() {
+            predicates.push(parse_entry(line, offset)?);
+        }
+        offset += line.len() + 1;
+    }
+
+    Ok(predicates)
+}
+
+/// Parses a single predicate or an OR chain of predicates
+fn parse_entry(entry: &str, offset: usize) -> ParseResult<SearchPredicate> {
+    const OR_SEPARATOR: &str = " OR ";
+    let mut predicates = Vec::new();
+    let mut start = 0;
+
+    for (index, _) in entry.match_indices(OR_SEPARATOR) {
+        predicates.push(parse_predicate(&entry[start..index], offset + start)?);
+        start = index + OR_SEPARATOR.len();
+    }
+    let last = parse_predicate(&entry[start..], offset + start)?;
+
+    if predicates.is_empty() {
+        Ok(last)
+    } else {
+        predicates.push(last);
+        Ok(SearchPredicate::Or(predicates))
+    }
+}
+
+/// Parses a single tag or system predicate
+fn parse_predicate(text: &str, start: usize) -> ParseResult<SearchPredicate> {
+    let trimmed = text.trim_start();
+    let offset = start + text.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+
+    if trimmed.is_empty() {
+        return Err(QueryParseError {
+            message: String::from("Expected a predicate"),
+            span: start..start + text.len(),
+        });
+    }
+    let (negated, predicate, predicate_offset) = match trimmed.strip_prefix('-') {
+        Some(predicate) => (true, predicate, offset + 1),
+        None => (false, trimmed, offset),
+    };
+
+    if let Some(system) = strip_prefix_ignore_case(predicate, "system:") {
+        let mut cursor = Cursor::new(system, predicate_offset + "system:".len());
+        let predicate = parse_system_predicate(&mut cursor)?;
+        cursor.expect_end()?;
+
+        return Ok(SearchPredicate::System { negated, predicate });
+    }
+    let tag = Tag::from(predicate);
+
+    if tag.name.trim().is_empty() {
+        Err(QueryParseError {
+            message: String::from("Expected a tag"),
+            span: offset..offset + trimmed.len(),
+        })
+    } else {
+        Ok(SearchPredicate::Tag(Tag { negated, ..tag }))
+    }
+}
+
+fn parse_system_predicate(c: &mut Cursor) -> ParseResult<SystemPredicate> {
+    let predicate = if c.eat("everything") {
+        SystemPredicate::Everything
+    } else if c.eat("inbox") {
+        SystemPredicate::Inbox
+    } else if c.eat("archive") {
+        SystemPredicate::Archive
+    } else if c.eat("has duration") {
+        SystemPredicate::HasDuration
+    } else if c.eat("no duration") {
+        SystemPredicate::NoDuration
+    } else if c.eat("best quality of group") {
+        SystemPredicate::BestQualityOfGroup
+    } else if c.eat("isn't best quality of group") {
+        SystemPredicate::NotBestQualityOfGroup
+    } else if c.eat("has audio") {
+        SystemPredicate::HasAudio
+    } else if c.eat("no audio") {
+        SystemPredicate::NoAudio
+    } else if c.eat("has tags") {
+        SystemPredicate::HasTags
+    } else if c.eat("no tags") {
+        SystemPredicate::NoTags
+    } else if c.eat("untagged") {
+        SystemPredicate::Untagged
+    } else if c.eat("has exif") {
+        SystemPredicate::HasExif
+    } else if c.eat("no exif") {
+        SystemPredicate::NoExif
+    } else if c.eat("has icc profile") {
+        SystemPredicate::HasIccProfile
+    } else if c.eat("no icc profile") {
+        SystemPredicate::NoIccProfile
+    } else if c.eat("has human-readable embedded metadata") {
+        SystemPredicate::HasEmbeddedMetadata
+    } else if c.eat("no human-readable embedded metadata") {
+        SystemPredicate::NoEmbeddedMetadata
+    } else if c.eat("has note with name") {
+        SystemPredicate::HasNoteWithName(c.remainder("a note name")?.to_string())
+    } else if c.eat("no note with name") {
+        SystemPredicate::NoNoteWithName(c.remainder("a note name")?.to_string())
+    } else if c.eat("has a rating for") {
+        SystemPredicate::HasRating(c.remainder("a service name")?.to_string())
+    } else if c.eat("does not have a rating for") {
+        SystemPredicate::NoRating(c.remainder("a service name")?.to_string())
+    } else if c.eat("rating for") {
+        let rest = c.remainder_segment("a service name")?;
+        let tokens: Vec<(&str, Range<usize>)> = rest.tokens().collect();
+        if tokens.len() < 3 {
+            return Err(QueryParseError::new(
+                "Expected a service name, a comparator and a rating",
+                rest.span(),
+            ));
+        }
+        let (value, value_span) = tokens[tokens.len() - 1].clone();
+        let (comparator, comparator_span) = tokens[tokens.len() - 2].clone();
+        let service_end = comparator_span.start - rest.offset;
+        let value = parse_rating_condition(value, value_span)?;
+        let comparator = match (&value, comparator) {
+            (RatingCondition::Like | RatingCondition::Dislike, "is") => Comparator::Equal,
+            (RatingCondition::Like | RatingCondition::Dislike, _) => {
+                return Err(QueryParseError::new(
+                    "Expected 'is' for like and dislike ratings",
+                    comparator_span,
+                ))
+            }
+            (_, "is") => {
+                return Err(QueryParseError::new(
+                    "Expected one of '>', '<', '=' or '~=' for numeric ratings",
+                    comparator_span,
+                ))
+            }
+            _ => parse_comparator(comparator, comparator_span)?,
+        };
+        SystemPredicate::Rating {
+            service: rest.text[..service_end].trim().to_string(),
+            comparator,
+            value,
+        }
+    } else if c.eat("num notes") {
+        SystemPredicate::NumberOfNotes {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("number of frames") {
+        SystemPredicate::NumberOfFrames {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("number of words") {
+        SystemPredicate::NumberOfWords {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("framerate") {
+        let comparator = c.comparator()?;
+        let (framerate, span) = c.token("a framerate")?;
+        let value = framerate
+            .strip_suffix("fps")
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| QueryParseError::new("Expected a framerate like '30fps'", span))?;
+        SystemPredicate::Framerate { comparator, value }
+    } else if c.eat("last viewed time") {
+        SystemPredicate::LastViewedTime {
+            comparator: c.comparator()?,
+            date: c.date()?,
+        }
+    } else if c.eat("archived time") {
+        SystemPredicate::ArchivedTime {
+            comparator: c.comparator()?,
+            date: c.date()?,
+        }
+    } else if c.eat("has url matching regex") {
+        SystemPredicate::HasUrlMatchingRegex(c.remainder("a regex")?.to_string())
+    } else if c.eat("does not have url matching regex") {
+        SystemPredicate::DoesNotHaveUrlMatchingRegex(c.remainder("a regex")?.to_string())
+    } else if c.eat("has url with class") {
+        SystemPredicate::HasUrlWithClass(c.remainder("an url class")?.to_string())
+    } else if c.eat("does not have url with class") {
+        SystemPredicate::DoesNotHaveUrlWithClass(c.remainder("an url class")?.to_string())
+    } else if c.eat("has url") {
+        SystemPredicate::HasUrl(c.remainder("an url")?.to_string())
+    } else if c.eat("does not have url") {
+        SystemPredicate::DoesNotHaveUrl(c.remainder("an url")?.to_string())
+    } else if c.eat("duration") {
+        SystemPredicate::Duration {
+            comparator: c.comparator()?,
+            value: c.number()?,
+            unit: c.duration_unit()?,
+        }
+    } else if c.eat("number of tags") {
+        SystemPredicate::NumberOfTags {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("height") {
+        SystemPredicate::Height {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("width") {
+        SystemPredicate::Width {
+            comparator: c.comparator()?,
+            value: c.number()?,
+        }
+    } else if c.eat("filesize") {
+        SystemPredicate::Filesize {
+            comparator: c.comparator()?,
+            value: c.number()?,
+            unit: c.filesize_unit()?,
+        }
+    } else if c.eat("similar to") {
+        let hashes = c.until("with distance")?;
+        let hashes = hashes
+            .split(',')
+            .map(|(hash, span)| parse_hash(hash, span))
+            .collect::<ParseResult<_>>()?;
+        c.expect("with distance")?;
+        SystemPredicate::SimilarTo {
+            hashes,
+            distance: c.number()?,
+        }
+    } else if c.eat("limit") {
+        c.expect("=")?;
+        SystemPredicate::Limit(c.number()?)
+    } else if c.eat("filetype") {
+        c.expect("=")?;
+        let mimes = c
+            .remainder_segment("a mime type")?
+            .split(',')
+            .map(|(mime, span)| {
+                mime.parse::<Mime>()
+                    .map_err(|_| QueryParseError::new("Invalid mime type", span))
+            })
+            .collect::<ParseResult<_>>()?;
+        SystemPredicate::Filetype(mimes)
+    } else if c.eat("hash") {
+        c.expect("=")?;
+        let mut hashes = Vec::new();
+        while !c.is_at_end() {
+            let (hash, span) = c.token("a hash")?;
+            hashes.push(parse_hash(hash, span)?);
+        }
+        if hashes.is_empty() {
+            return Err(c.error_at_next("Expected a hash"));
+        }
+        SystemPredicate::Hash(hashes)
+    } else if c.eat("modified date") {
+        SystemPredicate::ModifiedDate {
+            comparator: c.comparator()?,
+            date: c.date()?,
+        }
+    } else if c.eat("time imported") {
+        SystemPredicate::TimeImported {
+            comparator: c.comparator()?,
+            date: c.date()?,
+        }
+    } else if c.eat("file service") {
+        let comparator = if c.eat("is not") {
+            IsComparator::IsNot
+        } else if c.eat("is") {
+            IsComparator::Is
+        } else {
+            return Err(c.error_at_next("Expected 'is' or 'is not'"));
+        };
+        let status = if c.eat("currently in") {
+            CurrentlyOrPending::CurrentlyIn
+        } else if c.eat("pending to") {
+            CurrentlyOrPending::PendingTo
+        } else {
+            return Err(c.error_at_next("Expected 'currently in' or 'pending to'"));
+        };
+        SystemPredicate::FileService {
+            comparator,
+            status,
+            service: c.remainder("a service name")?.to_string(),
+        }
+    } else if c.eat("num file relationships") {
+        let comparator = c.comparator()?;
+        let value = c.number()?;
+        let relationship = if c.eat("alternates") {
+            FileRelationshipType::Alternates
+        } else if c.eat("false positives") {
+            FileRelationshipType::FalsePositives
+        } else if c.eat("duplicates") {
+            FileRelationshipType::Duplicates
+        } else if c.eat("potential duplicates") {
+            FileRelationshipType::PotentialDuplicates
+        } else {
+            return Err(c.error_at_next("Expected a file relationship type"));
+        };
+        SystemPredicate::NumberOfRelationships {
+            comparator,
+            value,
+            relationship,
+        }
+    } else if c.eat("ratio") {
+        let comparator = if c.eat("wider than") {
+            WiderTallerEqual::Wider
+        } else if c.eat("taller than") {
+            WiderTallerEqual::Taller
+        } else if c.eat("is") {
+            WiderTallerEqual::Equal
+        } else {
+            return Err(c.error_at_next("Expected 'wider than', 'taller than' or 'is'"));
+        };
+        let (ratio, span) = c.token("a ratio")?;
+        let ratio = ratio
+            .split_once(':')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .ok_or_else(|| QueryParseError::new("Invalid ratio", span))?;
+        SystemPredicate::Ratio { comparator, ratio }
+    } else if c.eat("num pixels") {
+        SystemPredicate::NumberOfPixels {
+            comparator: c.comparator()?,
+            value: c.number()?,
+            unit: c.pixel_unit()?,
+        }
+    } else if c.eat("tag as number") {
+        let rest = c.remainder_segment("a namespace")?;
+        let tokens: Vec<(&str, Range<usize>)> = rest.tokens().collect();
+        if tokens.len() < 3 {
+            return Err(QueryParseError::new(
+                "Expected a namespace, a comparator and a number",
+                rest.span(),
+            ));
+        }
+        if let [.., (between, between_span), (min, min_span), (and, _), (max, max_span)] =
+            tokens.as_slice()
+        {
+            if between.eq_ignore_ascii_case("between") && and.eq_ignore_ascii_case("and") {
+                let namespace_end = between_span.start - rest.offset;
+                return Ok(SystemPredicate::TagAsNumberBetween {
+                    namespace: rest.text[..namespace_end].trim().to_string(),
+                    min: parse_number(min, min_span.clone())?,
+                    max: parse_number(max, max_span.clone())?,
+                });
+            }
+        }
+        let (value, value_span) = tokens[tokens.len() - 1].clone();
+        let (comparator, comparator_span) = tokens[tokens.len() - 2].clone();
+        let namespace_end = comparator_span.start - rest.offset;
+        SystemPredicate::TagAsNumber {
+            namespace: rest.text[..namespace_end].trim().to_string(),
+            comparator: parse_comparator(comparator, comparator_span)?,
+            value: parse_number(value, value_span)?,
+        }
+    } else if let Some(view_type) = c.view_type() {
+        if c.eat("views") {
+            SystemPredicate::Views {
+                view_type,
+                comparator: c.comparator()?,
+                value: c.number()?,
+            }
+        } else if c.eat("viewtime") {
+            SystemPredicate::Viewtime {
+                view_type,
+                comparator: c.comparator()?,
+                duration: c.duration()?,
+            }
+        } else {
+            return Err(c.error_at_next("Expected 'views' or 'viewtime'"));
+        }
+    } else {
+        return Err(QueryParseError::new(
+            "Unknown system predicate",
+            c.rest_span(),
+        ));
+    };
+
+    Ok(predicate)
+}
+
+impl QueryParseError {
+    fn new<S: ToString>(message: S, span: Range<usize>) -> Self {
+        Self {
+            message: message.to_string(),
+            span,
+        }
+    }
+}
+
+fn parse_hash(hash: &str, span: Range<usize>) -> ParseResult<String> {
+    if !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hash.to_string())
+    } else {
+        Err(QueryParseError::new("Invalid hash", span))
+    }
+}
+
+fn parse_number<T: FromStr>(number: &str, span: Range<usize>) -> ParseResult<T> {
+    number
+        .parse()
+        .map_err(|_| QueryParseError::new("Invalid number", span))
+}
+
+fn parse_rating_condition(value: &str, span: Range<usize>) -> ParseResult<RatingCondition> {
+    if value.eq_ignore_ascii_case("like") {
+        return Ok(RatingCondition::Like);
+    }
+    if value.eq_ignore_ascii_case("dislike") {
+        return Ok(RatingCondition::Dislike);
+    }
+    let condition = match value.split_once('/') {
+        Some((stars, max)) => stars
+            .parse()
+            .ok()
+            .zip(max.parse().ok())
+            .map(|(stars, max)| RatingCondition::Stars(stars, max)),
+        None => value.parse().ok().map(RatingCondition::Count),
+    };
+
+    condition.ok_or_else(|| QueryParseError::new("Invalid rating", span))
+}
+
+fn parse_comparator(comparator: &str, span: Range<usize>) -> ParseResult<Comparator> {
+    match comparator {
+        ">" => Ok(Comparator::Greater),
+        "<" => Ok(Comparator::Less),
+        "=" => Ok(Comparator::Equal),
+        "~=" => Ok(Comparator::Approximate),
+        _ => Err(QueryParseError::new(
+            "Expected one of '>', '<', '=' or '~='",
+            span,
+        )),
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = text.get(..prefix.len())?;
+
+    if start.eq_ignore_ascii_case(prefix) {
+        Some(&text[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// A part of the query with its position in the query
+#[derive(Clone, Copy, Debug)]
+struct Segment<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Segment<'a> {
+    fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
+
+    /// Splits the segment at the separator and trims the parts
+    fn split(self, separator: char) -> impl Iterator<Item = (&'a str, Range<usize>)> {
+        let mut start = 0;
+        self.text.split(separator).map(move |part| {
+            let leading = part.len() - part.trim_start().len();
+            let trimmed = part.trim();
+            let part_start = self.offset + start + leading;
+            start += part.len() + separator.len_utf8();
+
+            (trimmed, part_start..part_start + trimmed.len())
+        })
+    }
+
+    /// Returns the whitespace separated tokens of the segment
+    fn tokens(self) -> impl Iterator<Item = (&'a str, Range<usize>)> {
+        self.text.split_whitespace().map(move |token| {
+            let start = token.as_ptr() as usize - self.text.as_ptr() as usize;
+            (
+                token,
+                self.offset + start..self.offset + start + token.len(),
+            )
+        })
+    }
+}
+
+/// Reads the parts of a system predicate while keeping track of their position
+struct Cursor<'a> {
+    text: &'a str,
+    offset: usize,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str, offset: usize) -> Self {
+        Self {
+            text,
+            offset,
+            position: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&mut self) -> &'a str {
+        self.skip_whitespace();
+        &self.text[self.position..]
+    }
+
+    fn rest_span(&mut self) -> Range<usize> {
+        let rest = self.rest().trim_end();
+        self.offset + self.position..self.offset + self.position + rest.len()
+    }
+
+    fn is_at_end(&mut self) -> bool {
+        self.rest().is_empty()
+    }
+
+    /// Consumes the keyword if the rest starts with it
+    fn eat(&mut self, keyword: &str) -> bool {
+        let rest = self.rest();
+        let matches_keyword = match strip_prefix_ignore_case(rest, keyword) {
+            Some(after) => {
+                let ends_word = keyword.ends_with(|c: char| !c.is_alphanumeric());
+                ends_word || !after.starts_with(|c: char| c.is_alphanumeric() || c == '\'')
+            }
+            None => false,
+        };
+        if matches_keyword {
+            self.position += keyword.len();
+        }
+
+        matches_keyword
+    }
+
+    fn expect(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat(keyword) {
+            Ok(())
+        } else {
+            Err(self.error_at_next(format!("Expected '{}'", keyword)))
+        }
+    }
+
+    fn expect_end(&mut self) -> ParseResult<()> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(QueryParseError::new("Unexpected input", self.rest_span()))
+        }
+    }
+
+    /// Creates an error pointing at the next token
+    fn error_at_next<S: ToString>(&mut self, message: S) -> QueryParseError {
+        let span = match self.next_token() {
+            Some((_, span)) => span,
+            None => {
+                let end = self.offset + self.text.len();
+                end..end
+            }
+        };
+
+        QueryParseError::new(message, span)
+    }
+
+    fn next_token(&mut self) -> Option<(&'a str, Range<usize>)> {
+        let rest = self.rest();
+        let token = rest.split_whitespace().next()?;
+        let start = self.offset + self.position;
+
+        Some((token, start..start + token.len()))
+    }
+
+    /// Consumes the next whitespace separated token
+    fn token(&mut self, expected: &str) -> ParseResult<(&'a str, Range<usize>)> {
+        match self.next_token() {
+            Some((token, span)) => {
+                self.position += token.len();
+                Ok((token, span))
+            }
+            None => Err(self.error_at_next(format!("Expected {}", expected))),
+        }
+    }
+
+    /// Consumes the rest of the predicate
+    fn remainder_segment(&mut self, expected: &str) -> ParseResult<Segment<'a>> {
+        let rest = self.rest().trim_end();
+
+        if rest.is_empty() {
+            return Err(self.error_at_next(format!("Expected {}", expected)));
+        }
+        let segment = Segment {
+            text: rest,
+            offset: self.offset + self.position,
+        };
+        self.position = self.text.len();
+
+        Ok(segment)
+    }
+
+    fn remainder(&mut self, expected: &str) -> ParseResult<&'a str> {
+        Ok(self.remainder_segment(expected)?.text)
+    }
+
+    /// Consumes everything up to the keyword
+    fn until(&mut self, keyword: &str) -> ParseResult<Segment<'a>> {
+        let rest = self.rest();
+        let end = rest
+            .to_ascii_lowercase()
+            .find(keyword)
+            .ok_or_else(|| self.error_at_next(format!("Expected '{}'", keyword)))?;
+        let segment = Segment {
+            text: rest[..end].trim_end(),
+            offset: self.offset + self.position,
+        };
+        self.position += end;
+
+        Ok(segment)
+    }
+
+    fn comparator(&mut self) -> ParseResult<Comparator> {
+        let (comparator, span) = self.token("a comparator")?;
+        parse_comparator(comparator, span)
+    }
+
+    fn number<T: FromStr>(&mut self) -> ParseResult<T> {
+        let (number, span) = self.token("a number")?;
+        parse_number(number, span)
+    }
+
+    fn date(&mut self) -> ParseResult<NaiveDate> {
+        let (date, span) = self.token("a date")?;
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| QueryParseError::new("Expected a date formatted as YYYY-MM-DD", span))
+    }
+
+    fn duration_unit(&mut self) -> ParseResult<DurationUnit> {
+        let (unit, span) = self.token("a duration unit")?;
+        match unit {
+            "hours" => Ok(DurationUnit::Hours),
+            "minutes" => Ok(DurationUnit::Minutes),
+            "seconds" => Ok(DurationUnit::Seconds),
+            "milliseconds" => Ok(DurationUnit::Milliseconds),
+            _ => Err(QueryParseError::new("Invalid duration unit", span)),
+        }
+    }
+
+    fn filesize_unit(&mut self) -> ParseResult<FileSizeUnit> {
+        let (unit, span) = self.token("a filesize unit")?;
+        match unit {
+            "B" => Ok(FileSizeUnit::Bytes),
+            "KB" => Ok(FileSizeUnit::Kilobytes),
+            "MB" => Ok(FileSizeUnit::Megabytes),
+            "GB" => Ok(FileSizeUnit::Gigabytes),
+            _ => Err(QueryParseError::new("Invalid filesize unit", span)),
+        }
+    }
+
+    fn pixel_unit(&mut self) -> ParseResult<PixelUnit> {
+        let (unit, span) = self.token("a pixel unit")?;
+        match unit {
+            "pixels" => Ok(PixelUnit::Pixels),
+            "kilopixels" => Ok(PixelUnit::Kilopixels),
+            "megapixels" => Ok(PixelUnit::Megapixels),
+            _ => Err(QueryParseError::new("Invalid pixel unit", span)),
+        }
+    }
+
+    fn view_type(&mut self) -> Option<ViewType> {
+        if self.eat("media") {
+            Some(ViewType::Media)
+        } else if self.eat("preview") {
+            Some(ViewType::Preview)
+        } else if self.eat("all") {
+            Some(ViewType::All)
+        } else {
+            None
+        }
+    }
+
+    /// Parses a duration in the format `1 days 2 hours 3 minutes 4 seconds`
+    fn duration(&mut self) -> ParseResult<Duration> {
+        let mut duration = Duration::zero();
+
+        loop {
+            let (number, number_span) = self.token("a number")?;
+            let value: u64 = parse_number(number, number_span.clone())?;
+            let value = i64::try_from(value).ok();
+            let (unit, span) = self.token("a time unit")?;
+            let part = match unit {
+                "days" => value.and_then(Duration::try_days),
+                "hours" => value.and_then(Duration::try_hours),
+                "minutes" => value.and_then(Duration::try_minutes),
+                "seconds" => value.and_then(Duration::try_seconds),
+                _ => return Err(QueryParseError::new("Invalid time unit", span)),
+            };
+            duration = part
+                .and_then(|part| duration.checked_add(&part))
+                .ok_or_else(|| QueryParseError::new("The duration is out of range", number_span))?;
+            if unit == "seconds" || self.is_at_end() {
+                return Ok(duration);
+            }
+        }
+    }
+}
@@ -1,19 +1,23 @@
-use crate::api_core::endpoints::access_management::GetServicesResponse;
+use crate::api_core::endpoints::access_management::{GetServicesResponse, ServiceInfo};
 use crate::api_core::endpoints::access_management::{
-    SERVICE_TYPE_ALL_KNOWN_FILES, SERVICE_TYPE_ALL_KNOWN_TAGS, SERVICE_TYPE_ALL_LOCAL_FILES,
-    SERVICE_TYPE_FILE_REPOSITORIES, SERVICE_TYPE_LOCAL_FILES, SERVICE_TYPE_LOCAL_TAGS,
+    SERVICE_TYPE_ALL_DELETED_FILES, SERVICE_TYPE_ALL_KNOWN_FILES, SERVICE_TYPE_ALL_KNOWN_TAGS,
+    SERVICE_TYPE_ALL_LOCAL_FILES, SERVICE_TYPE_ALL_MY_FILES, SERVICE_TYPE_FILE_REPOSITORIES,
+    SERVICE_TYPE_INC_DEC_RATINGS, SERVICE_TYPE_IPFS, SERVICE_TYPE_LIKE_RATINGS,
+    SERVICE_TYPE_LOCAL_FILES, SERVICE_TYPE_LOCAL_TAGS, SERVICE_TYPE_NUMERICAL_RATINGS,
+    SERVICE_TYPE_REPOSITORY_UPDATES, SERVICE_TYPE_SERVER_ADMINISTRATION,
     SERVICE_TYPE_TAG_REPOSITORIES, SERVICE_TYPE_TRASH,
 };
 
 use crate::api_core::common::ServiceIdentifier;
-use crate::error::Error;
+use crate::api_core::endpoints::managing_services::PendingCounts;
+use crate::error::{Error, Result};
 use crate::wrapper::builders::search_builder::SearchBuilder;
 use crate::Client;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone, PartialOrd, PartialEq, Hash)]
+#[derive(Clone, Debug, PartialOrd, PartialEq, Hash)]
 pub enum ServiceType {
     LocalTags,
     TagRepositories,
@@ -23,6 +27,14 @@ pub enum ServiceType {
     AllKnownFiles,
     AllKnownTags,
     Trash,
+    LikeRatings,
+    NumericalRatings,
+    IncDecRatings,
+    Ipfs,
+    AllDeletedFiles,
+    RepositoryUpdates,
+    AllMyFiles,
+    ServerAdministration,
 }
 
 impl Eq for ServiceType {}
@@ -30,7 +42,7 @@ impl Eq for ServiceType {}
 impl TryFrom<String> for ServiceType {
     type Error = Error;
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
         match value.as_str() {
             s if s == SERVICE_TYPE_LOCAL_TAGS => Ok(Self::LocalTags),
             s if s == SERVICE_TYPE_TAG_REPOSITORIES => Ok(Self::TagRepositories),
@@ -40,26 +52,93 @@ impl TryFrom<String> for ServiceType {
             s if s == SERVICE_TYPE_ALL_KNOWN_FILES => Ok(Self::AllKnownFiles),
             s if s == SERVICE_TYPE_ALL_KNOWN_TAGS => Ok(Self::AllKnownTags),
             s if s == SERVICE_TYPE_TRASH => Ok(Self::Trash),
+            s if s == SERVICE_TYPE_LIKE_RATINGS => Ok(Self::LikeRatings),
+            s if s == SERVICE_TYPE_NUMERICAL_RATINGS => Ok(Self::NumericalRatings),
+            s if s == SERVICE_TYPE_INC_DEC_RATINGS => Ok(Self::IncDecRatings),
+            s if s == SERVICE_TYPE_IPFS => Ok(Self::Ipfs),
+            s if s == SERVICE_TYPE_ALL_DELETED_FILES => Ok(Self::AllDeletedFiles),
+            s if s == SERVICE_TYPE_REPOSITORY_UPDATES => Ok(Self::RepositoryUpdates),
+            s if s == SERVICE_TYPE_ALL_MY_FILES => Ok(Self::AllMyFiles),
+            s if s == SERVICE_TYPE_SERVER_ADMINISTRATION => Ok(Self::ServerAdministration),
             _ => Err(Error::InvalidServiceType(value)),
         }
     }
 }
 
-impl ToString for ServiceType {
-    fn to_string(&self) -> String {
+impl ServiceType {
+    /// Returns the numeric id hydrus uses for the service type
+    pub fn id(&self) -> u32 {
         match self {
-            ServiceType::LocalTags => String::from(SERVICE_TYPE_LOCAL_TAGS),
-            ServiceType::TagRepositories => String::from(SERVICE_TYPE_TAG_REPOSITORIES),
-            ServiceType::LocalFiles => String::from(SERVICE_TYPE_LOCAL_FILES),
-            ServiceType::FileRepositories => String::from(SERVICE_TYPE_FILE_REPOSITORIES),
-            ServiceType::AllLocalFiles => String::from(SERVICE_TYPE_ALL_LOCAL_FILES),
-            ServiceType::AllKnownFiles => String::from(SERVICE_TYPE_ALL_KNOWN_FILES),
-            ServiceType::AllKnownTags => String::from(SERVICE_TYPE_ALL_KNOWN_TAGS),
-            ServiceType::Trash => String::from(SERVICE_TYPE_TRASH),
+            ServiceType::TagRepositories => 0,
+            ServiceType::FileRepositories => 1,
+            ServiceType::LocalFiles => 2,
+            ServiceType::LocalTags => 5,
+            ServiceType::NumericalRatings => 6,
+            ServiceType::LikeRatings => 7,
+            ServiceType::AllKnownTags => 10,
+            ServiceType::AllKnownFiles => 11,
+            ServiceType::Ipfs => 13,
+            ServiceType::Trash => 14,
+            ServiceType::AllLocalFiles => 15,
+            ServiceType::AllDeletedFiles => 19,
+            ServiceType::RepositoryUpdates => 20,
+            ServiceType::AllMyFiles => 21,
+            ServiceType::IncDecRatings => 22,
+            ServiceType::ServerAdministration => 99,
+        }
+    }
+}
+
+impl TryFrom<u32> for ServiceType {
+    type Error = Error;
+
+    fn try_from(id: u32) -> std::result::Result<Self, Self::Error> {
+        match id {
+            0 => Ok(Self::TagRepositories),
+            1 => Ok(Self::FileRepositories),
+            2 => Ok(Self::LocalFiles),
+            5 => Ok(Self::LocalTags),
+            6 => Ok(Self::NumericalRatings),
+            7 => Ok(Self::LikeRatings),
+            10 => Ok(Self::AllKnownTags),
+            11 => Ok(Self::AllKnownFiles),
+            13 => Ok(Self::Ipfs),
+            14 => Ok(Self::Trash),
+            15 => Ok(Self::AllLocalFiles),
+            19 => Ok(Self::AllDeletedFiles),
+            20 => Ok(Self::RepositoryUpdates),
+            21 => Ok(Self::AllMyFiles),
+            22 => Ok(Self::IncDecRatings),
+            99 => Ok(Self::ServerAdministration),
+            _ => Err(Error::InvalidServiceType(id.to_string())),
         }
     }
 }
 
+impl Display for ServiceType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ServiceType::LocalTags => SERVICE_TYPE_LOCAL_TAGS,
+            ServiceType::TagRepositories => SERVICE_TYPE_TAG_REPOSITORIES,
+            ServiceType::LocalFiles => SERVICE_TYPE_LOCAL_FILES,
+            ServiceType::FileRepositories => SERVICE_TYPE_FILE_REPOSITORIES,
+            ServiceType::AllLocalFiles => SERVICE_TYPE_ALL_LOCAL_FILES,
+            ServiceType::AllKnownFiles => SERVICE_TYPE_ALL_KNOWN_FILES,
+            ServiceType::AllKnownTags => SERVICE_TYPE_ALL_KNOWN_TAGS,
+            ServiceType::Trash => SERVICE_TYPE_TRASH,
+            ServiceType::LikeRatings => SERVICE_TYPE_LIKE_RATINGS,
+            ServiceType::NumericalRatings => SERVICE_TYPE_NUMERICAL_RATINGS,
+            ServiceType::IncDecRatings => SERVICE_TYPE_INC_DEC_RATINGS,
+            ServiceType::Ipfs => SERVICE_TYPE_IPFS,
+            ServiceType::AllDeletedFiles => SERVICE_TYPE_ALL_DELETED_FILES,
+            ServiceType::RepositoryUpdates => SERVICE_TYPE_REPOSITORY_UPDATES,
+            ServiceType::AllMyFiles => SERVICE_TYPE_ALL_MY_FILES,
+            ServiceType::ServerAdministration => SERVICE_TYPE_SERVER_ADMINISTRATION,
+        };
+        name.fmt(f)
+    }
+}
+
 #[derive(Clone, PartialOrd, PartialEq, Hash)]
 pub struct ServiceName(pub String);
 
@@ -97,9 +176,9 @@ impl Display for ServiceName {
     }
 }
 
-impl Into<ServiceIdentifier> for ServiceName {
-    fn into(self) -> ServiceIdentifier {
-        ServiceIdentifier::Name(self.0)
+impl From<ServiceName> for ServiceIdentifier {
+    fn from(name: ServiceName) -> Self {
+        ServiceIdentifier::Name(name.0)
     }
 }
 
@@ -109,9 +188,32 @@ pub struct Service {
     pub name: ServiceName,
     pub key: String,
     pub service_type: ServiceType,
+    /// The human readable service type. Only set when the service was
+    /// retrieved on its own with [crate::wrapper::hydrus::Hydrus::service]
+    pub type_pretty: Option<String>,
+    /// The shape of the stars displayed for rating services
+    pub star_shape: Option<String>,
+    /// The minimum number of stars of numerical rating services
+    pub min_stars: Option<u32>,
+    /// The maximum number of stars of numerical rating services
+    pub max_stars: Option<u32>,
 }
 
 impl Service {
+    /// Creates the service from the detailed information returned for a single service
+    pub(crate) fn from_info(client: Client, info: ServiceInfo) -> Result<Self> {
+        Ok(Self {
+            service_type: ServiceType::try_from(info.service_type)?,
+            name: ServiceName(info.name),
+            key: info.service_key,
+            type_pretty: Some(info.type_pretty),
+            star_shape: info.star_shape,
+            min_stars: info.min_stars,
+            max_stars: info.max_stars,
+            client,
+        })
+    }
+
     pub fn search(&self) -> SearchBuilder {
         let builder = SearchBuilder::new(self.client.clone());
         match self.service_type {
@@ -122,9 +224,38 @@ impl Service {
             | ServiceType::FileRepositories
             | ServiceType::AllLocalFiles
             | ServiceType::AllKnownFiles
-            | ServiceType::Trash => builder.file_service_key(&self.key),
+            | ServiceType::Trash
+            | ServiceType::AllDeletedFiles
+            | ServiceType::RepositoryUpdates
+            | ServiceType::AllMyFiles => builder.file_service_key(&self.key),
+            ServiceType::LikeRatings
+            | ServiceType::NumericalRatings
+            | ServiceType::IncDecRatings
+            | ServiceType::Ipfs
+            | ServiceType::ServerAdministration => builder,
         }
     }
+
+    /// Returns the number of items that are pending to be uploaded to this repository.
+    /// Fails if the service isn't a repository
+    pub async fn pending_counts(&self) -> Result<PendingCounts> {
+        let mut response = self.client.get_pending_counts().await?;
+
+        response
+            .pending_counts
+            .remove(&self.key)
+            .ok_or_else(|| Error::InvalidServiceType(self.service_type.to_string()))
+    }
+
+    /// Uploads the pending content to this repository
+    pub async fn commit_pending(&self) -> Result<()> {
+        self.client.commit_pending(&self.key).await
+    }
+
+    /// Discards the content that is pending to be uploaded to this repository
+    pub async fn forget_pending(&self) -> Result<()> {
+        self.client.forget_pending(&self.key).await
+    }
 }
 
 #[derive(Clone)]
@@ -149,6 +280,10 @@ impl Services {
                         service_type: mapped_type.clone(),
                         name: ServiceName(basic_service.name),
                         key: basic_service.service_key,
+                        type_pretty: None,
+                        star_shape: None,
+                        min_stars: None,
+                        max_stars: None,
                         client: client.clone(),
                     })
                 }
@@ -165,7 +300,7 @@ impl Services {
     /// Returns a list of all services of the given type
     pub fn get_services(&self, service_type: ServiceType) -> Vec<&Service> {
         if let Some(services) = self.inner.get(&service_type) {
-            services.into_iter().collect()
+            services.iter().collect()
         } else {
             Vec::new()
         }
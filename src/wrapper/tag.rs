@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub struct Tag {
     pub negated: bool,
@@ -31,13 +33,13 @@ where
     }
 }
 
-impl ToString for Tag {
-    fn to_string(&self) -> String {
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let negation = if self.negated { "-" } else { "" };
         if let Some(namespace) = &self.namespace {
-            format!("{}{}:{}", negation, namespace, self.name)
+            write!(f, "{}{}:{}", negation, namespace, self.name)
         } else {
-            format!("{}{}", negation, self.name)
+            write!(f, "{}{}", negation, self.name)
         }
     }
 }
@@ -0,0 +1,2 @@
+mod test_managing_file_relationships;
+mod test_searching_and_fetching_files;
@@ -0,0 +1,20 @@
+use hydrus_api::api_core::endpoints::managing_file_relationships::{
+    DuplicateRelationship, FileRelationship,
+};
+
+#[test]
+fn it_serializes_duplicate_relationships() {
+    let relationships = [
+        (DuplicateRelationship::PotentialDuplicates, 0),
+        (DuplicateRelationship::FalsePositives, 1),
+        (DuplicateRelationship::SameQuality, 2),
+        (DuplicateRelationship::Alternates, 3),
+        (DuplicateRelationship::ABetter, 4),
+        (DuplicateRelationship::BBetter, 7),
+    ];
+
+    for (relationship, id) in relationships {
+        let relationship = FileRelationship::new("a", "b", relationship);
+        assert_eq!(relationship.relationship, id);
+    }
+}
@@ -0,0 +1,15 @@
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::SortType;
+use hydrus_api::error::Error;
+use std::convert::TryFrom;
+
+#[test]
+fn it_maps_sort_types_to_ids() {
+    assert_eq!(SortType::NumberOfFrames.id(), 16);
+    assert_eq!(SortType::LastViewedTime.id(), 18);
+    assert_eq!(SortType::NumberOfMediaViewers.id(), 10);
+    assert_eq!(SortType::try_from(22).unwrap(), SortType::Blurhash);
+    assert!(matches!(
+        SortType::try_from(17),
+        Err(Error::InvalidSortType(17))
+    ));
+}
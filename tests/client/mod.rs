@@ -6,3 +6,7 @@ mod test_managing_cookies_and_http_headers;
 mod test_managing_pages;
 mod test_searching_and_fetching_files;
 mod test_deleting_notes;
+mod test_managing_file_relationships;
+mod test_editing_ratings;
+mod test_managing_services;
+mod test_managing_database;
@@ -1,4 +1,11 @@
 use super::super::common;
+use hydrus_api::api_core::common::ServiceIdentifier;
+use hydrus_api::api_core::endpoints::access_management::Permission;
+use hydrus_api::api_core::retry::RetryPolicy;
+use hydrus_api::error::Error;
+use hydrus_api::Client;
+use std::convert::TryFrom;
+use std::time::Duration;
 
 #[tokio::test]
 async fn it_returns_the_api_version() {
@@ -15,6 +22,15 @@ async fn it_returns_the_session_key() {
     assert!(session_key.session_key.len() > 0);
 }
 
+#[tokio::test]
+async fn it_authenticates_with_a_session_key() {
+    let client = common::get_session_client();
+    let verification_response = client.verify_access_key().await.unwrap();
+    assert!(!verification_response.human_description.is_empty());
+    let services_response = client.get_services().await.unwrap();
+    assert!(!services_response.0.is_empty());
+}
+
 #[tokio::test]
 async fn it_verifies_the_access_key() {
     let client = common::get_client();
@@ -23,9 +39,73 @@ async fn it_verifies_the_access_key() {
     assert!(verification_response.human_description.len() > 0);
 }
 
+#[tokio::test]
+async fn it_returns_typed_permissions() {
+    let client = common::get_client();
+    let verification_response = client.verify_access_key().await.unwrap();
+    let permissions = verification_response.permissions();
+    assert!(!permissions.is_empty());
+    assert!(verification_response.has_permission(permissions[0]));
+}
+
+#[test]
+fn it_maps_permissions_to_ids() {
+    assert_eq!(Permission::ManageDatabase.id(), 6);
+    assert_eq!(Permission::try_from(12).unwrap(), Permission::CommitPending);
+    assert!(matches!(
+        Permission::try_from(99),
+        Err(Error::InvalidPermission(99))
+    ));
+    assert_eq!(Permission::all().len(), 14);
+}
+
 #[tokio::test]
 async fn it_returns_a_list_of_services() {
     let client = common::get_client();
     let services_response = client.get_services().await.unwrap();
     assert!(services_response.0.keys().len() > 0);
 }
+
+#[tokio::test]
+async fn it_returns_a_single_service() {
+    let client = common::get_client();
+    let response = client
+        .get_service(ServiceIdentifier::Name(String::from("my tags")))
+        .await
+        .unwrap();
+    assert_eq!(response.service.name, "my tags");
+    assert!(!response.service.type_pretty.is_empty());
+
+    let response = client
+        .get_service(ServiceIdentifier::key(response.service.service_key))
+        .await
+        .unwrap();
+    assert_eq!(response.service.name, "my tags");
+}
+
+#[tokio::test]
+async fn it_rejects_invalid_access_keys() {
+    common::setup();
+    let client = Client::new(common::hydrus_url(), String::from("invalid"));
+    let error = client.verify_access_key().await.unwrap_err();
+    assert!(matches!(error, Error::Forbidden(_)));
+    assert_eq!(error.status(), Some(403));
+}
+
+#[tokio::test]
+async fn it_does_not_retry_forbidden_requests() {
+    common::setup();
+    let client = Client::builder()
+        .url(common::hydrus_url())
+        .access_key("invalid")
+        .retry_policy(
+            RetryPolicy::new()
+                .max_attempts(5)
+                .initial_backoff(Duration::from_secs(10)),
+        )
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let error = client.verify_access_key().await.unwrap_err();
+    assert!(matches!(error, Error::Forbidden(_)));
+}
@@ -1,7 +1,7 @@
 use crate::common;
 use crate::common::create_testdata;
 use crate::common::test_data::{get_test_hashes, TEST_HASH_1};
-use hydrus_api::api_core::common::FileSelection;
+use hydrus_api::api_core::common::{FileSelection, FileUploadStream};
 use hydrus_api::wrapper::service::ServiceName;
 
 #[tokio::test]
@@ -21,6 +21,17 @@ async fn it_adds_binary_files() {
     assert_eq!(result.status, 4); // should fail because the filetype is unknown
 }
 
+#[tokio::test]
+async fn it_adds_binary_file_streams() {
+    let client = common::get_client();
+    let bytes: &'static [u8] = &[0u8, 0u8, 0u8, 0u8];
+    let result = client
+        .add_binary_file_stream(FileUploadStream::from_reader(bytes, Some(4)))
+        .await
+        .unwrap();
+    assert_eq!(result.status, 4); // should fail because the filetype is unknown
+}
+
 #[tokio::test]
 async fn it_deletes_files() {
     let client = common::get_client();
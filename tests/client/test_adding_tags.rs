@@ -1,7 +1,10 @@
 use super::super::common;
 use crate::common::test_data::EMPTY_HASH;
 use hydrus_api::api_core::common::ServiceIdentifier;
-use hydrus_api::api_core::endpoints::adding_tags::{AddTagsRequestBuilder, TagAction};
+use hydrus_api::api_core::endpoints::adding_tags::{
+    AddTagsRequestBuilder, TagAction, TagDisplayType,
+};
+use hydrus_api::wrapper::service::ServiceName;
 
 #[tokio::test]
 async fn it_cleans_tags() {
@@ -36,3 +39,32 @@ async fn it_adds_tags() {
         .build();
     client.add_tags(request).await.unwrap();
 }
+
+#[tokio::test]
+async fn it_searches_tags() {
+    let client = common::get_client();
+    let response = client
+        .search_tags(
+            "character:",
+            ServiceName::all_known_tags().into(),
+            TagDisplayType::Display,
+        )
+        .await
+        .unwrap();
+    assert!(!response.tags.is_empty());
+    assert!(response
+        .tags
+        .iter()
+        .all(|t| t.value.starts_with("character:")));
+}
+
+#[tokio::test]
+async fn it_returns_siblings_and_parents() {
+    let client = common::get_client();
+    let response = client
+        .get_siblings_and_parents(vec!["lotr".into(), "character:frodo".into()])
+        .await
+        .unwrap();
+    assert!(response.tags.contains_key("lotr"));
+    assert!(response.tags.contains_key("character:frodo"));
+}
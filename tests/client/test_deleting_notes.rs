@@ -1,6 +1,7 @@
 use super::super::common;
 use crate::common::test_data::TEST_HASH_1;
 use hydrus_api::api_core::common::FileIdentifier;
+use hydrus_api::api_core::endpoints::adding_notes::{NoteConflictResolution, SetNotesRequest};
 use std::collections::HashMap;
 
 #[tokio::test]
@@ -17,6 +18,18 @@ async fn it_sets_notes() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn it_sets_notes_with_merge_options() {
+    let client = common::get_client();
+    let mut test_notes = HashMap::new();
+    test_notes.insert("merged".to_string(), "value".to_string());
+    let request = SetNotesRequest::new(FileIdentifier::hash(TEST_HASH_1), test_notes)
+        .merge_cleverly(true)
+        .conflict_resolution(NoteConflictResolution::Ignore);
+
+    client.set_notes_with_request(request).await.unwrap();
+}
+
 #[tokio::test]
 async fn it_deletes_notes() {
     let client = common::get_client();
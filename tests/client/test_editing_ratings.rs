@@ -0,0 +1,57 @@
+use super::super::common;
+use crate::common::test_data::TEST_HASH_1;
+use hydrus_api::api_core::common::{FileIdentifier, ServiceIdentifier};
+use hydrus_api::api_core::endpoints::editing_ratings::Rating;
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::FullMetadata;
+use hydrus_api::wrapper::service::ServiceType;
+use hydrus_api::Client;
+
+async fn like_rating_service_key(client: &Client) -> String {
+    let metadata = client
+        .get_file_metadata_by_identifier::<FullMetadata>(FileIdentifier::hash(TEST_HASH_1))
+        .await
+        .unwrap();
+
+    for service_key in metadata.ratings.keys() {
+        let response = client
+            .get_service(ServiceIdentifier::key(service_key))
+            .await
+            .unwrap();
+        if response.service.service_type == ServiceType::LikeRatings.id() {
+            return service_key.clone();
+        }
+    }
+    panic!("No like/dislike rating service found");
+}
+
+#[tokio::test]
+async fn it_sets_ratings() {
+    let client = common::get_client();
+    common::create_testdata(&client).await;
+    let service_key = like_rating_service_key(&client).await;
+
+    client
+        .set_rating(
+            FileIdentifier::hash(TEST_HASH_1),
+            &service_key,
+            Rating::LikeDislike(Some(true)),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn it_removes_ratings() {
+    let client = common::get_client();
+    common::create_testdata(&client).await;
+    let service_key = like_rating_service_key(&client).await;
+
+    client
+        .set_rating(
+            FileIdentifier::hash(TEST_HASH_1),
+            &service_key,
+            Rating::LikeDislike(None),
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,26 @@
+use super::super::common;
+use crate::common::create_testdata;
+
+#[tokio::test]
+async fn it_forces_a_commit() {
+    let client = common::get_client();
+    client.force_commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_file_statistics() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let response = client.mr_bones().await.unwrap();
+
+    assert!(response.boned_stats.num_files() > 0);
+    assert!(response.boned_stats.size_files() > 0);
+}
+
+#[tokio::test]
+async fn it_returns_the_client_options() {
+    let client = common::get_client();
+    let response = client.get_client_options().await.unwrap();
+
+    assert!(!response.options.booleans.is_empty());
+}
@@ -0,0 +1,68 @@
+use super::super::common;
+use crate::common::test_data::{get_test_hashes, TEST_HASH_1, TEST_HASH_2};
+use hydrus_api::api_core::common::FileSelection;
+use hydrus_api::api_core::endpoints::managing_file_relationships::{
+    DuplicateRelationship, FileRelationship, PixelDuplicates, PotentialsSearchOptions,
+    PotentialsSearchType,
+};
+
+fn get_options() -> PotentialsSearchOptions {
+    PotentialsSearchOptions::new()
+        .search_type(PotentialsSearchType::OneMatchesFirst)
+        .pixel_duplicates(PixelDuplicates::Allowed)
+        .max_hamming_distance(4)
+}
+
+#[tokio::test]
+async fn it_returns_file_relationships() {
+    let client = common::get_client();
+    let response = client
+        .get_file_relationships(vec![], get_test_hashes())
+        .await
+        .unwrap();
+    assert!(response.file_relationships.contains_key(TEST_HASH_1));
+}
+
+#[tokio::test]
+async fn it_returns_the_potentials_count() {
+    let client = common::get_client();
+    client.get_potentials_count(get_options()).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_potential_pairs() {
+    let client = common::get_client();
+    let response = client
+        .get_potential_pairs(get_options().max_num_pairs(5))
+        .await
+        .unwrap();
+    assert!(response.potential_duplicate_pairs.len() <= 5);
+}
+
+#[tokio::test]
+async fn it_returns_random_potentials() {
+    let client = common::get_client();
+    client.get_random_potentials(get_options()).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_sets_file_relationships() {
+    let client = common::get_client();
+    client
+        .set_file_relationships(vec![FileRelationship::new(
+            TEST_HASH_1,
+            TEST_HASH_2,
+            DuplicateRelationship::PotentialDuplicates,
+        )])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn it_sets_kings() {
+    let client = common::get_client();
+    client
+        .set_kings(FileSelection::by_hash(TEST_HASH_1))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,20 @@
+use super::super::common;
+use hydrus_api::api_core::endpoints::access_management::SERVICE_TYPE_TAG_REPOSITORIES;
+use hydrus_api::Client;
+
+async fn tag_repository_key(client: &Client) -> String {
+    let services = client.get_services().await.unwrap();
+
+    services.0.get(SERVICE_TYPE_TAG_REPOSITORIES).unwrap()[0]
+        .service_key
+        .clone()
+}
+
+#[tokio::test]
+async fn it_returns_pending_counts() {
+    let client = common::get_client();
+    let service_key = tag_repository_key(&client).await;
+    let response = client.get_pending_counts().await.unwrap();
+
+    assert!(response.pending_counts.contains_key(&service_key));
+}
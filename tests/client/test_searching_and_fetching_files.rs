@@ -1,12 +1,17 @@
 use super::super::common;
 use crate::common::create_testdata;
+use crate::common::test_data::TEST_HASH_1;
+use futures_util::TryStreamExt;
 use hydrus_api::api_core::common::FileIdentifier;
 use hydrus_api::api_core::endpoints::searching_and_fetching_files::file_sort_type::SORT_FILE_PIXEL_COUNT;
 use hydrus_api::api_core::endpoints::searching_and_fetching_files::{
-    BasicMetadata, FileSearchOptions, FullMetadata, Identifiers, SearchQueryEntry,
+    BasicMetadata, FileMetadataIdentifiers, FileSearchOptions, FullMetadata, Identifiers,
+    MetadataOptions, RenderFormat, RenderOptions, SearchQueryEntry, SortType,
 };
+use hydrus_api::error::Error;
 use hydrus_api::wrapper::builders::tag_builder::SystemTagBuilder;
 use hydrus_api::wrapper::service::ServiceName;
+use std::ops::Bound;
 
 #[tokio::test]
 async fn is_searches_files() {
@@ -46,6 +51,39 @@ async fn is_searches_file_hashes() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn it_searches_for_file_ids_and_hashes() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let response = client
+        .search_files_with_hashes(
+            vec![SearchQueryEntry::Tag(
+                SystemTagBuilder::new().everything().build().to_string(),
+            )],
+            FileSearchOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(!response.is_empty());
+    assert_eq!(response.file_ids.len(), response.hashes.len());
+    assert_eq!(response.files().count(), response.len());
+}
+
+#[tokio::test]
+async fn it_rejects_sort_directions_for_random_sorting() {
+    let client = common::get_client();
+    let options = FileSearchOptions::new().sort_type(SortType::Random).desc();
+    let result = client.search_files(vec!["beach".into()], options).await;
+    assert!(matches!(result, Err(Error::BuildError(_))));
+
+    let options = FileSearchOptions::new().asc().sort_type(SortType::Random);
+    let result = client
+        .search_file_hashes(vec!["beach".into()], options)
+        .await;
+    assert!(matches!(result, Err(Error::BuildError(_))));
+}
+
 #[tokio::test]
 async fn it_fetches_file_metadata() {
     let client = common::get_client();
@@ -86,6 +124,99 @@ async fn it_fetches_file_metadata_by_id() {
     response.unwrap();
 }
 
+#[tokio::test]
+async fn it_fetches_file_metadata_with_options() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let options = MetadataOptions::new()
+        .detailed_url_information(true)
+        .include_notes(true)
+        .include_milliseconds(true)
+        .hide_service_keys_tags(true);
+    let response = client
+        .get_file_metadata_with_options(vec![], vec![TEST_HASH_1.to_string()], options)
+        .await
+        .unwrap();
+    let metadata = &response.metadata[0];
+
+    assert!(metadata.notes.is_some());
+    assert!(metadata.detailed_known_urls.is_some());
+    assert!(metadata.service_keys_to_statuses_to_tags.is_none());
+}
+
+#[tokio::test]
+async fn it_fetches_only_identifiers_with_options() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let options = MetadataOptions::new().only_identifiers(true);
+    let response = client
+        .get_file_metadata_with_options(vec![], vec![TEST_HASH_1.to_string()], options)
+        .await
+        .unwrap();
+    let metadata = &response.metadata[0];
+
+    assert_eq!(metadata.identifiers.hash, TEST_HASH_1);
+    assert!(metadata.mime.is_none());
+}
+
+#[tokio::test]
+async fn it_fetches_file_metadata_in_chunks() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let files = client
+        .search_files(
+            vec![SearchQueryEntry::Tag(
+                SystemTagBuilder::new().everything().build().to_string(),
+            )],
+            FileSearchOptions::default(),
+        )
+        .await
+        .unwrap();
+    let metadata: Vec<FileMetadataIdentifiers> = client
+        .get_file_metadata_chunked::<Identifiers>(files.file_ids.clone(), 2, 2)
+        .try_collect()
+        .await
+        .unwrap();
+    let file_ids: Vec<u64> = metadata.into_iter().map(|m| m.file_id).collect();
+
+    assert_eq!(file_ids, files.file_ids);
+}
+
+#[tokio::test]
+async fn it_renders_files() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let options = RenderOptions::new()
+        .format(RenderFormat::Webp)
+        .quality(80)
+        .size(100, 100);
+    let record = client
+        .render_file(FileIdentifier::hash(TEST_HASH_1), options)
+        .await
+        .unwrap();
+
+    assert!(!record.bytes.is_empty());
+    assert_eq!(record.mime_type, RenderFormat::Webp.mime_type());
+}
+
+#[tokio::test]
+async fn it_fetches_file_paths() {
+    let client = common::get_client();
+    create_testdata(&client).await;
+    let file_path = client
+        .get_file_path(FileIdentifier::hash(TEST_HASH_1))
+        .await
+        .unwrap();
+    let thumbnail_path = client
+        .get_thumbnail_path(FileIdentifier::hash(TEST_HASH_1))
+        .await
+        .unwrap();
+
+    assert!(!file_path.path.is_empty());
+    assert!(file_path.size > 0);
+    assert!(!thumbnail_path.path.is_empty());
+}
+
 #[tokio::test]
 async fn it_fetches_single_files() {
     let client = common::get_client();
@@ -95,5 +226,43 @@ async fn it_fetches_single_files() {
         ))
         .await;
 
-    assert!(response.is_err()); // can't find the file
+    assert!(matches!(response, Err(Error::NotFound(_)))); // can't find the file
+}
+
+#[tokio::test]
+async fn it_fetches_thumbnails() {
+    let client = common::get_client();
+    let response = client
+        .get_thumbnail(FileIdentifier::Hash(
+            "0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+        ))
+        .await;
+
+    assert!(matches!(response, Err(Error::NotFound(_)))); // can't find the file
+}
+
+#[tokio::test]
+async fn it_rejects_empty_ranges() {
+    let client = common::get_client();
+    let id = FileIdentifier::hash(TEST_HASH_1);
+    let result = client.get_file_stream_range(id.clone(), 5..5).await;
+    assert!(matches!(result, Err(Error::BuildError(_))));
+    let result = client.get_file_stream_range(id.clone(), 0..0).await;
+    assert!(matches!(result, Err(Error::BuildError(_))));
+    let result = client
+        .get_file_stream_range(id, (Bound::Excluded(u64::MAX), Bound::Unbounded))
+        .await;
+    assert!(matches!(result, Err(Error::BuildError(_))));
+}
+
+#[tokio::test]
+async fn it_streams_single_files() {
+    let client = common::get_client();
+    let response = client
+        .get_file_stream(FileIdentifier::Hash(
+            "0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+        ))
+        .await;
+
+    assert!(matches!(response, Err(Error::NotFound(_)))); // can't find the file
 }
@@ -1,10 +1,12 @@
 use hydrus_api::api_core::client::Client;
-use hydrus_api::api_core::endpoints::adding_urls::AddUrlRequestBuilder;
+use hydrus_api::api_core::endpoints::adding_urls::{AddUrlRequestBuilder, URL_TYPE_POST};
+use hydrus_api::mock::{MockFile, MockHydrus, MockStore, MockUrlClass, MY_TAGS_KEY};
+use hydrus_api::wrapper::service::ServiceType;
 use hydrus_api::Hydrus;
 use std::env;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
-use test_data::TEST_URLS;
+use test_data::{TEST_HASH_1, TEST_HASH_2, TEST_IMAGE, TEST_URLS, TEST_URL_1, TEST_URL_2};
 pub mod test_data;
 
 pub fn setup() {
@@ -12,17 +14,42 @@ pub fn setup() {
     let mut setup_done: MutexGuard<bool> = SETUP_DONE.lock().unwrap();
 
     if !*setup_done {
-        dotenv::dotenv().expect("failed to initialize dotenv");
+        dotenv::dotenv().ok();
         tracing_subscriber::fmt::init();
         *setup_done = true;
     }
 }
 
-pub fn get_client() -> Client {
+/// Returns the url of the hydrus instance used for testing.
+/// If `HYDRUS_URL` isn't set, the tests run against a mock
+pub fn hydrus_url() -> String {
+    setup();
+    env::var("HYDRUS_URL").unwrap_or_else(|_| get_mock().url())
+}
+
+/// Returns the access key for the hydrus instance used for testing
+pub fn access_key() -> String {
     setup();
+    match env::var("HYDRUS_URL") {
+        Ok(_) => env::var("HYDRUS_ACCESS_KEY").unwrap(),
+        Err(_) => get_mock().access_key(),
+    }
+}
+
+pub fn get_client() -> Client {
     Client::builder()
-        .url(env::var("HYDRUS_URL").unwrap())
-        .access_key(env::var("HYDRUS_ACCESS_KEY").unwrap())
+        .url(hydrus_url())
+        .access_key(access_key())
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+}
+
+pub fn get_session_client() -> Client {
+    Client::builder()
+        .url(hydrus_url())
+        .access_key(access_key())
+        .use_session_key(true)
         .timeout(Duration::from_secs(5))
         .build()
         .unwrap()
@@ -42,3 +69,52 @@ pub async fn create_testdata(client: &Client) {
             .unwrap();
     }
 }
+
+/// Returns the mock shared by all tests that is prepared with the test data
+fn get_mock() -> &'static MockHydrus {
+    lazy_static::lazy_static! {
+        static ref MOCK: MockHydrus = MockHydrus::with_store(create_mock_store()).unwrap();
+    }
+
+    &MOCK
+}
+
+fn create_mock_store() -> MockStore {
+    let mut store = MockStore::new();
+    store.add_url_class(
+        MockUrlClass::new(
+            "pixiv file page",
+            URL_TYPE_POST,
+            r"pixiv\.net/member_illust\.php",
+        )
+        .unwrap(),
+    );
+    store.add_url_class(
+        MockUrlClass::new("yande.re file page", URL_TYPE_POST, r"yande\.re/post/show/").unwrap(),
+    );
+    store.add_service("public tag repository", ServiceType::TagRepositories);
+    store.add_tag_sibling(MY_TAGS_KEY, "lotr", "series:lord of the rings");
+    store.add_tag_parent(MY_TAGS_KEY, "character:frodo", "series:lord of the rings");
+    let test_files = [(TEST_HASH_1, TEST_URL_1), (TEST_HASH_2, TEST_URL_2)];
+
+    for (hash, url) in test_files {
+        store.add_file(
+            MockFile::new(TEST_IMAGE.to_vec())
+                .with_hash(hash)
+                .add_tags(
+                    MY_TAGS_KEY,
+                    vec!["character:megumin".to_string(), "summer".to_string()],
+                )
+                .add_url(url),
+        );
+    }
+    store.add_potential_duplicates(TEST_HASH_1, TEST_HASH_2);
+    // a file that isn't modified by any test
+    store.add_file(
+        MockFile::new(TEST_IMAGE.to_vec())
+            .add_tags(MY_TAGS_KEY, vec!["beach".to_string()])
+            .archived(),
+    );
+
+    store
+}
@@ -0,0 +1,785 @@
+use super::super::common;
+use crate::common::test_data::{TEST_HASH_1, TEST_HASH_2, TEST_IMAGE};
+use futures_util::TryStreamExt;
+use hydrus_api::api_core::common::{FileIdentifier, FileSelection, ServiceIdentifier};
+use hydrus_api::api_core::endpoints::access_management::Permission;
+use hydrus_api::api_core::endpoints::adding_notes::NoteConflictResolution;
+use hydrus_api::api_core::endpoints::adding_tags::{AddTagsRequestBuilder, TagAction};
+use hydrus_api::api_core::endpoints::editing_ratings::Rating;
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::{
+    BasicMetadata, FileSearchOptions, MetadataOptions,
+};
+use hydrus_api::api_core::retry::RetryPolicy;
+use hydrus_api::error::Error;
+use hydrus_api::mock::{MockFile, MockHydrus, FAVOURITES_KEY, MY_TAGS_KEY, TAG_STATUS_CURRENT};
+use hydrus_api::wrapper::builders::import_builder::FileImport;
+use hydrus_api::wrapper::builders::tag_builder::{Comparator, SystemTagBuilder};
+use hydrus_api::wrapper::hydrus::Hydrus;
+use hydrus_api::wrapper::hydrus_file::HydrusFile;
+use hydrus_api::wrapper::service::{ServiceName, ServiceType};
+use hydrus_api::Client;
+use std::time::Duration;
+
+fn start_mock() -> MockHydrus {
+    common::setup();
+    MockHydrus::start().unwrap()
+}
+
+#[tokio::test]
+async fn it_imports_files_into_the_store() {
+    let mock = start_mock();
+    let hydrus = mock.hydrus();
+    let mut file = hydrus
+        .import()
+        .file(FileImport::binary(&mut &TEST_IMAGE[..]).unwrap())
+        .run()
+        .await
+        .unwrap();
+
+    let hash = file.hash().await.unwrap();
+
+    assert!(mock.store().file(&hash).is_some());
+}
+
+#[tokio::test]
+async fn it_applies_tag_changes_to_the_store() {
+    let mock = start_mock();
+    let test_file = MockFile::new(TEST_IMAGE.to_vec()).add_tags(MY_TAGS_KEY, vec!["summer".into()]);
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let mut file = mock
+        .hydrus()
+        .file(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    file.add_tags(ServiceName::my_tags().into(), vec!["beach".into()])
+        .await
+        .unwrap();
+
+    let tags = mock
+        .store()
+        .file(&hash)
+        .unwrap()
+        .tags_with_status(MY_TAGS_KEY, TAG_STATUS_CURRENT);
+    assert_eq!(tags, vec!["summer".to_string(), "beach".to_string()]);
+}
+
+#[tokio::test]
+async fn it_searches_files_by_tags() {
+    let mock = start_mock();
+    mock.store()
+        .add_file(MockFile::new(TEST_IMAGE.to_vec()).add_tags(MY_TAGS_KEY, vec!["summer".into()]));
+    let hydrus = mock.hydrus();
+
+    let files = hydrus
+        .search()
+        .add_tag("summer".into())
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(files.len(), 1);
+    let files = hydrus
+        .search()
+        .add_tag("winter".into())
+        .run()
+        .await
+        .unwrap();
+    assert!(files.is_empty());
+}
+
+#[tokio::test]
+async fn it_searches_files_by_notes() {
+    let mock = start_mock();
+    mock.store()
+        .add_file(MockFile::new(TEST_IMAGE.to_vec()).add_note("source", "pixiv"));
+    let hydrus = mock.hydrus();
+
+    let files = hydrus
+        .search()
+        .add_tag(SystemTagBuilder::new().has_note_with_name("source").build())
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(files.len(), 1);
+    let files = hydrus
+        .search()
+        .add_tag(
+            SystemTagBuilder::new()
+                .number_of_notes(Comparator::Greater, 1)
+                .build(),
+        )
+        .run()
+        .await
+        .unwrap();
+    assert!(files.is_empty());
+}
+
+#[tokio::test]
+async fn it_reads_files_from_the_local_file_storage() {
+    let mock = start_mock();
+    let directory = std::env::temp_dir().join(format!("hydrus-mock-{}", std::process::id()));
+    mock.store().set_files_directory(&directory);
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let hydrus = mock.hydrus();
+
+    let record = hydrus
+        .file_source()
+        .file(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    assert_eq!(record.bytes, TEST_IMAGE.to_vec());
+    assert!(record.path.unwrap().starts_with(&directory));
+
+    let thumbnail = hydrus
+        .file_source()
+        .thumbnail(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    assert!(thumbnail.path.is_some());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_path_errors_that_the_api_would_repeat() {
+    let mock = start_mock();
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    mock.store().fail_next_requests(1, 503);
+
+    let result = mock
+        .hydrus()
+        .file_source()
+        .file(FileIdentifier::hash(&hash))
+        .await;
+    assert!(matches!(result, Err(Error::ServiceUnavailable(_))));
+    assert_eq!(mock.store().request_count("get_files/file"), 0);
+}
+
+#[tokio::test]
+async fn it_falls_back_to_the_api_for_unreadable_paths() {
+    let mock = start_mock();
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+
+    let record = mock
+        .hydrus()
+        .file_source()
+        .file(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    assert_eq!(record.bytes, TEST_IMAGE.to_vec());
+    assert!(record.path.is_none());
+}
+
+#[tokio::test]
+async fn it_commits_pending_tags() {
+    let mock = start_mock();
+    let repository_key = mock
+        .store()
+        .add_service("public tag repository", ServiceType::TagRepositories);
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let hydrus = mock.hydrus();
+    let mut file = hydrus.file(FileIdentifier::hash(&hash)).await.unwrap();
+    file.modify_tags(
+        ServiceIdentifier::key(&repository_key),
+        TagAction::PendAddToRepository,
+        vec!["summer".into(), "beach".into()],
+    )
+    .await
+    .unwrap();
+    let services = hydrus.services().await.unwrap();
+    let repository = services
+        .get_services(ServiceType::TagRepositories)
+        .pop()
+        .unwrap()
+        .clone();
+
+    let counts = repository.pending_counts().await.unwrap();
+    assert_eq!(counts.pending_tag_mappings, 2);
+    repository.commit_pending().await.unwrap();
+    assert!(repository.pending_counts().await.unwrap().is_empty());
+    let local_tags = services
+        .get_services(ServiceType::LocalTags)
+        .pop()
+        .unwrap()
+        .clone();
+    assert!(matches!(
+        local_tags.pending_counts().await,
+        Err(Error::InvalidServiceType(_))
+    ));
+
+    let tags = mock
+        .store()
+        .file(&hash)
+        .unwrap()
+        .tags_with_status(&repository_key, TAG_STATUS_CURRENT);
+    assert_eq!(tags, vec!["summer".to_string(), "beach".to_string()]);
+}
+
+#[tokio::test]
+async fn it_forgets_pending_tags() {
+    let mock = start_mock();
+    let repository_key = mock
+        .store()
+        .add_service("public tag repository", ServiceType::TagRepositories);
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let client = mock.client();
+    client
+        .add_tags(
+            AddTagsRequestBuilder::default()
+                .add_hash(&hash)
+                .add_tag_with_action(
+                    ServiceIdentifier::key(&repository_key),
+                    "summer",
+                    TagAction::PendAddToRepository,
+                )
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let response = client.get_pending_counts().await.unwrap();
+    assert_eq!(
+        response.pending_counts[&repository_key].pending_tag_mappings,
+        1
+    );
+    client.forget_pending(&repository_key).await.unwrap();
+    let response = client.get_pending_counts().await.unwrap();
+    assert_eq!(response.pending_counts[&repository_key].total(), 0);
+
+    let tags = mock
+        .store()
+        .file(&hash)
+        .unwrap()
+        .tags_with_status(&repository_key, TAG_STATUS_CURRENT);
+    assert!(tags.is_empty());
+}
+
+#[tokio::test]
+async fn it_rejects_committing_to_local_services() {
+    let mock = start_mock();
+    let result = mock.client().commit_pending(MY_TAGS_KEY).await;
+
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn it_returns_rating_service_details() {
+    let mock = start_mock();
+    let hydrus = Hydrus::new(mock.client());
+    let like_service = hydrus
+        .service(ServiceIdentifier::key(FAVOURITES_KEY))
+        .await
+        .unwrap();
+    assert_eq!(like_service.service_type, ServiceType::LikeRatings);
+    assert!(like_service.star_shape.is_some());
+    assert_eq!(like_service.max_stars, None);
+
+    let key = mock
+        .store()
+        .add_service("stars", ServiceType::NumericalRatings);
+    let numerical_service = hydrus.service(ServiceIdentifier::key(key)).await.unwrap();
+    assert_eq!(numerical_service.min_stars, Some(0));
+    assert_eq!(numerical_service.max_stars, Some(5));
+}
+
+#[tokio::test]
+async fn it_returns_virtual_service_details() {
+    let mock = start_mock();
+    let hydrus = Hydrus::new(mock.client());
+    for service_type in [
+        ServiceType::Ipfs,
+        ServiceType::AllDeletedFiles,
+        ServiceType::RepositoryUpdates,
+        ServiceType::AllMyFiles,
+        ServiceType::ServerAdministration,
+    ] {
+        let key = mock
+            .store()
+            .add_service(service_type.to_string(), service_type.clone());
+        let service = hydrus.service(ServiceIdentifier::key(key)).await.unwrap();
+        assert_eq!(service.service_type, service_type);
+    }
+}
+
+#[tokio::test]
+async fn it_rejects_unknown_services() {
+    let mock = start_mock();
+    let result = mock
+        .client()
+        .get_service(ServiceIdentifier::key("unknown"))
+        .await;
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn it_rejects_responses_ignoring_the_range() {
+    let mock = start_mock();
+    let file_id = mock.store().add_file(MockFile::new(TEST_IMAGE.to_vec()));
+    let result = mock
+        .client()
+        .get_file_stream_range(FileIdentifier::ID(file_id), 10..20)
+        .await;
+    result.unwrap();
+
+    mock.store().set_ignore_range_requests(true);
+    let result = mock
+        .client()
+        .get_file_stream_range(FileIdentifier::ID(file_id), 10..20)
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidResponse(_))));
+}
+
+#[tokio::test]
+async fn it_renews_expired_session_keys() {
+    let mock = start_mock();
+    let client = Client::builder()
+        .url(mock.url())
+        .access_key(mock.access_key())
+        .use_session_key(true)
+        .build()
+        .unwrap();
+    client.verify_access_key().await.unwrap();
+    mock.store().expire_sessions();
+
+    client.verify_access_key().await.unwrap();
+}
+
+#[tokio::test]
+async fn it_sends_the_access_key_only_to_request_session_keys() {
+    let mock = start_mock();
+    let client = Client::builder()
+        .url(mock.url())
+        .access_key(mock.access_key())
+        .use_session_key(true)
+        .build()
+        .unwrap();
+    client.verify_access_key().await.unwrap();
+    client.get_services().await.unwrap();
+    mock.store().expire_sessions();
+    client.verify_access_key().await.unwrap();
+
+    let store = mock.store();
+    assert_eq!(store.request_count("session_key"), 2);
+    assert_eq!(store.access_key_request_count("session_key"), 2);
+    assert_eq!(store.request_count("verify_access_key"), 3);
+    assert_eq!(store.access_key_request_count("verify_access_key"), 0);
+    assert_eq!(store.access_key_request_count("get_services"), 0);
+}
+
+#[tokio::test]
+async fn it_rejects_missing_permissions() {
+    let mock = start_mock();
+    mock.store().set_permissions(vec![3]);
+    let result = mock.client().get_pages().await;
+
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+}
+
+#[tokio::test]
+async fn it_checks_permissions_before_sending_requests() {
+    let mock = start_mock();
+    mock.store().set_permissions(vec![3]);
+    let client = Client::builder()
+        .url(mock.url())
+        .access_key(mock.access_key())
+        .check_permissions(true)
+        .build()
+        .unwrap();
+    let result = client.get_pages().await;
+    assert!(matches!(
+        result,
+        Err(Error::MissingPermission(Permission::ManagePages))
+    ));
+
+    client.get_services().await.unwrap();
+    client
+        .search_files(vec!["beach".into()], FileSearchOptions::new())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn it_registers_requested_permissions() {
+    let mock = start_mock();
+    let response = Client::new(mock.url(), String::new())
+        .request_new_permissions("setup", &[Permission::SearchFiles])
+        .await
+        .unwrap();
+    let client = Client::new(mock.url(), response.access_key);
+    let permissions = client.verify_access_key().await.unwrap().permissions();
+    assert_eq!(permissions, vec![Permission::SearchFiles]);
+
+    let result = client.get_pages().await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+}
+
+#[tokio::test]
+async fn it_keeps_the_status_of_other_error_responses() {
+    let mock = start_mock();
+    mock.store().fail_next_requests(1, 409);
+    let error = mock.client().verify_access_key().await.unwrap_err();
+
+    assert!(matches!(error, Error::Api(_)));
+    assert_eq!(error.status(), Some(409));
+    assert!(error.hydrus_error().unwrap().exception_type.is_some());
+}
+
+#[tokio::test]
+async fn it_locks_the_database_while_the_future_runs() {
+    let mock = start_mock();
+    let client = mock.client();
+    let hydrus = Hydrus::new(client.clone());
+    hydrus
+        .database()
+        .with_lock(|| async {
+            assert!(mock.store().is_database_locked());
+            let result = client.get_services().await;
+            assert!(matches!(result, Err(Error::ServiceUnavailable(_))));
+            Ok::<_, Error>(())
+        })
+        .await
+        .unwrap();
+
+    assert!(!mock.store().is_database_locked());
+    client.get_services().await.unwrap();
+}
+
+#[tokio::test]
+async fn it_unlocks_the_database_when_the_future_fails() {
+    let mock = start_mock();
+    let hydrus = Hydrus::new(mock.client());
+    let result = hydrus
+        .database()
+        .with_lock(|| async { Err::<(), _>(Error::BuildError(String::from("snapshot failed"))) })
+        .await;
+
+    assert!(matches!(result, Err(Error::BuildError(_))));
+    assert!(!mock.store().is_database_locked());
+}
+
+#[tokio::test]
+async fn it_unlocks_the_database_when_the_lock_is_dropped() {
+    let mock = start_mock();
+    let hydrus = Hydrus::new(mock.client());
+    let lock = hydrus.database().lock().await.unwrap();
+    assert!(mock.store().is_database_locked());
+    drop(lock);
+
+    for _ in 0..50 {
+        if !mock.store().is_database_locked() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("The database is still locked");
+}
+
+#[tokio::test]
+async fn it_processes_duplicates() {
+    let mock = start_mock();
+    let better = MockFile::new(TEST_IMAGE.to_vec()).add_tags(MY_TAGS_KEY, vec!["summer".into()]);
+    let worse = MockFile::new(TEST_IMAGE.to_vec())
+        .with_hash("0000000000000000000000000000000000000000000000000000000000000001")
+        .add_tags(MY_TAGS_KEY, vec!["beach".into()]);
+    let (better_hash, worse_hash) = (better.hash.clone(), worse.hash.clone());
+    {
+        let mut store = mock.store();
+        store.add_file(better);
+        store.add_file(worse);
+        store.add_potential_duplicates(&better_hash, &worse_hash);
+    }
+    let hydrus = mock.hydrus();
+    hydrus
+        .duplicates()
+        .set_better(&better_hash, &worse_hash)
+        .delete_worse(true)
+        .run()
+        .await
+        .unwrap();
+
+    let mut file = hydrus
+        .file(FileIdentifier::hash(&better_hash))
+        .await
+        .unwrap();
+    let relationships = file.relationships().await.unwrap();
+    assert!(relationships.is_king);
+    assert_eq!(relationships.duplicates, vec![worse_hash.clone()]);
+    assert!(relationships.potential_duplicates.is_empty());
+
+    let store = mock.store();
+    assert!(store.file(&worse_hash).unwrap().is_trashed);
+    assert!(store
+        .file(&better_hash)
+        .unwrap()
+        .tags_with_status(MY_TAGS_KEY, TAG_STATUS_CURRENT)
+        .contains(&"beach".to_string()));
+}
+
+#[tokio::test]
+async fn it_removes_potential_duplicates() {
+    let mock = start_mock();
+    let client = mock.client();
+    mock.store()
+        .add_potential_duplicates(TEST_HASH_1, TEST_HASH_2);
+    client
+        .remove_potentials(FileSelection::by_hash(TEST_HASH_1))
+        .await
+        .unwrap();
+
+    assert!(mock.store().potential_duplicates().is_empty());
+}
+
+#[tokio::test]
+async fn it_stores_ratings() {
+    let mock = start_mock();
+    let stars_key = mock
+        .store()
+        .add_service("stars", ServiceType::NumericalRatings);
+    let test_file = MockFile::new(TEST_IMAGE.to_vec())
+        .with_rating(FAVOURITES_KEY, Rating::LikeDislike(Some(true)));
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let mut file = mock
+        .hydrus()
+        .file(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    file.set_rating(&stars_key, Rating::Numerical(Some(3)))
+        .await
+        .unwrap();
+
+    let ratings = file.ratings().await.unwrap();
+    assert_eq!(ratings.len(), 2);
+    assert_eq!(
+        ratings
+            .values()
+            .find(|r| matches!(r, Rating::LikeDislike(_))),
+        Some(&Rating::LikeDislike(Some(true)))
+    );
+    assert_eq!(
+        mock.store().file(&hash).unwrap().ratings.get(&stars_key),
+        Some(&Rating::Numerical(Some(3)))
+    );
+}
+
+#[tokio::test]
+async fn it_resolves_rating_services_by_their_info() {
+    let mock = start_mock();
+    let stars_key = mock
+        .store()
+        .add_service("stars", ServiceType::NumericalRatings);
+    let test_file = MockFile::new(TEST_IMAGE.to_vec())
+        .with_rating(FAVOURITES_KEY, Rating::LikeDislike(Some(true)));
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let hydrus = mock.hydrus();
+    let services = hydrus.services().await.unwrap();
+    assert!(services.get_services(ServiceType::LikeRatings).is_empty());
+
+    let mut file = hydrus.file(FileIdentifier::hash(&hash)).await.unwrap();
+    let ratings = file.ratings().await.unwrap();
+    assert_eq!(ratings.len(), 2);
+    assert_eq!(
+        ratings.get(&ServiceIdentifier::key(FAVOURITES_KEY)),
+        Some(&Rating::LikeDislike(Some(true)))
+    );
+    assert_eq!(
+        ratings.get(&ServiceIdentifier::key(stars_key)),
+        Some(&Rating::Numerical(None))
+    );
+}
+
+#[tokio::test]
+async fn it_rejects_invalid_ratings() {
+    let mock = start_mock();
+    let test_file = MockFile::new(TEST_IMAGE.to_vec());
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let result = mock
+        .client()
+        .set_rating(
+            FileIdentifier::hash(&hash),
+            FAVOURITES_KEY,
+            Rating::Numerical(Some(3)),
+        )
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn it_merges_notes() {
+    let mock = start_mock();
+    let test_file = MockFile::new(TEST_IMAGE.to_vec())
+        .add_note("source", "pixiv")
+        .add_note("comment", "nice");
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let mut file = mock
+        .hydrus()
+        .file(FileIdentifier::hash(&hash))
+        .await
+        .unwrap();
+    assert_eq!(
+        file.note("source").await.unwrap(),
+        Some(&"pixiv".to_string())
+    );
+
+    file.add_notes()
+        .add_note("source", "pixiv and twitter")
+        .add_note("comment", "very nice")
+        .merge_cleverly(true)
+        .conflict_resolution(NoteConflictResolution::Rename)
+        .run()
+        .await
+        .unwrap();
+
+    let store = mock.store();
+    let notes = &store.file(&hash).unwrap().notes;
+    assert_eq!(notes.get("source"), Some(&"pixiv and twitter".to_string()));
+    assert_eq!(notes.get("comment"), Some(&"nice".to_string()));
+    assert_eq!(notes.get("comment (1)"), Some(&"very nice".to_string()));
+}
+
+#[tokio::test]
+async fn it_returns_metadata_according_to_options() {
+    let mock = start_mock();
+    let test_file =
+        MockFile::new(TEST_IMAGE.to_vec()).with_blurhash("U00000fQfQfQfQfQfQfQfQfQfQfQ");
+    let hash = test_file.hash.clone();
+    mock.store().add_file(test_file);
+    let options = MetadataOptions::new()
+        .include_blurhash(true)
+        .include_milliseconds(true);
+    let response = mock
+        .client()
+        .get_file_metadata_with_options(vec![], vec![hash], options)
+        .await
+        .unwrap();
+    let metadata = &response.metadata[0];
+
+    assert!(metadata.blurhash.is_some());
+    assert!(metadata.time_modified.is_some());
+    assert!(metadata.file_services.is_some());
+    assert!(metadata.notes.is_none());
+}
+
+#[tokio::test]
+async fn it_creates_file_ids_for_unknown_hashes() {
+    let mock = start_mock();
+    let hash = "0000000000000000000000000000000000000000000000000000000000000002".to_string();
+    let client = mock.client();
+    let response = client
+        .get_file_metadata_with_options(vec![], vec![hash.clone()], MetadataOptions::new())
+        .await
+        .unwrap();
+    assert!(response.metadata.is_empty());
+
+    let options = MetadataOptions::new().create_new_file_ids(true);
+    let response = client
+        .get_file_metadata_with_options(vec![], vec![hash.clone()], options)
+        .await
+        .unwrap();
+    assert_eq!(response.metadata.len(), 1);
+    let file_id = mock.store().unknown_file_id(&hash);
+    assert_eq!(response.metadata[0].identifiers.file_id, file_id);
+}
+
+#[tokio::test]
+async fn it_splits_metadata_requests_with_long_urls() {
+    let mock = start_mock();
+    {
+        let mut store = mock.store();
+        for i in 0..3000 {
+            store.add_file(
+                MockFile::new(TEST_IMAGE.to_vec())
+                    .with_hash(format!("{:064x}", i))
+                    .add_tags(MY_TAGS_KEY, vec!["bulk".into()]),
+            );
+        }
+    }
+    let files: Vec<HydrusFile> = mock
+        .hydrus()
+        .search()
+        .add_tag("bulk".into())
+        .metadata_chunk_size(5000)
+        .run_with_metadata::<BasicMetadata>()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(files.len(), 3000);
+    assert!(mock.store().request_count("get_files/file_metadata") > 1);
+}
+
+fn retrying_client(mock: &MockHydrus, policy: RetryPolicy) -> Client {
+    Client::builder()
+        .url(mock.url())
+        .access_key(mock.access_key())
+        .retry_policy(
+            policy
+                .initial_backoff(Duration::from_millis(1))
+                .jitter(false),
+        )
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn it_retries_unavailable_requests() {
+    let mock = start_mock();
+    let client = retrying_client(&mock, RetryPolicy::new().max_attempts(3));
+    mock.store().fail_next_requests(2, 503);
+
+    client.verify_access_key().await.unwrap();
+    assert_eq!(mock.store().request_count("verify_access_key"), 3);
+}
+
+#[tokio::test]
+async fn it_stops_retrying_after_the_maximum_attempts() {
+    let mock = start_mock();
+    let client = retrying_client(&mock, RetryPolicy::new().max_attempts(3));
+    mock.store().fail_next_requests(5, 503);
+
+    let error = client.verify_access_key().await.unwrap_err();
+    assert_eq!(error.status(), Some(503));
+    assert_eq!(mock.store().request_count("verify_access_key"), 3);
+}
+
+#[tokio::test]
+async fn it_does_not_retry_non_idempotent_requests() {
+    let mock = start_mock();
+    let client = retrying_client(&mock, RetryPolicy::new().max_attempts(3));
+    mock.store().fail_next_requests(1, 503);
+
+    let error = client.set_user_agent("Retry").await.unwrap_err();
+    assert_eq!(error.status(), Some(503));
+    assert_eq!(
+        mock.store().request_count("manage_headers/set_user_agent"),
+        1
+    );
+
+    let client = retrying_client(
+        &mock,
+        RetryPolicy::new().max_attempts(3).idempotent_only(false),
+    );
+    mock.store().fail_next_requests(1, 503);
+    client.set_user_agent("Retry").await.unwrap();
+    assert_eq!(
+        mock.store().request_count("manage_headers/set_user_agent"),
+        3
+    );
+}
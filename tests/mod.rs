@@ -1,3 +1,8 @@
+// the hydrus tests assert non-empty responses by comparing their length to zero
+#![allow(clippy::len_zero)]
+
+mod api_core;
 mod client;
 mod common;
+mod mock;
 mod wrapper;
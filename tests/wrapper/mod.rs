@@ -4,6 +4,8 @@ mod test_hydrus;
 mod test_import;
 mod test_or_chain;
 mod test_page;
+mod test_search_expression;
+mod test_search_predicate;
 mod test_service;
 mod test_tags;
 mod test_url;
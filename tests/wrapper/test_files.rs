@@ -1,8 +1,14 @@
 use super::super::common;
 use crate::common::test_data::TEST_HASH_2;
 use crate::common::{create_testdata, get_client};
-use hydrus_api::api_core::common::FileIdentifier;
+use futures_util::TryStreamExt;
+use hydrus_api::api_core::common::{FileIdentifier, ServiceIdentifier};
+use hydrus_api::api_core::endpoints::adding_notes::NoteConflictResolution;
 use hydrus_api::api_core::endpoints::adding_tags::TagAction;
+use hydrus_api::api_core::endpoints::editing_ratings::Rating;
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::{
+    MetadataOptions, RenderFormat,
+};
 use hydrus_api::wrapper::hydrus_file::HydrusFile;
 use hydrus_api::wrapper::service::ServiceName;
 
@@ -88,6 +94,34 @@ async fn it_adds_notes() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn it_has_notes() {
+    let file = get_file().await;
+    file.add_notes()
+        .add_note("Description", "A test image")
+        .run()
+        .await
+        .unwrap();
+
+    let mut file = get_file().await;
+    let note = file.note("Description").await.unwrap();
+    assert_eq!(note, Some(&"A test image".to_string()));
+    assert!(file.notes().await.unwrap().contains_key("Description"));
+}
+
+#[tokio::test]
+async fn it_merges_notes() {
+    let file = get_file().await;
+    file.add_notes()
+        .add_note("Source", "Found somewhere")
+        .merge_cleverly(true)
+        .extend_existing_note_if_possible(true)
+        .conflict_resolution(NoteConflictResolution::Append)
+        .run()
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn it_deletes_notes() {
     let file = get_file().await;
@@ -102,6 +136,73 @@ async fn it_retrieves_content() {
     assert!(file.bytes.len() > 0) // assuming it exists
 }
 
+#[tokio::test]
+async fn it_streams_content() {
+    let file = get_file().await;
+    let stream = file.stream().await.unwrap();
+    let content_length = stream.content_length;
+    let bytes: Vec<u8> = stream
+        .map_ok(|chunk| chunk.to_vec())
+        .try_concat()
+        .await
+        .unwrap();
+
+    assert!(!bytes.is_empty());
+    assert_eq!(content_length, Some(bytes.len() as u64))
+}
+
+#[tokio::test]
+async fn it_streams_a_content_range() {
+    let file = get_file().await;
+    let stream = file.stream_range(10..20).await.unwrap();
+    let total_length = stream.total_length.unwrap();
+    let bytes: Vec<u8> = stream
+        .map_ok(|chunk| chunk.to_vec())
+        .try_concat()
+        .await
+        .unwrap();
+
+    assert_eq!(bytes.len(), 10);
+    assert!(total_length > 20)
+}
+
+#[tokio::test]
+async fn it_retrieves_thumbnails() {
+    let file = get_file().await;
+    let thumbnail = file.thumbnail().await.unwrap();
+
+    assert!(!thumbnail.bytes.is_empty());
+    assert!(thumbnail.mime_type.starts_with("image/"))
+}
+
+#[tokio::test]
+async fn it_renders_content() {
+    let file = get_file().await;
+    let record = file.render().format(RenderFormat::Png).run().await.unwrap();
+
+    assert!(!record.bytes.is_empty());
+    assert_eq!(record.mime_type, "image/png");
+}
+
+#[tokio::test]
+async fn it_returns_local_paths() {
+    let file = get_file().await;
+    let path = file.local_path().await.unwrap();
+    let thumbnail_path = file.thumbnail_path().await.unwrap();
+
+    assert!(path.to_string_lossy().contains(TEST_HASH_2));
+    assert_ne!(path, thumbnail_path);
+}
+
+#[tokio::test]
+async fn it_reads_content() {
+    let file = get_file().await;
+    let record = file.read().await.unwrap();
+
+    assert!(!record.bytes.is_empty());
+    assert!(!record.mime_type.is_empty());
+}
+
 #[tokio::test]
 async fn it_retrieves_metadata() {
     let mut file = get_file().await;
@@ -112,6 +213,18 @@ async fn it_retrieves_metadata() {
     assert!(file.time_imported("000").await.is_ok());
 }
 
+#[tokio::test]
+async fn it_retrieves_metadata_with_options() {
+    let file = get_file().await;
+    let metadata = file
+        .metadata_with_options(MetadataOptions::new().only_basic_information(true))
+        .await
+        .unwrap();
+
+    assert!(metadata.mime.is_some());
+    assert!(metadata.is_inbox.is_none());
+}
+
 #[tokio::test]
 async fn it_deletes() {
     let mut file = get_file().await;
@@ -122,3 +235,44 @@ async fn it_deletes() {
         .unwrap();
     file.undelete(ServiceName::my_files().into()).await.unwrap();
 }
+
+#[tokio::test]
+async fn it_has_relationships() {
+    let mut file = get_file().await;
+    let relationships = file.relationships().await.unwrap();
+
+    assert!(relationships.king.is_some());
+}
+
+#[tokio::test]
+async fn it_has_ratings() {
+    let mut file = get_file().await;
+    let ratings = file.ratings().await.unwrap();
+
+    assert!(!ratings.is_empty());
+}
+
+#[tokio::test]
+async fn it_sets_ratings() {
+    let mut file = get_file().await;
+    let service_key = file
+        .ratings()
+        .await
+        .unwrap()
+        .into_iter()
+        .find_map(|(service, rating)| match (service, rating) {
+            (ServiceIdentifier::Key(key), Rating::LikeDislike(_)) => Some(key),
+            _ => None,
+        })
+        .unwrap();
+    file.set_rating(&service_key, Rating::LikeDislike(Some(false)))
+        .await
+        .unwrap();
+
+    let mut file = get_file().await;
+    let ratings = file.ratings().await.unwrap();
+    assert_eq!(
+        ratings.get(&ServiceIdentifier::key(&service_key)),
+        Some(&Rating::LikeDislike(Some(false)))
+    );
+}
@@ -1,9 +1,15 @@
 use super::super::common;
+use futures_util::TryStreamExt;
+use hydrus_api::api_core::common::{FileIdentifier, ServiceIdentifier};
 use hydrus_api::api_core::endpoints::adding_tags::TagAction;
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::FullMetadata;
+use hydrus_api::error::Error;
 use hydrus_api::wrapper::builders::or_chain_builder::OrChainBuilder;
 use hydrus_api::wrapper::builders::search_builder::SortType;
 use hydrus_api::wrapper::builders::tag_builder::TagBuilder;
+use hydrus_api::wrapper::hydrus_file::HydrusFile;
 use hydrus_api::wrapper::service::{ServiceName, ServiceType};
+use hydrus_api::wrapper::tag::Tag;
 use hydrus_api::wrapper::url::UrlType;
 
 #[tokio::test]
@@ -14,6 +20,22 @@ async fn it_retrieves_version_info() {
     assert!(version.api > 0);
 }
 
+#[tokio::test]
+async fn it_retrieves_permissions() {
+    let hydrus = common::get_hydrus();
+    let permissions = hydrus.permissions().await.unwrap();
+
+    assert!(!permissions.is_empty());
+}
+
+#[tokio::test]
+async fn it_retrieves_database_statistics() {
+    let hydrus = common::get_hydrus();
+    let database = hydrus.database();
+    database.statistics().await.unwrap();
+    database.client_options().await.unwrap();
+}
+
 #[tokio::test]
 async fn it_retrieves_services() {
     let hydrus = common::get_hydrus();
@@ -24,6 +46,23 @@ async fn it_retrieves_services() {
     assert!(services.get_services(ServiceType::AllKnownTags).len() > 0);
 }
 
+#[tokio::test]
+async fn it_retrieves_a_single_service() {
+    let hydrus = common::get_hydrus();
+    let service = hydrus
+        .service(ServiceName::all_known_tags().into())
+        .await
+        .unwrap();
+    assert_eq!(service.service_type, ServiceType::AllKnownTags);
+    assert!(service.type_pretty.is_some());
+
+    let service_by_key = hydrus
+        .service(ServiceIdentifier::key(&service.key))
+        .await
+        .unwrap();
+    assert_eq!(service_by_key.key, service.key);
+}
+
 #[tokio::test]
 async fn it_retrieves_url_information() {
     let hydrus = common::get_hydrus();
@@ -54,6 +93,81 @@ async fn it_searches() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn it_searches_sorted_by_hash() {
+    let hydrus = common::get_hydrus();
+    let files = hydrus
+        .search()
+        .add_tag("character:megumin".into())
+        .sort_by(SortType::Hash)
+        .sort_ascending()
+        .run()
+        .await
+        .unwrap();
+    let mut hashes = Vec::new();
+    for mut file in files {
+        hashes.push(file.hash().await.unwrap());
+    }
+    let mut sorted_hashes = hashes.clone();
+    sorted_hashes.sort();
+
+    assert_eq!(hashes, sorted_hashes);
+}
+
+#[tokio::test]
+async fn it_rejects_sort_directions_for_random_sorting() {
+    let hydrus = common::get_hydrus();
+    let result = hydrus
+        .search()
+        .add_tag("character:megumin".into())
+        .sort_descending()
+        .sort_by(SortType::Random)
+        .run()
+        .await;
+
+    assert!(matches!(result, Err(Error::BuildError(_))));
+}
+
+#[tokio::test]
+async fn it_searches_files_with_ids_and_hashes() {
+    let hydrus = common::get_hydrus();
+    let files = hydrus
+        .search()
+        .add_tag("character:megumin".into())
+        .run()
+        .await
+        .unwrap();
+
+    for mut file in files {
+        assert!(matches!(file.id, FileIdentifier::ID(_)));
+        assert!(!file.hash().await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn it_searches_with_metadata() {
+    let hydrus = common::get_hydrus();
+    let files: Vec<HydrusFile> = hydrus
+        .search()
+        .add_tag("character:megumin".into())
+        .metadata_chunk_size(1)
+        .metadata_concurrency(2)
+        .run_with_metadata::<FullMetadata>()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    for mut file in files {
+        assert!(file
+            .tags()
+            .await
+            .unwrap()
+            .contains(&Tag::from("character:megumin")));
+    }
+}
+
 #[tokio::test]
 async fn it_adds_tags() {
     let hydrus = common::get_hydrus();
@@ -78,3 +192,42 @@ async fn it_sets_the_user_agent() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn it_suggests_tags() {
+    let hydrus = common::get_hydrus();
+    let suggestions = hydrus
+        .tag_suggestions()
+        .search("character:*")
+        .service(ServiceName::my_tags().into())
+        .run()
+        .await
+        .unwrap();
+
+    assert!(!suggestions.is_empty());
+    assert!(suggestions
+        .iter()
+        .all(|s| s.tag.namespace == Some(String::from("character"))));
+}
+
+#[tokio::test]
+async fn it_resolves_ideal_tags() {
+    let hydrus = common::get_hydrus();
+    let services = hydrus.services().await.unwrap();
+    let service = services.get_services(ServiceType::LocalTags).pop().unwrap();
+    let tags = hydrus
+        .ideal_tags(
+            vec![Tag::from("-lotr"), Tag::from("character:frodo")],
+            service,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        tags,
+        vec![
+            Tag::from("-series:lord of the rings"),
+            Tag::from("character:frodo")
+        ]
+    );
+}
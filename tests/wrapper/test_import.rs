@@ -1,9 +1,12 @@
 use super::super::common;
+use bytes::Bytes;
+use hydrus_api::error::Error;
 use hydrus_api::wrapper::builders::import_builder::FileImport;
 use hydrus_api::wrapper::page::PageIdentifier;
 use hydrus_api::wrapper::service::ServiceName;
 use hydrus_api::wrapper::tag::Tag;
 use hydrus_api::wrapper::url::UrlType;
+use std::io;
 
 #[tokio::test]
 async fn it_imports_file_paths() {
@@ -23,13 +26,45 @@ async fn it_imports_binary_files() {
     let bytes = [0u8, 0u8, 0u8, 0u8];
     let result = hydrus
         .import()
-        .file(FileImport::binary(&mut &bytes[..]))
+        .file(FileImport::binary(&mut &bytes[..]).unwrap())
         .run()
         .await;
 
     assert!(result.is_err()) // return status should be 4
 }
 
+#[tokio::test]
+async fn it_imports_file_streams() {
+    let hydrus = common::get_hydrus();
+    let bytes: &'static [u8] = &[0u8, 0u8, 0u8, 0u8];
+    let result = hydrus
+        .import()
+        .file(FileImport::reader(bytes, Some(bytes.len() as u64)))
+        .run()
+        .await;
+
+    assert!(result.is_err()) // return status should be 4
+}
+
+#[tokio::test]
+async fn it_fails_on_broken_file_streams() {
+    let hydrus = common::get_hydrus();
+    let stream = futures_util::stream::iter(vec![
+        Ok(Bytes::from_static(&[0u8, 0u8])),
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection lost",
+        )),
+    ]);
+    let result = hydrus
+        .import()
+        .file(FileImport::stream(stream, None))
+        .run()
+        .await;
+
+    assert!(matches!(result, Err(Error::Io(_))))
+}
+
 #[tokio::test]
 async fn it_imports_urls() {
     let hydrus = common::get_hydrus();
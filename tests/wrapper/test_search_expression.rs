@@ -0,0 +1,127 @@
+use super::super::common;
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::SearchQueryEntry;
+use hydrus_api::error::Error;
+use hydrus_api::wrapper::builders::tag_builder::{Comparator, SystemTagBuilder};
+use hydrus_api::wrapper::search_expression::SearchExpression;
+use hydrus_api::wrapper::tag::Tag;
+
+fn tag_entry(tag: &str) -> SearchQueryEntry {
+    SearchQueryEntry::Tag(tag.to_string())
+}
+
+fn or_entry(tags: &[&str]) -> SearchQueryEntry {
+    SearchQueryEntry::OrChain(tags.iter().map(|t| t.to_string()).collect())
+}
+
+fn entries(expression: SearchExpression) -> Vec<SearchQueryEntry> {
+    expression.into_query_entries().unwrap()
+}
+
+#[test]
+fn it_normalizes_expressions() {
+    let expression = Tag::from("beach") & (Tag::from("summer") | Tag::from("winter"));
+    assert_eq!(
+        entries(expression),
+        vec![tag_entry("beach"), or_entry(&["summer", "winter"])]
+    );
+
+    let expression = Tag::from("beach") | (Tag::from("summer") & Tag::from("winter"));
+    assert_eq!(
+        entries(expression),
+        vec![
+            or_entry(&["beach", "summer"]),
+            or_entry(&["beach", "winter"])
+        ]
+    );
+}
+
+#[test]
+fn it_pushes_negations_to_tags() {
+    let expression = !(Tag::from("character:megumin") | Tag::from("-character:aqua"));
+    assert_eq!(
+        entries(expression),
+        vec![tag_entry("-character:megumin"), tag_entry("character:aqua")]
+    );
+
+    let expression = !(Tag::from("summer") & SystemTagBuilder::new().inbox().build());
+    assert_eq!(
+        entries(expression),
+        vec![or_entry(&["-summer", "system:archive"])]
+    );
+}
+
+#[test]
+fn it_drops_tautologies() {
+    let expression = Tag::from("beach") & (Tag::from("summer") | !Tag::from("summer"));
+    assert_eq!(entries(expression), vec![tag_entry("beach")]);
+}
+
+#[test]
+fn it_matches_everything_without_clauses() {
+    let expression = Tag::from("summer") | !Tag::from("summer");
+    assert_eq!(entries(expression), vec![tag_entry("system:everything")]);
+
+    let expression = SearchExpression::And(vec![]);
+    assert_eq!(entries(expression), vec![tag_entry("system:everything")]);
+}
+
+#[test]
+fn it_rejects_negated_system_predicates_without_inverse() {
+    let expression = !(SystemTagBuilder::new().limit(5).build()
+        | SystemTagBuilder::new()
+            .width(Comparator::Equal, 500)
+            .build());
+    assert!(matches!(
+        expression.into_query_entries(),
+        Err(Error::BuildError(_))
+    ));
+}
+
+#[test]
+fn it_inverts_comparison_predicates() {
+    let expression = !(SystemTagBuilder::new()
+        .width(Comparator::Greater, 500)
+        .build()
+        | SystemTagBuilder::new()
+            .number_of_tags(Comparator::Less, 3)
+            .build());
+    assert_eq!(
+        entries(expression),
+        vec![
+            tag_entry("system:width < 501"),
+            tag_entry("system:number of tags > 2")
+        ]
+    );
+
+    let expression =
+        !SearchExpression::from(SystemTagBuilder::new().height(Comparator::Less, 0).build());
+    assert!(matches!(
+        expression.into_query_entries(),
+        Err(Error::BuildError(_))
+    ));
+}
+
+#[test]
+fn it_limits_the_number_of_clauses() {
+    let expression = (0..11)
+        .map(|i| Tag::from(format!("a{}", i)) & Tag::from(format!("b{}", i)))
+        .fold(SearchExpression::from(Tag::from("start")), |acc, e| acc | e);
+    assert!(matches!(
+        expression.into_query_entries(),
+        Err(Error::BuildError(_))
+    ));
+}
+
+#[tokio::test]
+async fn it_searches_with_expressions() {
+    let hydrus = common::get_hydrus();
+    let expression = SystemTagBuilder::new().everything().build()
+        & !(Tag::from("character:megumin") & Tag::from("series:konosuba"));
+    hydrus
+        .search()
+        .add_expression(expression)
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+}
@@ -0,0 +1,233 @@
+use chrono::{Duration, NaiveDate};
+use hydrus_api::api_core::endpoints::searching_and_fetching_files::SearchQueryEntry;
+use hydrus_api::wrapper::builders::tag_builder::{
+    Comparator, CurrentlyOrPending, DurationUnit, FileRelationshipType, FileSizeUnit, IsComparator,
+    PixelUnit, RatingCondition, SystemTagBuilder, TagBuilder, ViewType, WiderTallerEqual,
+};
+use hydrus_api::wrapper::search_predicate::{parse_query, SearchPredicate, SystemPredicate};
+use hydrus_api::wrapper::service::ServiceName;
+
+const TEST_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+fn assert_round_trip(builder: SystemTagBuilder) {
+    let tag = builder.build();
+    let query = tag.to_string();
+    let predicate: SearchPredicate = query.parse().unwrap();
+
+    assert_eq!(predicate.to_string(), query);
+    assert_eq!(predicate.into_tag(), Some(tag));
+}
+
+#[test]
+fn it_round_trips_system_tags() {
+    assert_round_trip(SystemTagBuilder::new().everything());
+    assert_round_trip(SystemTagBuilder::new().inbox().negate());
+    assert_round_trip(SystemTagBuilder::new().archive());
+    assert_round_trip(SystemTagBuilder::new().has_duration());
+    assert_round_trip(SystemTagBuilder::new().no_duration());
+    assert_round_trip(SystemTagBuilder::new().best_duplicate_quality());
+    assert_round_trip(SystemTagBuilder::new().not_best_duplicate_quality());
+    assert_round_trip(SystemTagBuilder::new().has_audio());
+    assert_round_trip(SystemTagBuilder::new().no_audio());
+    assert_round_trip(SystemTagBuilder::new().has_tags());
+    assert_round_trip(SystemTagBuilder::new().no_tags());
+    assert_round_trip(SystemTagBuilder::new().untagged());
+    assert_round_trip(SystemTagBuilder::new().duration(
+        Comparator::Less,
+        20,
+        DurationUnit::Seconds,
+    ));
+    assert_round_trip(SystemTagBuilder::new().number_of_tags(Comparator::Greater, 12));
+    assert_round_trip(SystemTagBuilder::new().height(Comparator::Approximate, 200));
+    assert_round_trip(SystemTagBuilder::new().width(Comparator::Equal, 200));
+    assert_round_trip(SystemTagBuilder::new().filesize(
+        Comparator::Less,
+        200,
+        FileSizeUnit::Megabytes,
+    ));
+    assert_round_trip(
+        SystemTagBuilder::new().similar_to(vec![TEST_HASH.to_string(), TEST_HASH.to_string()], 4),
+    );
+    assert_round_trip(SystemTagBuilder::new().limit(50));
+    assert_round_trip(SystemTagBuilder::new().filetype(vec![mime::IMAGE_PNG, mime::IMAGE_JPEG]));
+    assert_round_trip(SystemTagBuilder::new().hash(vec![TEST_HASH.to_string()]));
+    assert_round_trip(SystemTagBuilder::new().date_modified(
+        Comparator::Greater,
+        NaiveDate::from_ymd_opt(2022, 1, 5).unwrap(),
+    ));
+    assert_round_trip(SystemTagBuilder::new().time_imported(
+        Comparator::Less,
+        NaiveDate::from_ymd_opt(2021, 12, 24).unwrap(),
+    ));
+    assert_round_trip(SystemTagBuilder::new().file_service(
+        IsComparator::IsNot,
+        CurrentlyOrPending::PendingTo,
+        ServiceName::my_files(),
+    ));
+    assert_round_trip(SystemTagBuilder::new().number_of_relationships(
+        Comparator::Approximate,
+        3,
+        FileRelationshipType::PotentialDuplicates,
+    ));
+    assert_round_trip(SystemTagBuilder::new().ratio(WiderTallerEqual::Taller, (16, 9)));
+    assert_round_trip(SystemTagBuilder::new().number_of_pixels(
+        Comparator::Less,
+        50,
+        PixelUnit::Megapixels,
+    ));
+    assert_round_trip(SystemTagBuilder::new().views(ViewType::Preview, Comparator::Equal, 1000));
+    assert_round_trip(SystemTagBuilder::new().viewtime(
+        ViewType::All,
+        Comparator::Less,
+        Duration::days(1) + Duration::minutes(10) + Duration::seconds(5),
+    ));
+    assert_round_trip(SystemTagBuilder::new().has_url_matching_regex(".*pixiv.net.*"));
+    assert_round_trip(SystemTagBuilder::new().does_not_have_url_matching_regex(".*pixiv.net.*"));
+    assert_round_trip(SystemTagBuilder::new().has_url_with_class("pixiv file page"));
+    assert_round_trip(SystemTagBuilder::new().does_not_have_url_with_class("pixiv file page"));
+    assert_round_trip(SystemTagBuilder::new().tag_namespace_as_number(
+        "page",
+        Comparator::Approximate,
+        5,
+    ));
+}
+
+#[test]
+fn it_round_trips_extended_system_tags() {
+    let favourites = || ServiceName(String::from("my favourites"));
+    let date = NaiveDate::from_ymd_opt(2022, 3, 1).unwrap();
+    assert_round_trip(SystemTagBuilder::new().tag_namespace_as_number_between("page", 5, 10));
+    assert_round_trip(SystemTagBuilder::new().number_of_notes(Comparator::Greater, 1));
+    assert_round_trip(SystemTagBuilder::new().has_note_with_name("source url"));
+    assert_round_trip(SystemTagBuilder::new().no_note_with_name("source url"));
+    assert_round_trip(SystemTagBuilder::new().has_rating(favourites()));
+    assert_round_trip(SystemTagBuilder::new().no_rating(favourites()));
+    assert_round_trip(SystemTagBuilder::new().rating(
+        favourites(),
+        Comparator::Equal,
+        RatingCondition::Dislike,
+    ));
+    assert_round_trip(SystemTagBuilder::new().rating(
+        favourites(),
+        Comparator::Greater,
+        RatingCondition::Stars(3, 5),
+    ));
+    assert_round_trip(SystemTagBuilder::new().rating(
+        favourites(),
+        Comparator::Less,
+        RatingCondition::Count(10),
+    ));
+    assert_round_trip(SystemTagBuilder::new().last_viewed_time(Comparator::Less, date));
+    assert_round_trip(SystemTagBuilder::new().archived_time(Comparator::Greater, date));
+    assert_round_trip(SystemTagBuilder::new().has_exif());
+    assert_round_trip(SystemTagBuilder::new().no_exif());
+    assert_round_trip(SystemTagBuilder::new().has_icc_profile());
+    assert_round_trip(SystemTagBuilder::new().no_icc_profile());
+    assert_round_trip(SystemTagBuilder::new().has_embedded_metadata());
+    assert_round_trip(SystemTagBuilder::new().no_embedded_metadata());
+    assert_round_trip(SystemTagBuilder::new().framerate(Comparator::Approximate, 60));
+    assert_round_trip(SystemTagBuilder::new().number_of_frames(Comparator::Greater, 25));
+    assert_round_trip(SystemTagBuilder::new().number_of_words(Comparator::Less, 500));
+    assert_round_trip(SystemTagBuilder::new().has_url("https://example.com/post/1"));
+    assert_round_trip(SystemTagBuilder::new().does_not_have_url("https://example.com/post/1"));
+}
+
+#[test]
+fn it_parses_queries() {
+    let query = "character:megumin\n-system:inbox\nsummer OR -winter OR system:limit = 5\n";
+    let predicates = parse_query(query).unwrap();
+
+    assert_eq!(
+        predicates,
+        vec![
+            SearchPredicate::Tag(TagBuilder::new("megumin").namespace("character").build()),
+            SearchPredicate::System {
+                negated: true,
+                predicate: SystemPredicate::Inbox,
+            },
+            SearchPredicate::Or(vec![
+                SearchPredicate::Tag("summer".into()),
+                SearchPredicate::Tag(TagBuilder::new("winter").negate().build()),
+                SearchPredicate::System {
+                    negated: false,
+                    predicate: SystemPredicate::Limit(5),
+                },
+            ]),
+        ]
+    );
+    let entries: Vec<SearchQueryEntry> = predicates
+        .into_iter()
+        .map(SearchPredicate::into_query_entry)
+        .collect();
+    assert!(matches!(&entries[2], SearchQueryEntry::OrChain(tags) if tags.len() == 3));
+}
+
+#[test]
+fn it_reports_the_span_of_errors() {
+    let query = "summer\nsystem:width > many";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "many");
+
+    let query = "system:filesize < 20 TB";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "TB");
+
+    let query = "beach OR system:unknown predicate";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "unknown predicate");
+
+    let query = "system:hash = 00zz";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "00zz");
+
+    let query = "system:inbox please";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "please");
+
+    let query = "series:";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "series:");
+
+    let query = "a OR    ";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(error.span, 5..8);
+    assert_eq!(&query[error.span], "   ");
+
+    let query = "a OR   OR b";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(error.span, 5..6);
+    assert_eq!(&query[error.span], " ");
+}
+
+#[test]
+fn it_rejects_invalid_rating_comparators() {
+    let query = "system:rating for favourites > like";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], ">");
+
+    let query = "system:rating for favourites is 3/5";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "is");
+}
+
+#[test]
+fn it_rejects_negative_durations() {
+    let query = "system:media viewtime > -5 seconds";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "-5");
+}
+
+#[test]
+fn it_rejects_durations_out_of_range() {
+    let query = "system:media viewtime > 99999999999999 days";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "99999999999999");
+
+    let query = "system:all viewtime < 9223372036854775807 seconds";
+    let error = parse_query(query).unwrap_err();
+    assert_eq!(&query[error.span], "9223372036854775807");
+
+    let query = "system:media viewtime > 106751991167 days 23 hours 59 minutes 59 seconds";
+    let error = parse_query(query).unwrap_err();
+    assert!(error.span.start > "system:media viewtime > ".len());
+}
@@ -1,15 +1,20 @@
 use super::super::common;
-use chrono::{Duration, Local};
+use chrono::{Duration, Local, NaiveDate};
 use hydrus_api::error::Result;
 use hydrus_api::wrapper::builders::tag_builder::{
     Comparator, CurrentlyOrPending, FileRelationshipType, FileSizeUnit, IsComparator, PixelUnit,
-    SystemTagBuilder, ViewType, WiderTallerEqual,
+    RatingCondition, SystemTagBuilder, ViewType, WiderTallerEqual,
 };
 use hydrus_api::wrapper::service::ServiceName;
 use hydrus_api::wrapper::tag::Tag;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2022, 3, 1).unwrap()
+}
+
+#[allow(unused_must_use)]
 async fn retrieve_single_tag(tag: Tag) -> Result<()> {
     lazy_static::lazy_static! { static ref SEM: Arc<Mutex<()>> = Arc::new(Mutex::new(())); }
     SEM.lock().await;
@@ -415,3 +420,162 @@ async fn it_returns_files_with_namespace_properties() {
         .build();
     retrieve_single_tag(tag).await.unwrap();
 }
+
+#[tokio::test]
+async fn it_returns_files_with_a_range_of_namespace_numbers() {
+    let tag = SystemTagBuilder::new()
+        .tag_namespace_as_number_between("page", 5, 10)
+        .build();
+    assert_eq!(
+        tag.to_string(),
+        "system:tag as number page between 5 and 10"
+    );
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_number_of_notes() {
+    let tag = SystemTagBuilder::new()
+        .number_of_notes(Comparator::Greater, 0)
+        .build();
+    assert_eq!(tag.to_string(), "system:num notes > 0");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_named_note() {
+    let tag = SystemTagBuilder::new().has_note_with_name("source").build();
+    assert_eq!(tag.to_string(), "system:has note with name source");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_without_a_named_note() {
+    let tag = SystemTagBuilder::new().no_note_with_name("source").build();
+    assert_eq!(tag.to_string(), "system:no note with name source");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_rated_files() {
+    let tag = SystemTagBuilder::new()
+        .has_rating(ServiceName(String::from("favourites")))
+        .build();
+    assert_eq!(tag.to_string(), "system:has a rating for favourites");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_unrated_files() {
+    let tag = SystemTagBuilder::new()
+        .no_rating(ServiceName(String::from("favourites")))
+        .build();
+    assert_eq!(
+        tag.to_string(),
+        "system:does not have a rating for favourites"
+    );
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_rating() {
+    let tag = SystemTagBuilder::new()
+        .rating(
+            ServiceName(String::from("favourites")),
+            Comparator::Equal,
+            RatingCondition::Like,
+        )
+        .build();
+    assert_eq!(tag.to_string(), "system:rating for favourites is like");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_last_viewed_time() {
+    let tag = SystemTagBuilder::new()
+        .last_viewed_time(Comparator::Less, date())
+        .build();
+    assert_eq!(tag.to_string(), "system:last viewed time < 2022-03-01");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_archived_time() {
+    let tag = SystemTagBuilder::new()
+        .archived_time(Comparator::Greater, date())
+        .build();
+    assert_eq!(tag.to_string(), "system:archived time > 2022-03-01");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_exif() {
+    let tag = SystemTagBuilder::new().has_exif().build();
+    assert_eq!(tag.to_string(), "system:has exif");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_without_icc_profile() {
+    let tag = SystemTagBuilder::new().no_icc_profile().build();
+    assert_eq!(tag.to_string(), "system:no icc profile");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_embedded_metadata() {
+    let tag = SystemTagBuilder::new().has_embedded_metadata().build();
+    assert_eq!(
+        tag.to_string(),
+        "system:has human-readable embedded metadata"
+    );
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_framerate() {
+    let tag = SystemTagBuilder::new()
+        .framerate(Comparator::Approximate, 60)
+        .build();
+    assert_eq!(tag.to_string(), "system:framerate ~= 60fps");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_number_of_frames() {
+    let tag = SystemTagBuilder::new()
+        .number_of_frames(Comparator::Greater, 25)
+        .build();
+    assert_eq!(tag.to_string(), "system:number of frames > 25");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_a_specific_number_of_words() {
+    let tag = SystemTagBuilder::new()
+        .number_of_words(Comparator::Less, 500)
+        .build();
+    assert_eq!(tag.to_string(), "system:number of words < 500");
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_with_an_url() {
+    let tag = SystemTagBuilder::new()
+        .has_url("https://www.pixiv.net/member_illust.php?illust_id=83406361&mode=medium")
+        .build();
+    assert_eq!(
+        tag.to_string(),
+        "system:has url https://www.pixiv.net/member_illust.php?illust_id=83406361&mode=medium"
+    );
+    retrieve_single_tag(tag).await.unwrap();
+}
+
+#[tokio::test]
+async fn it_returns_files_without_an_url() {
+    let tag = SystemTagBuilder::new()
+        .does_not_have_url("https://www.pixiv.net/member_illust.php?illust_id=83406361&mode=medium")
+        .build();
+    assert_eq!(tag.to_string(), "system:does not have url https://www.pixiv.net/member_illust.php?illust_id=83406361&mode=medium");
+    retrieve_single_tag(tag).await.unwrap();
+}